@@ -67,7 +67,7 @@ where
 
         // Calculate backstrides
         for i in (0..ndim).rev() {
-            backstrides[i] = -1 * strides[i] * (shape[i] - 1);
+            backstrides[i] = -strides[i] * (shape[i] - 1);
         }
 
         // Create indices
@@ -99,10 +99,8 @@ where
         let mut indices = vec![vec![0; ndim]; count];
         let mut current_index = vec![0; ndim];
 
-        for i in 0..count {
-            for j in 0..ndim {
-                indices[i][j] = current_index[j];
-            }
+        for index in indices.iter_mut() {
+            index.copy_from_slice(&current_index);
             for j in (0..ndim).rev() {
                 current_index[j] += 1;
                 if current_index[j] < shape[j] {
@@ -119,16 +117,27 @@ where
     fn create_linear_indices(shape: &[i32], strides: &[i32], itemsize: usize, totalsize: usize) -> LinearIndices {
         let mut indices = vec![0; totalsize];
         let idxs = Array::<T>::create_array_indices(shape, shape.len());
-        for i in 0..totalsize {
+        for (lidx, nd_index) in indices.iter_mut().zip(&idxs.indices) {
             let mut idx = 0;
-            for j in 0..shape.len() {
-                idx += idxs.indices[i][j] * strides[j];
+            for (&i, &stride) in nd_index.iter().zip(strides) {
+                idx += i * stride;
             }
-            indices[i] = (idx / itemsize as i32) as usize;
+            *lidx = (idx / itemsize as i32) as usize;
         }
         LinearIndices { indices, count: totalsize }
     }
 
+    // Wrap an existing C-order buffer with the given shape, e.g. to return
+    // results from the closures passed to nr_solve_ivp or nr_minimize
+    pub fn from_vec(data: Vec<T>, shape: &[i32]) -> Array<T> {
+        let mut arr = Array::nr_create(shape, shape.len());
+        if data.len() != arr.totalsize {
+            panic!("Data length {} does not match shape {:?}", data.len(), shape);
+        }
+        arr.data = data;
+        arr
+    }
+
     // Print array information
     pub fn nr_print_info(&self) {
         println!("Shape: {:?}", self.shape);
@@ -144,8 +153,8 @@ impl<T: Display + Copy> Array<T> {
         fn print_recursive<T: std::fmt::Display>(data_slice: &[T], shape: &[i32]) {
             // Base case: If the shape is 1D, print all elements separated by spaces.
             if shape.len() == 1 {
-                for i in 0..shape[0] as usize {
-                    print!("{} ", data_slice[i]);
+                for val in data_slice.iter().take(shape[0] as usize) {
+                    print!("{} ", val);
                 }
                 println!();
                 return;
@@ -0,0 +1,85 @@
+// Element-wise helpers for arrays of num_complex::Complex values
+// (np.real, np.imag, np.conj, np.angle, np.abs) and the Hermitian transpose.
+use crate::array::Array;
+use num_complex::Complex;
+use num_traits::{Float, Num, Zero};
+
+// Element types with a complex conjugate, the identity for real numbers;
+// lets generic code such as nr_vdot conjugate like NumPy does
+pub trait Conjugate: Copy {
+    fn conjugate(self) -> Self;
+}
+
+macro_rules! real_conjugate {
+    ($($t:ty),*) => {
+        $(impl Conjugate for $t {
+            fn conjugate(self) -> Self {
+                self
+            }
+        })*
+    };
+}
+
+real_conjugate!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl<T: Copy + Num + std::ops::Neg<Output = T>> Conjugate for Complex<T> {
+    fn conjugate(self) -> Self {
+        Complex::new(self.re, -self.im)
+    }
+}
+
+// Complex array from real and imaginary parts of the same shape
+pub fn nr_complex<T: Float>(re: &Array<T>, im: &Array<T>) -> Array<Complex<T>> {
+    if re.shape != im.shape {
+        panic!("complex: real part {:?} and imaginary part {:?} must have the same shape", re.shape, im.shape);
+    }
+    let data = re.data.iter().zip(&im.data).map(|(&r, &i)| Complex::new(r, i)).collect();
+    Array::from_vec(data, &re.shape)
+}
+
+fn map<T: Float, U: Copy + Zero>(a: &Array<Complex<T>>, f: impl Fn(&Complex<T>) -> U) -> Array<U> {
+    Array::from_vec(a.data.iter().map(f).collect(), &a.shape)
+}
+
+// Real part of each element
+pub fn nr_real<T: Float>(a: &Array<Complex<T>>) -> Array<T> {
+    map(a, |z| z.re)
+}
+
+// Imaginary part of each element
+pub fn nr_imag<T: Float>(a: &Array<Complex<T>>) -> Array<T> {
+    map(a, |z| z.im)
+}
+
+// Complex conjugate of each element
+pub fn nr_conj<T: Float>(a: &Array<Complex<T>>) -> Array<Complex<T>> {
+    map(a, |z| z.conj())
+}
+
+// Argument of each element in radians, in (-pi, pi]
+pub fn nr_angle<T: Float>(a: &Array<Complex<T>>) -> Array<T> {
+    map(a, |z| z.arg())
+}
+
+// Modulus of each element, computed with hypot to avoid overflow
+pub fn nr_abs<T: Float>(a: &Array<Complex<T>>) -> Array<T> {
+    map(a, |z| z.norm())
+}
+
+// Conjugate transpose of the last two axes, applied to each matrix of a
+// stack (the .mH of NumPy). A 1-D array is only conjugated.
+pub fn nr_conj_transpose<T: Float>(a: &Array<Complex<T>>) -> Array<Complex<T>> {
+    if a.ndim == 1 {
+        return nr_conj(a);
+    }
+    let (m, n) = (a.shape[a.ndim - 2] as usize, a.shape[a.ndim - 1] as usize);
+    let mut data = Vec::with_capacity(a.totalsize);
+    for mat in a.data.chunks(m * n) {
+        for j in 0..n {
+            data.extend((0..m).map(|i| mat[i * n + j].conj()));
+        }
+    }
+    let mut shape = a.shape.clone();
+    shape.swap(a.ndim - 2, a.ndim - 1);
+    Array::from_vec(data, &shape)
+}
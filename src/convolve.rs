@@ -0,0 +1,202 @@
+// Discrete linear convolution and cross-correlation (np.convolve,
+// np.correlate, scipy.signal.convolve2d / correlate2d).
+//
+// 1-D products switch to an FFT of the zero-padded inputs once both inputs
+// are long enough for O(n log n) to beat the direct O(n m) sum. 2-D products
+// are summed directly, with the first input extended past its edges
+// according to a Boundary rule.
+use crate::array::Array;
+use crate::fft::{nr_irfft, nr_rfft, FftNorm};
+use num_traits::Float;
+use rayon::prelude::*;
+
+// Shorter 1-D input length from which the FFT path is used
+const FFT_MIN_LEN: usize = 64;
+
+// Output size, as the `mode` argument of np.convolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvolveMode {
+    // Every point of overlap, n + m - 1 values
+    Full,
+    // Centered part of the full output with the size of the larger input
+    // (the first input for the 2-D functions)
+    Same,
+    // Only points where the inputs overlap completely
+    Valid,
+}
+
+// How the 2-D functions extend the first input past its edges
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary<T> {
+    // Pad with a constant value
+    Fill(T),
+    // Periodic extension
+    Wrap,
+    // Mirror with the edge value repeated: ... b a | a b c | c b ...
+    Symmetric,
+}
+
+fn vector<'a, T>(a: &'a Array<T>, name: &str) -> &'a [T] {
+    if a.ndim != 1 {
+        panic!("{}: expected 1-D arrays, got shape {:?}", name, a.shape);
+    }
+    &a.data
+}
+
+// Full linear convolution of two non-empty sequences
+fn full_convolve<T: Float + Send + Sync>(a: &[T], v: &[T]) -> Vec<T> {
+    let n = a.len() + v.len() - 1;
+    if a.len().min(v.len()) < FFT_MIN_LEN {
+        let mut out = vec![T::zero(); n];
+        for (i, &x) in a.iter().enumerate() {
+            for (o, &y) in out[i..].iter_mut().zip(v) {
+                *o = *o + x * y;
+            }
+        }
+        return out;
+    }
+
+    // Pad to a power of two so the transforms stay on the radix-2 path
+    let size = n.next_power_of_two();
+    let fa = nr_rfft(&Array::from_vec(a.to_vec(), &[a.len() as i32]), Some(size), None, FftNorm::Backward);
+    let fv = nr_rfft(&Array::from_vec(v.to_vec(), &[v.len() as i32]), Some(size), None, FftNorm::Backward);
+    let prod: Vec<_> = fa.data.iter().zip(&fv.data).map(|(&x, &y)| x * y).collect();
+    let spec = Array::from_vec(prod, &fa.shape);
+    let mut out = nr_irfft(&spec, Some(size), None, FftNorm::Backward).data;
+    out.truncate(n);
+    out
+}
+
+// Window of a full 1-D result for `mode`, where `short` and `long` are the
+// input lengths
+fn crop<T: Copy>(full: &[T], short: usize, long: usize, mode: ConvolveMode) -> Vec<T> {
+    match mode {
+        ConvolveMode::Full => full.to_vec(),
+        ConvolveMode::Same => full[(short - 1) / 2..(short - 1) / 2 + long].to_vec(),
+        ConvolveMode::Valid => full[short - 1..long].to_vec(),
+    }
+}
+
+// Discrete linear convolution of two 1-D arrays (np.convolve)
+pub fn nr_convolve<T>(a: &Array<T>, v: &Array<T>, mode: ConvolveMode) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    let (a, v) = (vector(a, "convolve"), vector(v, "convolve"));
+    let (short, long) = (a.len().min(v.len()), a.len().max(v.len()));
+    let out = crop(&full_convolve(a, v), short, long, mode);
+    let len = out.len() as i32;
+    Array::from_vec(out, &[len])
+}
+
+// Cross-correlation of two 1-D arrays, c[k] = sum_n a[n + k] v[n]
+// (np.correlate)
+pub fn nr_correlate<T>(a: &Array<T>, v: &Array<T>, mode: ConvolveMode) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    let (a, v) = (vector(a, "correlate"), vector(v, "correlate"));
+    // Like NumPy, correlate the longer input against the shorter one and
+    // reverse the result when the arguments come in the other order
+    let swapped = v.len() > a.len();
+    let (a, v) = if swapped { (v, a) } else { (a, v) };
+    let reversed: Vec<T> = v.iter().rev().copied().collect();
+    let mut out = crop(&full_convolve(a, &reversed), v.len(), a.len(), mode);
+    if swapped {
+        out.reverse();
+    }
+    let len = out.len() as i32;
+    Array::from_vec(out, &[len])
+}
+
+fn matrix_dims<T>(a: &Array<T>, name: &str) -> (usize, usize) {
+    if a.ndim != 2 {
+        panic!("{}: expected 2-D arrays, got shape {:?}", name, a.shape);
+    }
+    (a.shape[0] as usize, a.shape[1] as usize)
+}
+
+// Index into a length-n axis for position x of the extended input, or None
+// where the Fill value applies
+fn extend<T>(x: isize, n: usize, boundary: &Boundary<T>) -> Option<usize> {
+    if x >= 0 && (x as usize) < n {
+        return Some(x as usize);
+    }
+    match boundary {
+        Boundary::Fill(_) => None,
+        Boundary::Wrap => Some(x.rem_euclid(n as isize) as usize),
+        Boundary::Symmetric => {
+            let r = x.rem_euclid(2 * n as isize) as usize;
+            Some(if r < n { r } else { 2 * n - 1 - r })
+        }
+    }
+}
+
+fn convolve2d<T>(a: &Array<T>, k: &Array<T>, mode: ConvolveMode, boundary: Boundary<T>, name: &str) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    let (ma, na) = matrix_dims(a, name);
+    let (mk, nk) = matrix_dims(k, name);
+    let (a, (ma, na), k, (mk, nk)) = if mode == ConvolveMode::Valid && mk >= ma && nk >= na {
+        // Convolution commutes, so take the larger input as the image
+        (k, (mk, nk), a, (ma, na))
+    } else {
+        (a, (ma, na), k, (mk, nk))
+    };
+    if mode == ConvolveMode::Valid && (mk > ma || nk > na) {
+        panic!("{}: in valid mode one input must be at least as large as the other in every dimension", name);
+    }
+
+    // Output window inside the full (ma + mk - 1) x (na + nk - 1) result
+    let (r0, c0, rows, cols) = match mode {
+        ConvolveMode::Full => (0, 0, ma + mk - 1, na + nk - 1),
+        ConvolveMode::Same => ((mk - 1) / 2, (nk - 1) / 2, ma, na),
+        ConvolveMode::Valid => (mk - 1, nk - 1, ma - mk + 1, na - nk + 1),
+    };
+    let fill = match boundary {
+        Boundary::Fill(v) => v,
+        _ => T::zero(),
+    };
+
+    let mut out = vec![T::zero(); rows * cols];
+    out.par_chunks_mut(cols).enumerate().for_each(|(r, row)| {
+        let i = (r + r0) as isize;
+        for (c, o) in row.iter_mut().enumerate() {
+            let j = (c + c0) as isize;
+            let mut acc = T::zero();
+            for p in 0..mk {
+                let x = extend(i - p as isize, ma, &boundary);
+                for q in 0..nk {
+                    let w = k.data[p * nk + q];
+                    let value = match (x, extend(j - q as isize, na, &boundary)) {
+                        (Some(x), Some(y)) => a.data[x * na + y],
+                        _ => fill,
+                    };
+                    acc = acc + value * w;
+                }
+            }
+            *o = acc;
+        }
+    });
+    Array::from_vec(out, &[rows as i32, cols as i32])
+}
+
+// 2-D convolution of `a` with kernel `k` (scipy.signal.convolve2d)
+pub fn nr_convolve2d<T>(a: &Array<T>, k: &Array<T>, mode: ConvolveMode, boundary: Boundary<T>) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    convolve2d(a, k, mode, boundary, "convolve2d")
+}
+
+// 2-D cross-correlation of `a` with kernel `k`, i.e. convolution with the
+// kernel rotated by 180 degrees (scipy.signal.correlate2d)
+pub fn nr_correlate2d<T>(a: &Array<T>, k: &Array<T>, mode: ConvolveMode, boundary: Boundary<T>) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    matrix_dims(k, "correlate2d");
+    let rotated: Vec<T> = k.data.iter().rev().copied().collect();
+    convolve2d(a, &Array::from_vec(rotated, &k.shape), mode, boundary, "correlate2d")
+}
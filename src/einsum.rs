@@ -0,0 +1,347 @@
+use crate::array::Array;
+use crate::gemm;
+use num_traits::Num;
+use std::collections::HashMap;
+
+// Labels are letters as their char code, ellipsis dimensions get
+// codes from ELLIPSIS_BASE upwards so they never collide with letters
+type Label = u32;
+const ELLIPSIS_BASE: Label = 0x10000;
+
+// An operand (or intermediate) whose axes carry einsum labels
+struct Term<T> {
+    data: Vec<T>,
+    labels: Vec<Label>,
+    shape: Vec<usize>,
+}
+
+// Parsed subscripts of one input or of the output
+struct Subscript {
+    letters_before: Vec<Label>,
+    ellipsis: bool,
+    letters_after: Vec<Label>,
+}
+
+// Einstein summation over the operands (np.einsum).
+//
+// Supports implicit ("ij,jk") and explicit ("ij,jk->ik") output, repeated
+// labels within one operand for traces and diagonals, and "..." for
+// broadcast dimensions. With more than two operands a greedy contraction
+// path is chosen, and every pairwise contraction runs on the GEMM kernel.
+// A scalar result is returned with shape [1].
+pub fn nr_einsum<T>(subscripts: &str, operands: &[&Array<T>]) -> Array<T>
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    let subscripts: String = subscripts.chars().filter(|c| !c.is_whitespace()).collect();
+    let (inputs, output) = match subscripts.split_once("->") {
+        Some((lhs, rhs)) => (lhs, Some(rhs)),
+        None => (subscripts.as_str(), None),
+    };
+    let input_subs: Vec<Subscript> = inputs.split(',').map(parse_subscript).collect();
+    if input_subs.len() != operands.len() {
+        panic!(
+            "einsum: {} operands given but subscripts describe {}",
+            operands.len(),
+            input_subs.len()
+        );
+    }
+
+    // Expand "..." into one label per broadcast dimension, aligned from the right
+    let mut ell_ndim = 0;
+    for (sub, op) in input_subs.iter().zip(operands) {
+        let named = sub.letters_before.len() + sub.letters_after.len();
+        if sub.ellipsis {
+            if op.ndim < named {
+                panic!("einsum: operand of shape {:?} has too few dimensions for its subscripts", op.shape);
+            }
+            ell_ndim = ell_ndim.max(op.ndim - named);
+        } else if op.ndim != named {
+            panic!(
+                "einsum: operand of shape {:?} does not match its {} subscripts",
+                op.shape, named
+            );
+        }
+    }
+    let ell_labels: Vec<Label> = (0..ell_ndim as Label).map(|i| ELLIPSIS_BASE + i).collect();
+
+    let mut terms = Vec::with_capacity(operands.len());
+    for (sub, op) in input_subs.iter().zip(operands) {
+        let named = sub.letters_before.len() + sub.letters_after.len();
+        let mut labels = sub.letters_before.clone();
+        if sub.ellipsis {
+            labels.extend_from_slice(&ell_labels[ell_ndim - (op.ndim - named)..]);
+        }
+        labels.extend_from_slice(&sub.letters_after);
+        terms.push(Term {
+            data: op.data.clone(),
+            labels,
+            shape: op.shape.iter().map(|&d| d as usize).collect(),
+        });
+    }
+
+    // Resolve the size of every label, letting size-1 dimensions broadcast
+    let mut sizes: HashMap<Label, usize> = HashMap::new();
+    for term in &terms {
+        for (&label, &dim) in term.labels.iter().zip(&term.shape) {
+            let entry = sizes.entry(label).or_insert(dim);
+            if *entry == 1 {
+                *entry = dim;
+            } else if dim != 1 && dim != *entry {
+                panic!(
+                    "einsum: label '{}' has inconsistent sizes {} and {}",
+                    label_name(label),
+                    *entry,
+                    dim
+                );
+            }
+        }
+    }
+
+    let output_labels = match output {
+        Some(out) => {
+            let sub = parse_subscript(out);
+            let mut labels = sub.letters_before.clone();
+            if sub.ellipsis {
+                labels.extend_from_slice(&ell_labels);
+            }
+            labels.extend_from_slice(&sub.letters_after);
+            for (i, label) in labels.iter().enumerate() {
+                if labels[..i].contains(label) {
+                    panic!("einsum: output label '{}' appears more than once", label_name(*label));
+                }
+                if !sizes.contains_key(label) {
+                    panic!("einsum: output label '{}' does not appear in any input", label_name(*label));
+                }
+            }
+            labels
+        }
+        None => {
+            // Implicit mode: broadcast dims, then letters seen exactly once, sorted
+            let mut counts: HashMap<Label, usize> = HashMap::new();
+            for term in &terms {
+                for &label in &term.labels {
+                    *counts.entry(label).or_insert(0) += 1;
+                }
+            }
+            let mut letters: Vec<Label> = counts
+                .iter()
+                .filter(|&(&l, &c)| l < ELLIPSIS_BASE && c == 1)
+                .map(|(&l, _)| l)
+                .collect();
+            letters.sort_unstable();
+            ell_labels.iter().copied().chain(letters).collect()
+        }
+    };
+
+    // Take diagonals, materialize broadcasts and sum out labels private to one operand
+    let mut terms: Vec<Term<T>> = (0..terms.len())
+        .map(|i| {
+            let needed = |l: &Label| {
+                output_labels.contains(l)
+                    || terms.iter().enumerate().any(|(j, t)| j != i && t.labels.contains(l))
+            };
+            let mut order = Vec::new();
+            for &label in &terms[i].labels {
+                if needed(&label) && !order.contains(&label) {
+                    order.push(label);
+                }
+            }
+            let data = arrange(&terms[i], &order, &sizes);
+            let shape = order.iter().map(|l| sizes[l]).collect();
+            Term { data, labels: order, shape }
+        })
+        .collect();
+
+    while terms.len() > 1 {
+        let (i, j) = greedy_pair(&terms, &output_labels, &sizes);
+        let b = terms.remove(j);
+        let a = terms.remove(i);
+        let keep: Vec<Label> = a
+            .labels
+            .iter()
+            .chain(&b.labels)
+            .copied()
+            .filter(|l| output_labels.contains(l) || terms.iter().any(|t| t.labels.contains(l)))
+            .collect();
+        terms.push(contract_pair(&a, &b, &keep, &sizes));
+    }
+
+    let result = terms.pop().unwrap();
+    let data = arrange(&result, &output_labels, &sizes);
+    let mut shape: Vec<i32> = output_labels.iter().map(|l| sizes[l] as i32).collect();
+    if shape.is_empty() {
+        shape.push(1);
+    }
+    Array::from_vec(data, &shape)
+}
+
+fn parse_subscript(s: &str) -> Subscript {
+    let mut sub = Subscript { letters_before: Vec::new(), ellipsis: false, letters_after: Vec::new() };
+    let mut rest = s;
+    while !rest.is_empty() {
+        if let Some(tail) = rest.strip_prefix("...") {
+            if sub.ellipsis {
+                panic!("einsum: more than one ellipsis in subscripts '{}'", s);
+            }
+            sub.ellipsis = true;
+            rest = tail;
+            continue;
+        }
+        let c = rest.chars().next().unwrap();
+        if !c.is_ascii_alphabetic() {
+            panic!("einsum: invalid subscript character '{}' in '{}'", c, s);
+        }
+        if sub.ellipsis {
+            sub.letters_after.push(c as Label);
+        } else {
+            sub.letters_before.push(c as Label);
+        }
+        rest = &rest[1..];
+    }
+    sub
+}
+
+fn label_name(label: Label) -> String {
+    if label >= ELLIPSIS_BASE {
+        "...".to_string()
+    } else {
+        char::from_u32(label).unwrap().to_string()
+    }
+}
+
+// Gather the term's elements with axes in `order`, summing over any of its
+// labels not in `order`. Repeated labels walk the diagonal and size-1 axes
+// are broadcast to the full label size.
+fn arrange<T: Num + Copy>(term: &Term<T>, order: &[Label], sizes: &HashMap<Label, usize>) -> Vec<T> {
+    let mut all = order.to_vec();
+    for &label in &term.labels {
+        if !all.contains(&label) {
+            all.push(label);
+        }
+    }
+    let n_keep = order.len();
+
+    let mut elem_strides = vec![1; term.shape.len()];
+    for ax in (0..term.shape.len().saturating_sub(1)).rev() {
+        elem_strides[ax] = elem_strides[ax + 1] * term.shape[ax + 1];
+    }
+
+    let shape: Vec<usize> = all.iter().map(|l| sizes[l]).collect();
+    let strides: Vec<usize> = all
+        .iter()
+        .map(|&l| {
+            if !term.labels.contains(&l) {
+                panic!("einsum: label '{}' missing from operand", label_name(l));
+            }
+            term.labels
+                .iter()
+                .zip(&term.shape)
+                .zip(&elem_strides)
+                .filter(|((&tl, &dim), _)| tl == l && !(dim == 1 && sizes[&l] > 1))
+                .map(|(_, &stride)| stride)
+                .sum()
+        })
+        .collect();
+
+    // Fast path: already in order with nothing to sum or broadcast
+    if n_keep == all.len() && all == term.labels && shape == term.shape {
+        return term.data.clone();
+    }
+
+    let total: usize = shape.iter().product();
+    let mut gathered = Vec::with_capacity(total);
+    let mut index = vec![0; shape.len()];
+    let mut off = 0;
+    for _ in 0..total {
+        gathered.push(term.data[off]);
+        for d in (0..shape.len()).rev() {
+            index[d] += 1;
+            off += strides[d];
+            if index[d] < shape[d] {
+                break;
+            }
+            off -= strides[d] * shape[d];
+            index[d] = 0;
+        }
+    }
+
+    let summed: usize = shape[n_keep..].iter().product();
+    if summed == 1 {
+        return gathered;
+    }
+    gathered
+        .chunks(summed)
+        .map(|chunk| chunk.iter().fold(T::zero(), |acc, &v| acc + v))
+        .collect()
+}
+
+// Contract two terms as a batched matrix product:
+// a -> (batch, left, contracted), b -> (batch, contracted, right)
+fn contract_pair<T>(a: &Term<T>, b: &Term<T>, keep: &[Label], sizes: &HashMap<Label, usize>) -> Term<T>
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    let batch: Vec<Label> = a.labels.iter().copied().filter(|l| b.labels.contains(l) && keep.contains(l)).collect();
+    let contracted: Vec<Label> = a.labels.iter().copied().filter(|l| b.labels.contains(l) && !keep.contains(l)).collect();
+    let left: Vec<Label> = a.labels.iter().copied().filter(|l| !b.labels.contains(l) && keep.contains(l)).collect();
+    let right: Vec<Label> = b.labels.iter().copied().filter(|l| !a.labels.contains(l) && keep.contains(l)).collect();
+
+    let a_order: Vec<Label> = batch.iter().chain(&left).chain(&contracted).copied().collect();
+    let b_order: Vec<Label> = batch.iter().chain(&contracted).chain(&right).copied().collect();
+    let a_data = arrange(a, &a_order, sizes);
+    let b_data = arrange(b, &b_order, sizes);
+
+    let size_of = |labels: &[Label]| labels.iter().map(|l| sizes[l]).product::<usize>();
+    let (nb, m, k, n) = (size_of(&batch), size_of(&left), size_of(&contracted), size_of(&right));
+
+    let mut data = vec![T::zero(); nb * m * n];
+    for bi in 0..nb {
+        gemm::matmul_2d(
+            &a_data[bi * m * k..(bi + 1) * m * k],
+            &b_data[bi * k * n..(bi + 1) * k * n],
+            &mut data[bi * m * n..(bi + 1) * m * n],
+            m,
+            k,
+            n,
+        );
+    }
+
+    let labels: Vec<Label> = batch.iter().chain(&left).chain(&right).copied().collect();
+    let shape = labels.iter().map(|l| sizes[l]).collect();
+    Term { data, labels, shape }
+}
+
+// Greedy path step: pick the pair whose contraction shrinks the total
+// intermediate size the most, breaking ties by the cheaper contraction
+fn greedy_pair<T>(terms: &[Term<T>], output: &[Label], sizes: &HashMap<Label, usize>) -> (usize, usize) {
+    let mut best = (0, 1);
+    let mut best_cost = (f64::INFINITY, f64::INFINITY);
+    for i in 0..terms.len() {
+        for j in i + 1..terms.len() {
+            let mut union: Vec<Label> = terms[i].labels.clone();
+            for &l in &terms[j].labels {
+                if !union.contains(&l) {
+                    union.push(l);
+                }
+            }
+            let kept = union.iter().filter(|l| {
+                output.contains(l)
+                    || terms.iter().enumerate().any(|(o, t)| o != i && o != j && t.labels.contains(l))
+            });
+            let removed = labels_size(kept, sizes)
+                - labels_size(terms[i].labels.iter(), sizes)
+                - labels_size(terms[j].labels.iter(), sizes);
+            let flops = labels_size(union.iter(), sizes);
+            if (removed, flops) < best_cost {
+                best_cost = (removed, flops);
+                best = (i, j);
+            }
+        }
+    }
+    best
+}
+
+fn labels_size<'a>(labels: impl Iterator<Item = &'a Label>, sizes: &HashMap<Label, usize>) -> f64 {
+    labels.map(|l| sizes[l] as f64).product()
+}
@@ -0,0 +1,311 @@
+// Discrete Fourier transforms over Array (numpy.fft).
+//
+// Every length is supported: mixed-radix Cooley-Tukey splits off the small
+// prime factors and a remaining large prime is handled by Bluestein's
+// chirp-z algorithm, so the cost stays O(n log n). Multi-dimensional
+// transforms apply the 1-D transform along each requested axis in turn,
+// with independent lanes processed in parallel.
+mod plan;
+
+use crate::array::Array;
+use num_complex::Complex;
+use num_traits::{Float, Zero};
+use plan::FftPlan;
+use rayon::prelude::*;
+
+// Scaling convention, as the `norm` argument of numpy.fft
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FftNorm {
+    // Forward unscaled, inverse scaled by 1/n
+    #[default]
+    Backward,
+    // Both directions scaled by 1/sqrt(n)
+    Ortho,
+    // Forward scaled by 1/n, inverse unscaled
+    Forward,
+}
+
+impl FftNorm {
+    fn factor<T: Float>(self, n: usize, inverse: bool) -> T {
+        let n = T::from(n).unwrap();
+        match (self, inverse) {
+            (FftNorm::Backward, false) | (FftNorm::Forward, true) => T::one(),
+            (FftNorm::Ortho, _) => n.sqrt().recip(),
+            _ => n.recip(),
+        }
+    }
+}
+
+// Resolve an optional axis, defaulting to the last one
+pub(crate) fn resolve_axis<T>(a: &Array<T>, axis: Option<usize>, name: &str) -> usize {
+    let axis = axis.unwrap_or(a.ndim - 1);
+    if axis >= a.ndim {
+        panic!("{}: axis {} is out of bounds for array of shape {:?}", name, axis, a.shape);
+    }
+    axis
+}
+
+// Apply `f` to every 1-D lane of `a` along `axis`. f maps an input lane to
+// an output lane of length `n_out`, which replaces the axis length.
+pub(crate) fn map_lanes<T, U, F>(a: &Array<T>, axis: usize, n_out: usize, f: F) -> Array<U>
+where
+    T: Copy + Zero + Send + Sync,
+    U: Copy + Zero + Send + Sync,
+    F: Fn(&[T]) -> Vec<U> + Sync,
+{
+    let len = a.shape[axis] as usize;
+    let outer: usize = a.shape[..axis].iter().map(|&d| d as usize).product();
+    let inner: usize = a.shape[axis + 1..].iter().map(|&d| d as usize).product();
+
+    let lanes: Vec<Vec<U>> = (0..outer * inner)
+        .into_par_iter()
+        .map(|lane| {
+            let (o, i) = (lane / inner, lane % inner);
+            let input: Vec<T> = (0..len).map(|j| a.data[(o * len + j) * inner + i]).collect();
+            f(&input)
+        })
+        .collect();
+
+    let mut shape = a.shape.clone();
+    shape[axis] = n_out as i32;
+    let mut out = vec![U::zero(); outer * n_out * inner];
+    for (lane, values) in lanes.iter().enumerate() {
+        let (o, i) = (lane / inner, lane % inner);
+        for (j, &v) in values.iter().enumerate() {
+            out[(o * n_out + j) * inner + i] = v;
+        }
+    }
+    Array::from_vec(out, &shape)
+}
+
+// Lane truncated or zero-padded to length n
+fn fit<T: Copy + Zero>(lane: &[T], n: usize) -> Vec<T> {
+    let mut v: Vec<T> = lane.iter().take(n).copied().collect();
+    v.resize(n, T::zero());
+    v
+}
+
+fn complex_transform<T>(a: &Array<Complex<T>>, n: Option<usize>, axis: Option<usize>, norm: FftNorm, inverse: bool, name: &str) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    let axis = resolve_axis(a, axis, name);
+    let n = n.unwrap_or(a.shape[axis] as usize);
+    if n == 0 {
+        panic!("{}: invalid number of data points 0", name);
+    }
+    let plan = FftPlan::new(n);
+    let scale = Complex::new(norm.factor::<T>(n, inverse), T::zero());
+    map_lanes(a, axis, n, |lane| {
+        let x = fit(lane, n);
+        let mut out = vec![Complex::zero(); n];
+        if inverse {
+            plan.inverse(&x, &mut out);
+        } else {
+            plan.forward(&x, &mut out);
+        }
+        out.iter().map(|&v| v * scale).collect()
+    })
+}
+
+// 1-D discrete Fourier transform along `axis` (default last), cropping or
+// zero-padding the input to n points first (np.fft.fft)
+pub fn nr_fft<T>(a: &Array<Complex<T>>, n: Option<usize>, axis: Option<usize>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    complex_transform(a, n, axis, norm, false, "fft")
+}
+
+// Inverse of nr_fft (np.fft.ifft)
+pub fn nr_ifft<T>(a: &Array<Complex<T>>, n: Option<usize>, axis: Option<usize>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    complex_transform(a, n, axis, norm, true, "ifft")
+}
+
+// FFT of real input, keeping the n / 2 + 1 non-negative frequencies
+// (np.fft.rfft)
+pub fn nr_rfft<T>(a: &Array<T>, n: Option<usize>, axis: Option<usize>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    let axis = resolve_axis(a, axis, "rfft");
+    let n = n.unwrap_or(a.shape[axis] as usize);
+    if n == 0 {
+        panic!("rfft: invalid number of data points 0");
+    }
+    let plan = FftPlan::new(n);
+    let scale = norm.factor::<T>(n, false);
+    map_lanes(a, axis, n / 2 + 1, |lane| {
+        let x: Vec<Complex<T>> = fit(lane, n).into_iter().map(|v| Complex::new(v, T::zero())).collect();
+        let mut out = vec![Complex::zero(); n];
+        plan.forward(&x, &mut out);
+        out.truncate(n / 2 + 1);
+        out.iter().map(|&v| v * scale).collect()
+    })
+}
+
+// Inverse of nr_rfft (np.fft.irfft). n is the length of the real output and
+// defaults to 2 (m - 1) for m input points; the imaginary parts of the zero
+// and Nyquist terms are ignored.
+pub fn nr_irfft<T>(a: &Array<Complex<T>>, n: Option<usize>, axis: Option<usize>, norm: FftNorm) -> Array<T>
+where
+    T: Float + Send + Sync,
+{
+    let axis = resolve_axis(a, axis, "irfft");
+    let m = a.shape[axis] as usize;
+    let n = n.unwrap_or(2 * (m.max(1) - 1));
+    if n == 0 {
+        panic!("irfft: invalid number of data points 0");
+    }
+    let plan = FftPlan::new(n);
+    let scale = norm.factor::<T>(n, true);
+    map_lanes(a, axis, n, |lane| {
+        // Rebuild the full Hermitian spectrum
+        let half = fit(lane, n / 2 + 1);
+        let mut full = vec![Complex::zero(); n];
+        full[0] = Complex::new(half[0].re, T::zero());
+        for k in 1..=(n - 1) / 2 {
+            full[k] = half[k];
+            full[n - k] = half[k].conj();
+        }
+        if n.is_multiple_of(2) {
+            full[n / 2] = Complex::new(half[n / 2].re, T::zero());
+        }
+        let mut out = vec![Complex::zero(); n];
+        plan.inverse(&full, &mut out);
+        out.iter().map(|v| v.re * scale).collect()
+    })
+}
+
+fn nd_transform<T>(a: &Array<Complex<T>>, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm, inverse: bool, name: &str) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    let axes: Vec<usize> = match (axes, s) {
+        (Some(axes), _) => axes.to_vec(),
+        (None, Some(s)) => (a.ndim - s.len().min(a.ndim)..a.ndim).collect(),
+        (None, None) => (0..a.ndim).collect(),
+    };
+    if let Some(s) = s {
+        if s.len() != axes.len() {
+            panic!("{}: s and axes must have the same length", name);
+        }
+    }
+    let mut out = Array::from_vec(a.data.clone(), &a.shape);
+    for (i, &axis) in axes.iter().enumerate() {
+        out = complex_transform(&out, s.map(|s| s[i]), Some(axis), norm, inverse, name);
+    }
+    out
+}
+
+// N-dimensional FFT over `axes` (default all, or the last len(s)), with
+// optional output sizes s per axis (np.fft.fftn)
+pub fn nr_fftn<T>(a: &Array<Complex<T>>, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    nd_transform(a, s, axes, norm, false, "fftn")
+}
+
+// Inverse of nr_fftn (np.fft.ifftn)
+pub fn nr_ifftn<T>(a: &Array<Complex<T>>, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    nd_transform(a, s, axes, norm, true, "ifftn")
+}
+
+// Default axes of the 2-D transforms
+fn last_two_axes<T>(a: &Array<T>, name: &str) -> [usize; 2] {
+    if a.ndim < 2 {
+        panic!("{}: input of shape {:?} needs at least 2 dimensions unless axes are given", name, a.shape);
+    }
+    [a.ndim - 2, a.ndim - 1]
+}
+
+// 2-D FFT over `axes`, the last two by default (np.fft.fft2)
+pub fn nr_fft2<T>(a: &Array<Complex<T>>, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    let last_two;
+    let axes = match axes {
+        Some(axes) => axes,
+        None => {
+            last_two = last_two_axes(a, "fft2");
+            &last_two
+        }
+    };
+    nd_transform(a, s, Some(axes), norm, false, "fft2")
+}
+
+// Inverse of nr_fft2 (np.fft.ifft2)
+pub fn nr_ifft2<T>(a: &Array<Complex<T>>, s: Option<&[usize]>, axes: Option<&[usize]>, norm: FftNorm) -> Array<Complex<T>>
+where
+    T: Float + Send + Sync,
+{
+    let last_two;
+    let axes = match axes {
+        Some(axes) => axes,
+        None => {
+            last_two = last_two_axes(a, "ifft2");
+            &last_two
+        }
+    };
+    nd_transform(a, s, Some(axes), norm, true, "ifft2")
+}
+
+// Sample frequencies of an n-point FFT with sample spacing d
+// (np.fft.fftfreq): [0, 1, ..., ceil(n/2) - 1, -floor(n/2), ..., -1] / (d n)
+pub fn nr_fftfreq<T: Float>(n: usize, d: T) -> Array<T> {
+    let scale = (d * T::from(n).unwrap()).recip();
+    let data = (0..n)
+        .map(|k| {
+            let k = if k < n.div_ceil(2) { k as f64 } else { k as f64 - n as f64 };
+            T::from(k).unwrap() * scale
+        })
+        .collect();
+    Array::from_vec(data, &[n as i32])
+}
+
+// Sample frequencies of an n-point rfft: [0, 1, ..., n/2] / (d n)
+// (np.fft.rfftfreq)
+pub fn nr_rfftfreq<T: Float>(n: usize, d: T) -> Array<T> {
+    let scale = (d * T::from(n).unwrap()).recip();
+    let data = (0..=n / 2).map(|k| T::from(k).unwrap() * scale).collect();
+    Array::from_vec(data, &[(n / 2 + 1) as i32])
+}
+
+// Move the zero-frequency term to the center of each axis in `axes`
+// (default all) (np.fft.fftshift)
+pub fn nr_fftshift<T: Copy + Zero>(a: &Array<T>, axes: Option<&[usize]>) -> Array<T> {
+    shift(a, axes, false)
+}
+
+// Inverse of nr_fftshift (np.fft.ifftshift)
+pub fn nr_ifftshift<T: Copy + Zero>(a: &Array<T>, axes: Option<&[usize]>) -> Array<T> {
+    shift(a, axes, true)
+}
+
+fn shift<T: Copy + Zero>(a: &Array<T>, axes: Option<&[usize]>, inverse: bool) -> Array<T> {
+    let all: Vec<usize> = (0..a.ndim).collect();
+    let axes = axes.unwrap_or(&all);
+    let mut out = Array::from_vec(a.data.clone(), &a.shape);
+    for &axis in axes {
+        if axis >= a.ndim {
+            panic!("fftshift: axis {} is out of bounds for array of shape {:?}", axis, a.shape);
+        }
+        let len = a.shape[axis] as usize;
+        let roll = if inverse { len - len / 2 } else { len / 2 };
+        let inner: usize = a.shape[axis + 1..].iter().map(|&d| d as usize).product();
+        let block = len * inner;
+        // Rolling an axis is a rotation of each contiguous block it spans
+        for chunk in out.data.chunks_mut(block) {
+            chunk.rotate_right(roll * inner);
+        }
+    }
+    out
+}
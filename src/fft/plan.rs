@@ -0,0 +1,158 @@
+use num_complex::Complex;
+use num_traits::Float;
+
+// Prime lengths up to this size use a direct DFT; longer ones use Bluestein
+const DIRECT_DFT_MAX: usize = 16;
+
+// Precomputed transform of one length: radices for mixed-radix decimation
+// in time, the twiddle table exp(-2 pi i k / n), and a Bluestein stage for
+// a large prime left over after the small factors are split off.
+pub(crate) struct FftPlan<T> {
+    n: usize,
+    radices: Vec<usize>,
+    twiddles: Vec<Complex<T>>,
+    bluestein: Option<Box<Bluestein<T>>>,
+}
+
+struct Bluestein<T> {
+    // exp(-pi i k^2 / p)
+    chirp: Vec<Complex<T>>,
+    // FFT of the conjugate chirp, wrapped around to the padded length
+    kernel: Vec<Complex<T>>,
+    inner: FftPlan<T>,
+}
+
+impl<T: Float> FftPlan<T> {
+    pub fn new(n: usize) -> FftPlan<T> {
+        let mut radices = Vec::new();
+        let mut rest = n;
+        let mut p = 2;
+        while p * p <= rest {
+            while rest.is_multiple_of(p) {
+                radices.push(p);
+                rest /= p;
+            }
+            p += 1;
+        }
+        // Whatever remains is prime (or 1) and ends up as the leaf length
+        let bluestein = if rest > DIRECT_DFT_MAX { Some(Box::new(Bluestein::new(rest))) } else { None };
+        if rest > 1 && bluestein.is_none() {
+            radices.push(rest);
+        }
+        let twiddles = (0..n).map(|k| root(k, n)).collect();
+        FftPlan { n, radices, twiddles, bluestein }
+    }
+
+    // Forward (unnormalized) transform of x into out
+    pub fn forward(&self, x: &[Complex<T>], out: &mut [Complex<T>]) {
+        if self.n == 0 {
+            return;
+        }
+        self.transform(x, 1, out, 0, 1);
+    }
+
+    // Inverse (unnormalized) transform via conj(FFT(conj(x)))
+    pub fn inverse(&self, x: &[Complex<T>], out: &mut [Complex<T>]) {
+        let conj: Vec<Complex<T>> = x.iter().map(|v| v.conj()).collect();
+        self.forward(&conj, out);
+        for v in out.iter_mut() {
+            *v = v.conj();
+        }
+    }
+
+    // Transform of the out.len() elements x[0], x[stride], ... using the
+    // radices from `level` on. `scale` maps twiddles of this sub-length
+    // onto the full table.
+    fn transform(&self, x: &[Complex<T>], stride: usize, out: &mut [Complex<T>], level: usize, scale: usize) {
+        let n = out.len();
+        if level == self.radices.len() {
+            match &self.bluestein {
+                Some(b) if n > 1 => b.transform(x, stride, out),
+                _ => self.direct(x, stride, out, scale),
+            }
+            return;
+        }
+
+        let p = self.radices[level];
+        let m = n / p;
+        for r in 0..p {
+            self.transform(&x[r * stride..], stride * p, &mut out[r * m..(r + 1) * m], level + 1, scale * p);
+        }
+
+        // Combine p sub-transforms of length m:
+        // X[k + q m] = sum_r (W_n^(r k) Y_r[k]) W_p^(r q)
+        let mut t = vec![Complex::new(T::zero(), T::zero()); p];
+        for k in 0..m {
+            for (r, tr) in t.iter_mut().enumerate() {
+                *tr = out[r * m + k] * self.twiddles[(r * k * scale) % self.n];
+            }
+            if p == 2 {
+                out[k] = t[0] + t[1];
+                out[k + m] = t[0] - t[1];
+                continue;
+            }
+            for q in 0..p {
+                let mut acc = t[0];
+                for (r, &tr) in t.iter().enumerate().skip(1) {
+                    acc = acc + tr * self.twiddles[((r * q) % p) * m * scale];
+                }
+                out[k + q * m] = acc;
+            }
+        }
+    }
+
+    // O(n^2) DFT of a short leaf
+    fn direct(&self, x: &[Complex<T>], stride: usize, out: &mut [Complex<T>], scale: usize) {
+        let n = out.len();
+        for (k, o) in out.iter_mut().enumerate() {
+            let mut acc = Complex::new(T::zero(), T::zero());
+            for j in 0..n {
+                acc = acc + x[j * stride] * self.twiddles[((j * k) % n) * scale];
+            }
+            *o = acc;
+        }
+    }
+}
+
+impl<T: Float> Bluestein<T> {
+    fn new(p: usize) -> Bluestein<T> {
+        let m = (2 * p - 1).next_power_of_two();
+        // k^2 mod 2p keeps the chirp angle small and exact
+        let chirp: Vec<Complex<T>> = (0..p).map(|k| root((k * k) % (2 * p), 2 * p)).collect();
+        let mut b = vec![Complex::new(T::zero(), T::zero()); m];
+        b[0] = chirp[0].conj();
+        for k in 1..p {
+            b[k] = chirp[k].conj();
+            b[m - k] = chirp[k].conj();
+        }
+        let inner = FftPlan::new(m);
+        let mut kernel = vec![Complex::new(T::zero(), T::zero()); m];
+        inner.forward(&b, &mut kernel);
+        Bluestein { chirp, kernel, inner }
+    }
+
+    fn transform(&self, x: &[Complex<T>], stride: usize, out: &mut [Complex<T>]) {
+        let m = self.kernel.len();
+        let p = self.chirp.len();
+        let mut a = vec![Complex::new(T::zero(), T::zero()); m];
+        for k in 0..p {
+            a[k] = x[k * stride] * self.chirp[k];
+        }
+        let mut spec = vec![Complex::new(T::zero(), T::zero()); m];
+        self.inner.forward(&a, &mut spec);
+        for (s, &k) in spec.iter_mut().zip(&self.kernel) {
+            *s = *s * k;
+        }
+        self.inner.inverse(&spec, &mut a);
+        let inv_m = T::from(m).unwrap().recip();
+        for k in 0..p {
+            out[k] = a[k] * self.chirp[k] * inv_m;
+        }
+    }
+}
+
+// exp(-2 pi i k / n), computed in f64
+fn root<T: Float>(k: usize, n: usize) -> Complex<T> {
+    let angle = -2.0 * std::f64::consts::PI * k as f64 / n as f64;
+    Complex::new(T::from(angle.cos()).unwrap(), T::from(angle.sin()).unwrap())
+}
@@ -0,0 +1,185 @@
+use num_complex::Complex;
+use num_traits::{Float, Num};
+use rayon::prelude::*;
+use std::any::TypeId;
+
+// Blocking parameters for the packed GEMM kernel.
+// KC x NR panels of B stay in L1, MC x KC blocks of A stay in L2.
+const MR: usize = 4;
+const NR: usize = 8;
+const MC: usize = 64;
+const KC: usize = 256;
+const NC: usize = 4096;
+
+// Below this many multiply-adds the packing overhead is not worth it
+const SMALL_GEMM: usize = 32 * 32 * 32;
+
+// C (m x n) = A (m x k) * B (k x n), all row-major and contiguous.
+// f32/f64 go through the packed kernel, Complex<f32>/Complex<f64> through four
+// real products of the same kernel, every other T uses the generic loop.
+pub fn matmul_2d<T>(a: &[T], b: &[T], c: &mut [T], m: usize, k: usize, n: usize)
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    if TypeId::of::<T>() == TypeId::of::<f32>() {
+        // SAFETY: T is f32, so the slices have identical layout
+        let (a, b, c) = unsafe { (cast::<T, f32>(a), cast::<T, f32>(b), cast_mut::<T, f32>(c)) };
+        gemm_float(a, b, c, m, k, n);
+    } else if TypeId::of::<T>() == TypeId::of::<f64>() {
+        // SAFETY: T is f64, so the slices have identical layout
+        let (a, b, c) = unsafe { (cast::<T, f64>(a), cast::<T, f64>(b), cast_mut::<T, f64>(c)) };
+        gemm_float(a, b, c, m, k, n);
+    } else if TypeId::of::<T>() == TypeId::of::<Complex<f32>>() {
+        // SAFETY: T is Complex<f32>, so the slices have identical layout
+        let (a, b, c) = unsafe { (cast::<T, Complex<f32>>(a), cast::<T, Complex<f32>>(b), cast_mut::<T, Complex<f32>>(c)) };
+        gemm_complex(a, b, c, m, k, n);
+    } else if TypeId::of::<T>() == TypeId::of::<Complex<f64>>() {
+        // SAFETY: T is Complex<f64>, so the slices have identical layout
+        let (a, b, c) = unsafe { (cast::<T, Complex<f64>>(a), cast::<T, Complex<f64>>(b), cast_mut::<T, Complex<f64>>(c)) };
+        gemm_complex(a, b, c, m, k, n);
+    } else {
+        gemm_generic(a, b, c, m, k, n);
+    }
+}
+
+unsafe fn cast<T, U>(s: &[T]) -> &[U] {
+    std::slice::from_raw_parts(s.as_ptr() as *const U, s.len())
+}
+
+unsafe fn cast_mut<T, U>(s: &mut [T]) -> &mut [U] {
+    std::slice::from_raw_parts_mut(s.as_mut_ptr() as *mut U, s.len())
+}
+
+// i-k-j loop order so the inner loop walks both B and C contiguously
+fn gemm_generic<T: Num + Copy>(a: &[T], b: &[T], c: &mut [T], m: usize, k: usize, n: usize) {
+    for v in c.iter_mut() {
+        *v = T::zero();
+    }
+    for i in 0..m {
+        let c_row = &mut c[i * n..(i + 1) * n];
+        for p in 0..k {
+            let aip = a[i * k + p];
+            let b_row = &b[p * n..(p + 1) * n];
+            for (cv, &bv) in c_row.iter_mut().zip(b_row) {
+                *cv = *cv + aip * bv;
+            }
+        }
+    }
+}
+
+// Complex product split into real and imaginary planes:
+// Re C = Ar Br - Ai Bi, Im C = Ar Bi + Ai Br
+fn gemm_complex<T: Float + Send + Sync>(a: &[Complex<T>], b: &[Complex<T>], c: &mut [Complex<T>], m: usize, k: usize, n: usize) {
+    if m * n * k <= SMALL_GEMM {
+        gemm_generic(a, b, c, m, k, n);
+        return;
+    }
+    let split = |x: &[Complex<T>]| -> (Vec<T>, Vec<T>) { x.iter().map(|z| (z.re, z.im)).unzip() };
+    let (ar, ai) = split(a);
+    let (br, bi) = split(b);
+    let mut t = vec![T::zero(); m * n];
+    gemm_float(&ar, &br, &mut t, m, k, n);
+    for (cv, &v) in c.iter_mut().zip(&t) {
+        *cv = Complex::new(v, T::zero());
+    }
+    gemm_float(&ai, &bi, &mut t, m, k, n);
+    for (cv, &v) in c.iter_mut().zip(&t) {
+        cv.re = cv.re - v;
+    }
+    gemm_float(&ar, &bi, &mut t, m, k, n);
+    for (cv, &v) in c.iter_mut().zip(&t) {
+        cv.im = v;
+    }
+    gemm_float(&ai, &br, &mut t, m, k, n);
+    for (cv, &v) in c.iter_mut().zip(&t) {
+        cv.im = cv.im + v;
+    }
+}
+
+fn gemm_float<T: Float + Send + Sync>(a: &[T], b: &[T], c: &mut [T], m: usize, k: usize, n: usize) {
+    if m * n * k <= SMALL_GEMM {
+        gemm_generic(a, b, c, m, k, n);
+        return;
+    }
+    for v in c.iter_mut() {
+        *v = T::zero();
+    }
+
+    for jc in (0..n).step_by(NC) {
+        let nc = NC.min(n - jc);
+        for pc in (0..k).step_by(KC) {
+            let kc = KC.min(k - pc);
+            let packed_b = pack_b(b, n, pc, jc, kc, nc);
+
+            // Each row block of C is owned by exactly one task
+            c.par_chunks_mut(MC * n).enumerate().for_each(|(ib, c_block)| {
+                let ic = ib * MC;
+                let mc = MC.min(m - ic);
+                let packed_a = pack_a(a, k, ic, pc, mc, kc);
+
+                for jr in (0..nc).step_by(NR) {
+                    let nr = NR.min(nc - jr);
+                    let pb = &packed_b[jr * kc..(jr + NR) * kc];
+                    for ir in (0..mc).step_by(MR) {
+                        let mr = MR.min(mc - ir);
+                        let pa = &packed_a[ir * kc..(ir + MR) * kc];
+                        let acc = micro_kernel(kc, pa, pb);
+                        for (i, acc_row) in acc.iter().enumerate().take(mr) {
+                            let row = &mut c_block[(ir + i) * n + jc + jr..(ir + i) * n + jc + jr + nr];
+                            for (cv, &av) in row.iter_mut().zip(acc_row) {
+                                *cv = *cv + av;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    }
+}
+
+// Pack an mc x kc block of A into MR-row slivers, each stored column by column.
+// Rows past mc are zero padded so the micro-kernel never branches.
+fn pack_a<T: Float>(a: &[T], lda: usize, ic: usize, pc: usize, mc: usize, kc: usize) -> Vec<T> {
+    let slivers = mc.div_ceil(MR);
+    let mut packed = vec![T::zero(); slivers * MR * kc];
+    for s in 0..slivers {
+        let dst = &mut packed[s * MR * kc..(s + 1) * MR * kc];
+        for i in 0..MR.min(mc - s * MR) {
+            let src = &a[(ic + s * MR + i) * lda + pc..(ic + s * MR + i) * lda + pc + kc];
+            for (p, &v) in src.iter().enumerate() {
+                dst[p * MR + i] = v;
+            }
+        }
+    }
+    packed
+}
+
+// Pack a kc x nc panel of B into NR-column slivers, each stored row by row.
+fn pack_b<T: Float>(b: &[T], ldb: usize, pc: usize, jc: usize, kc: usize, nc: usize) -> Vec<T> {
+    let slivers = nc.div_ceil(NR);
+    let mut packed = vec![T::zero(); slivers * NR * kc];
+    for s in 0..slivers {
+        let width = NR.min(nc - s * NR);
+        let dst = &mut packed[s * NR * kc..(s + 1) * NR * kc];
+        for p in 0..kc {
+            let src = &b[(pc + p) * ldb + jc + s * NR..(pc + p) * ldb + jc + s * NR + width];
+            dst[p * NR..p * NR + width].copy_from_slice(src);
+        }
+    }
+    packed
+}
+
+// MR x NR outer-product accumulation over one packed sliver pair
+#[inline(always)]
+fn micro_kernel<T: Float>(kc: usize, pa: &[T], pb: &[T]) -> [[T; NR]; MR] {
+    let mut acc = [[T::zero(); NR]; MR];
+    for (a_col, b_row) in pa.chunks_exact(MR).zip(pb.chunks_exact(NR)).take(kc) {
+        for i in 0..MR {
+            let av = a_col[i];
+            for j in 0..NR {
+                acc[i][j] = acc[i][j] + av * b_row[j];
+            }
+        }
+    }
+    acc
+}
@@ -0,0 +1,322 @@
+use super::dense::Interpolant;
+use super::{initial_step, min_step, rms_norm, Problem, Stepper};
+use crate::linalg::{lu_in_place, lu_solve};
+
+const MAX_ORDER: usize = 5;
+const NEWTON_MAXITER: usize = 4;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+// Matrix that rescales backward differences of order `order` from step h
+// to step factor * h
+fn compute_r(order: usize, factor: f64) -> Vec<Vec<f64>> {
+    let mut r = vec![vec![1.0; order + 1]; order + 1];
+    for i in 1..=order {
+        let (done, rest) = r.split_at_mut(i);
+        for (j, (v, prev)) in rest[0].iter_mut().zip(&done[i - 1]).enumerate() {
+            let m = if j == 0 { 0.0 } else { (i as f64 - 1.0 - factor * j as f64) / i as f64 };
+            *v = prev * m;
+        }
+    }
+    r
+}
+
+// Rescale the differences in d[..=order] to a step changed by `factor`
+fn change_d(d: &mut [Vec<f64>], order: usize, factor: f64) {
+    let r = compute_r(order, factor);
+    let u = compute_r(order, 1.0);
+    // (R U)^T applied to the rows of d
+    let ru: Vec<Vec<f64>> = (0..=order).map(|i| (0..=order).map(|j| (0..=order).map(|k| r[i][k] * u[k][j]).sum()).collect()).collect();
+    let n = d[0].len();
+    let new: Vec<Vec<f64>> = (0..=order)
+        .map(|j| {
+            let mut row = vec![0.0; n];
+            for (i, di) in d.iter().enumerate().take(order + 1) {
+                for (v, x) in row.iter_mut().zip(di) {
+                    *v += ru[i][j] * x;
+                }
+            }
+            row
+        })
+        .collect();
+    for (di, row) in d.iter_mut().zip(new) {
+        *di = row;
+    }
+}
+
+// Variable order (1 to 5) backward differentiation formulas with the
+// quasi-constant step size formulation of Shampine and Reichelt, as in
+// scipy's BDF. Each step solves its implicit equation by a simplified
+// Newton iteration on the LU factors of I - c J.
+pub(super) struct Bdf<'p, 'f> {
+    problem: &'p Problem<'f>,
+    t: f64,
+    t_old: f64,
+    y: Vec<f64>,
+    h_abs: f64,
+    order: usize,
+    n_equal_steps: usize,
+    newton_tol: f64,
+    // Backward differences of the interpolating polynomial, scaled by h
+    d: Vec<Vec<f64>>,
+    j: Vec<f64>,
+    lu: Option<(Vec<f64>, Vec<usize>)>,
+    gamma: [f64; MAX_ORDER + 1],
+    alpha: [f64; MAX_ORDER + 1],
+    error_const: [f64; MAX_ORDER + 1],
+}
+
+impl<'p, 'f> Bdf<'p, 'f> {
+    pub(super) fn new(problem: &'p Problem<'f>, t0: f64, y0: Vec<f64>, first_step: Option<f64>) -> Bdf<'p, 'f> {
+        let f = problem.eval(t0, &y0);
+        let h_abs = first_step.unwrap_or_else(|| initial_step(problem, t0, &y0, &f, 1));
+        let n = y0.len();
+        let mut d = vec![vec![0.0; n]; MAX_ORDER + 3];
+        d[1] = f.iter().map(|v| v * h_abs * problem.direction).collect();
+        d[0] = y0.clone();
+
+        let kappa = [0.0, -0.1850, -1.0 / 9.0, -0.0823, -0.0415, 0.0];
+        let mut gamma = [0.0; MAX_ORDER + 1];
+        for k in 1..=MAX_ORDER {
+            gamma[k] = gamma[k - 1] + 1.0 / k as f64;
+        }
+        let alpha = std::array::from_fn(|k| (1.0 - kappa[k]) * gamma[k]);
+        let error_const = std::array::from_fn(|k| kappa[k] * gamma[k] + 1.0 / (k + 1) as f64);
+
+        let mut bdf = Bdf {
+            problem,
+            t: t0,
+            t_old: t0,
+            y: y0,
+            h_abs,
+            order: 1,
+            n_equal_steps: 0,
+            newton_tol: (10.0 * f64::EPSILON / problem.rtol).max(0.03f64.min(problem.rtol.sqrt())),
+            d,
+            j: Vec::new(),
+            lu: None,
+            gamma,
+            alpha,
+            error_const,
+        };
+        bdf.j = bdf.jacobian(t0, &bdf.y.clone(), &f);
+        bdf
+    }
+
+    // Row-major Jacobian of f at (t, y), by forward differences unless an
+    // analytic one was given; f0 = f(t, y)
+    fn jacobian(&self, t: f64, y: &[f64], f0: &[f64]) -> Vec<f64> {
+        let njev = &self.problem.njev;
+        njev.set(njev.get() + 1);
+        if let Some(j) = self.problem.eval_jac(t, y) {
+            return j;
+        }
+        let n = y.len();
+        let mut j = vec![0.0; n * n];
+        let mut yp = y.to_vec();
+        for col in 0..n {
+            let h = f64::EPSILON.sqrt() * y[col].abs().max(1.0);
+            yp[col] = y[col] + h;
+            let h = yp[col] - y[col];
+            let fp = self.problem.eval(t, &yp);
+            for row in 0..n {
+                j[row * n + col] = (fp[row] - f0[row]) / h;
+            }
+            yp[col] = y[col];
+        }
+        j
+    }
+
+    // LU factors of I - c J
+    fn factor(&self, c: f64) -> (Vec<f64>, Vec<usize>) {
+        let nlu = &self.problem.nlu;
+        nlu.set(nlu.get() + 1);
+        let n = self.y.len();
+        let mut m: Vec<f64> = self.j.iter().map(|v| -c * v).collect();
+        for i in 0..n {
+            m[i * n + i] += 1.0;
+        }
+        let factors = lu_in_place(&mut m, n, n);
+        (m, factors.perm)
+    }
+
+    // Simplified Newton iteration for the implicit BDF equation. Returns
+    // whether it converged, the iteration count, y_new and the correction
+    // d = y_new - y_predict.
+    fn solve_system(&self, t_new: f64, y_predict: &[f64], c: f64, psi: &[f64], scale: &[f64]) -> (bool, usize, Vec<f64>, Vec<f64>) {
+        let (lu, perm) = self.lu.as_ref().unwrap();
+        let n = y_predict.len();
+        let mut y = y_predict.to_vec();
+        let mut d = vec![0.0; n];
+        let mut dy_norm_old: Option<f64> = None;
+        for k in 0..NEWTON_MAXITER {
+            let f = self.problem.eval(t_new, &y);
+            if f.iter().any(|v| !v.is_finite()) {
+                return (false, k + 1, y, d);
+            }
+            let rhs: Vec<f64> = (0..n).map(|i| c * f[i] - psi[i] - d[i]).collect();
+            let dy = lu_solve(lu, perm, n, &rhs, 1);
+            let dy_norm = rms_norm(&dy.iter().zip(scale).map(|(v, s)| v / s).collect::<Vec<_>>());
+            let rate = dy_norm_old.map(|old| dy_norm / old);
+            if let Some(rate) = rate {
+                if rate >= 1.0 || rate.powi((NEWTON_MAXITER - k) as i32) / (1.0 - rate) * dy_norm > self.newton_tol {
+                    return (false, k + 1, y, d);
+                }
+            }
+            for i in 0..n {
+                y[i] += dy[i];
+                d[i] += dy[i];
+            }
+            if dy_norm == 0.0 || rate.is_some_and(|rate| rate / (1.0 - rate) * dy_norm < self.newton_tol) {
+                return (true, k + 1, y, d);
+            }
+            dy_norm_old = Some(dy_norm);
+        }
+        (false, NEWTON_MAXITER, y, d)
+    }
+}
+
+impl Stepper for Bdf<'_, '_> {
+    fn step(&mut self) -> Result<(), String> {
+        let p = self.problem;
+        let t = self.t;
+        let n = self.y.len();
+        let min_step = min_step(t, p.direction);
+        let mut h_abs = self.h_abs;
+        if h_abs > p.max_step {
+            h_abs = p.max_step;
+            change_d(&mut self.d, self.order, p.max_step / self.h_abs);
+            self.n_equal_steps = 0;
+        } else if h_abs < min_step {
+            h_abs = min_step;
+            change_d(&mut self.d, self.order, min_step / self.h_abs);
+            self.n_equal_steps = 0;
+        }
+        let order = self.order;
+        let mut current_jac = false;
+
+        let (t_new, y_new, d, scale, safety, error_norm) = loop {
+            if h_abs < min_step {
+                return Err("required step size is less than spacing between numbers".to_string());
+            }
+            let mut t_new = t + h_abs * p.direction;
+            if p.direction * (t_new - p.t_bound) > 0.0 {
+                t_new = p.t_bound;
+                change_d(&mut self.d, order, (t_new - t).abs() / h_abs);
+                self.n_equal_steps = 0;
+                self.lu = None;
+            }
+            let h = t_new - t;
+            h_abs = h.abs();
+
+            let mut y_predict = vec![0.0; n];
+            for row in &self.d[..=order] {
+                for (y, v) in y_predict.iter_mut().zip(row) {
+                    *y += v;
+                }
+            }
+            let scale: Vec<f64> = y_predict.iter().map(|y| p.atol + p.rtol * y.abs()).collect();
+            let mut psi = vec![0.0; n];
+            for (row, g) in self.d[1..=order].iter().zip(&self.gamma[1..=order]) {
+                for (s, v) in psi.iter_mut().zip(row) {
+                    *s += v * g / self.alpha[order];
+                }
+            }
+
+            let c = h / self.alpha[order];
+            let (converged, n_iter, y_new, d) = loop {
+                if self.lu.is_none() {
+                    self.lu = Some(self.factor(c));
+                }
+                let result = self.solve_system(t_new, &y_predict, c, &psi, &scale);
+                if result.0 || current_jac {
+                    break result;
+                }
+                // Retry once with a fresh Jacobian before shrinking the step
+                let f = p.eval(t_new, &y_predict);
+                self.j = self.jacobian(t_new, &y_predict, &f);
+                self.lu = None;
+                current_jac = true;
+            };
+
+            if !converged {
+                h_abs *= 0.5;
+                change_d(&mut self.d, order, 0.5);
+                self.n_equal_steps = 0;
+                self.lu = None;
+                continue;
+            }
+
+            let safety = 0.9 * (2 * NEWTON_MAXITER + 1) as f64 / (2 * NEWTON_MAXITER + n_iter) as f64;
+            let scale: Vec<f64> = y_new.iter().map(|y| p.atol + p.rtol * y.abs()).collect();
+            let error: Vec<f64> = d.iter().zip(&scale).map(|(v, s)| self.error_const[order] * v / s).collect();
+            let error_norm = rms_norm(&error);
+            if error_norm > 1.0 {
+                let factor = MIN_FACTOR.max(safety * error_norm.powf(-1.0 / (order + 1) as f64));
+                h_abs *= factor;
+                change_d(&mut self.d, order, factor);
+                self.n_equal_steps = 0;
+            } else {
+                break (t_new, y_new, d, scale, safety, error_norm);
+            }
+        };
+
+        self.n_equal_steps += 1;
+        self.t_old = t;
+        self.t = t_new;
+        self.y = y_new;
+        self.h_abs = h_abs;
+
+        // D^{j+1} y_n = D^j y_n - D^j y_{n-1}, and d is D^{order+1} y_n
+        self.d[order + 2] = d.iter().zip(&self.d[order + 1]).map(|(a, b)| a - b).collect();
+        self.d[order + 1] = d;
+        for i in (0..=order).rev() {
+            let next = self.d[i + 1].clone();
+            for (v, x) in self.d[i].iter_mut().zip(&next) {
+                *v += x;
+            }
+        }
+
+        if self.n_equal_steps < order + 1 {
+            return Ok(());
+        }
+
+        // Pick the order among order - 1, order, order + 1 that allows the
+        // largest next step
+        let norm_of = |k: usize, row: usize| rms_norm(&self.d[row].iter().zip(&scale).map(|(v, s)| self.error_const[k] * v / s).collect::<Vec<_>>());
+        let error_m_norm = if order > 1 { norm_of(order - 1, order) } else { f64::INFINITY };
+        let error_p_norm = if order < MAX_ORDER { norm_of(order + 1, order + 2) } else { f64::INFINITY };
+        let factors: Vec<f64> =
+            [error_m_norm, error_norm, error_p_norm].iter().enumerate().map(|(i, e)| e.powf(-1.0 / (order + i) as f64)).collect();
+        let best = (0..3).fold(0, |best, i| if factors[i] > factors[best] { i } else { best });
+        self.order = order + best - 1;
+
+        let factor = MAX_FACTOR.min(safety * factors[best]);
+        self.h_abs *= factor;
+        change_d(&mut self.d, self.order, factor);
+        self.n_equal_steps = 0;
+        self.lu = None;
+        Ok(())
+    }
+
+    fn t(&self) -> f64 {
+        self.t
+    }
+
+    fn t_old(&self) -> f64 {
+        self.t_old
+    }
+
+    fn y(&self) -> &[f64] {
+        &self.y
+    }
+
+    fn dense_output(&self) -> Interpolant {
+        let h = self.h_abs * self.problem.direction;
+        Interpolant::Differences {
+            t_shift: (0..self.order).map(|i| self.t - h * i as f64).collect(),
+            denom: (0..self.order).map(|i| h * (i + 1) as f64).collect(),
+            d: self.d[..=self.order].to_vec(),
+        }
+    }
+}
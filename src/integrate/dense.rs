@@ -0,0 +1,97 @@
+use crate::array::Array;
+
+// Continuous extension of the solution over a single step
+#[derive(Debug, Clone)]
+pub(super) enum Interpolant {
+    // y_old + h * sum_k q[k] x^(k + 1) with x = (t - t_old) / h
+    Power { t_old: f64, h: f64, y_old: Vec<f64>, q: Vec<Vec<f64>> },
+    // Hairer's nested form for DOP853: y_old + f[0] x + f[1] x (1 - x) +
+    // f[2] x^2 (1 - x) + ..., alternating factors x and 1 - x
+    Nested { t_old: f64, h: f64, y_old: Vec<f64>, f: Vec<Vec<f64>> },
+    // BDF interpolating polynomial in backward differences:
+    // d[0] + sum_k d[k + 1] prod_{j <= k} (t - t_shift[j]) / denom[j]
+    Differences { t_shift: Vec<f64>, denom: Vec<f64>, d: Vec<Vec<f64>> },
+}
+
+fn axpy(y: &mut [f64], a: f64, x: &[f64]) {
+    for (y, x) in y.iter_mut().zip(x) {
+        *y += a * x;
+    }
+}
+
+impl Interpolant {
+    pub(super) fn eval(&self, t: f64) -> Vec<f64> {
+        match self {
+            Interpolant::Power { t_old, h, y_old, q } => {
+                let x = (t - t_old) / h;
+                let mut y = y_old.clone();
+                let mut p = 1.0;
+                for qk in q {
+                    p *= x;
+                    axpy(&mut y, h * p, qk);
+                }
+                y
+            }
+            Interpolant::Nested { t_old, h, y_old, f } => {
+                let x = (t - t_old) / h;
+                let mut y = vec![0.0; y_old.len()];
+                for (i, fi) in f.iter().rev().enumerate() {
+                    let factor = if i % 2 == 0 { x } else { 1.0 - x };
+                    for (y, fi) in y.iter_mut().zip(fi) {
+                        *y = (*y + fi) * factor;
+                    }
+                }
+                axpy(&mut y, 1.0, y_old);
+                y
+            }
+            Interpolant::Differences { t_shift, denom, d } => {
+                let mut y = d[0].clone();
+                let mut p = 1.0;
+                for ((shift, denom), dk) in t_shift.iter().zip(denom).zip(&d[1..]) {
+                    p *= (t - shift) / denom;
+                    axpy(&mut y, p, dk);
+                }
+                y
+            }
+        }
+    }
+}
+
+// Dense output of a solution from nr_solve_ivp: a piecewise polynomial with
+// one piece per solver step (scipy.integrate.OdeSolution). Times outside
+// the integration interval are extrapolated from the first or last piece.
+#[derive(Debug, Clone)]
+pub struct OdeSolution {
+    // Step boundaries in the direction of integration
+    ts: Vec<f64>,
+    interpolants: Vec<Interpolant>,
+}
+
+impl OdeSolution {
+    pub(super) fn new(ts: Vec<f64>, interpolants: Vec<Interpolant>) -> OdeSolution {
+        OdeSolution { ts, interpolants }
+    }
+
+    // First and last time covered by the steps
+    pub fn t_span(&self) -> (f64, f64) {
+        (self.ts[0], self.ts[self.ts.len() - 1])
+    }
+
+    // State at time t as a 1-D array
+    pub fn eval(&self, t: f64) -> Array<f64> {
+        let y = self.eval_vec(t);
+        let n = y.len() as i32;
+        Array::from_vec(y, &[n])
+    }
+
+    pub(super) fn eval_vec(&self, t: f64) -> Vec<f64> {
+        let ascending = self.ts[self.ts.len() - 1] >= self.ts[0];
+        let ind = if ascending {
+            self.ts.partition_point(|&s| s < t)
+        } else {
+            self.ts.partition_point(|&s| s > t)
+        };
+        let segment = ind.saturating_sub(1).min(self.interpolants.len() - 1);
+        self.interpolants[segment].eval(t)
+    }
+}
@@ -0,0 +1,391 @@
+// Initial value problems for systems of ODEs dy/dt = f(t, y), mirroring
+// scipy.integrate.solve_ivp.
+//
+// States are 1-D f64 arrays. nr_solve_ivp drives one of the steppers
+// (explicit Runge-Kutta pairs for non-stiff problems, BDF for stiff ones)
+// from t0 to t1, recording the solution at every step or at requested
+// times, locating sign changes of event functions on the dense output and
+// optionally returning that dense output as an OdeSolution.
+mod bdf;
+mod dense;
+mod rk;
+
+pub use dense::OdeSolution;
+
+use crate::array::Array;
+use bdf::Bdf;
+use dense::Interpolant;
+use rk::{RkMethod, RungeKutta};
+use std::cell::Cell;
+
+// Function of time and state: the right-hand side, its Jacobian or an event
+type StateFn<'a, R> = dyn Fn(f64, &Array<f64>) -> R + 'a;
+
+// Integration method for nr_solve_ivp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OdeMethod {
+    // Dormand-Prince 5(4) with 4th-order dense output; the default choice
+    // for non-stiff problems
+    Rk45,
+    // Dormand-Prince 8(5,3) with 7th-order dense output, for tight
+    // tolerances
+    Dop853,
+    // Implicit variable-order backward differentiation formulas for stiff
+    // problems
+    Bdf,
+}
+
+// Scalar function of the state whose zeros nr_solve_ivp locates
+pub struct Event<'a> {
+    fun: Box<StateFn<'a, f64>>,
+    terminal: bool,
+    direction: f64,
+}
+
+impl<'a> Event<'a> {
+    // A terminal event stops the integration at its first zero. A positive
+    // direction only reports zeros where the function goes from negative to
+    // positive, a negative one the opposite, and zero reports both.
+    pub fn new<F: Fn(f64, &Array<f64>) -> f64 + 'a>(fun: F, terminal: bool, direction: f64) -> Event<'a> {
+        Event { fun: Box::new(fun), terminal, direction }
+    }
+}
+
+// Settings for nr_solve_ivp; see IvpOptions::default for the defaults
+pub struct IvpOptions<'a> {
+    // Relative and absolute tolerances on the local error of each component
+    pub rtol: f64,
+    pub atol: f64,
+    // Largest allowed step size
+    pub max_step: f64,
+    // Size of the first step, chosen automatically when None
+    pub first_step: Option<f64>,
+    // Times at which to store the solution, sorted in the direction of
+    // integration and inside the time span. None stores every step.
+    pub t_eval: Option<Vec<f64>>,
+    // Whether to return the continuous solution
+    pub dense_output: bool,
+    pub events: Vec<Event<'a>>,
+    // Jacobian df/dy as an [n, n] array for BDF, estimated by finite
+    // differences when None
+    pub jac: Option<Box<StateFn<'a, Array<f64>>>>,
+}
+
+impl Default for IvpOptions<'_> {
+    fn default() -> Self {
+        IvpOptions {
+            rtol: 1e-3,
+            atol: 1e-6,
+            max_step: f64::INFINITY,
+            first_step: None,
+            t_eval: None,
+            dense_output: false,
+            events: Vec::new(),
+            jac: None,
+        }
+    }
+}
+
+// How nr_solve_ivp ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum IvpStatus {
+    // Reached the end of the time span
+    Finished,
+    // Stopped at a terminal event
+    Terminated,
+    // The step size collapsed; the message says why
+    Failed(String),
+}
+
+// Outcome of nr_solve_ivp
+pub struct OdeResult {
+    // Time points and the states there
+    pub t: Vec<f64>,
+    pub y: Vec<Array<f64>>,
+    // Continuous solution, when dense output was requested
+    pub sol: Option<OdeSolution>,
+    // For each event, the times of its zeros and the states there
+    pub t_events: Vec<Vec<f64>>,
+    pub y_events: Vec<Vec<Array<f64>>>,
+    // Evaluations of f and of the Jacobian, and LU decompositions
+    pub nfev: usize,
+    pub njev: usize,
+    pub nlu: usize,
+    pub status: IvpStatus,
+}
+
+// The right-hand side with its evaluation counters and step size settings
+struct Problem<'f> {
+    fun: &'f StateFn<'f, Array<f64>>,
+    jac: Option<&'f StateFn<'f, Array<f64>>>,
+    n: usize,
+    t_bound: f64,
+    direction: f64,
+    rtol: f64,
+    atol: f64,
+    max_step: f64,
+    nfev: Cell<usize>,
+    njev: Cell<usize>,
+    nlu: Cell<usize>,
+}
+
+impl Problem<'_> {
+    fn state(&self, y: &[f64]) -> Array<f64> {
+        Array::from_vec(y.to_vec(), &[self.n as i32])
+    }
+
+    fn eval(&self, t: f64, y: &[f64]) -> Vec<f64> {
+        self.nfev.set(self.nfev.get() + 1);
+        let f = (self.fun)(t, &self.state(y));
+        if f.totalsize != self.n {
+            panic!("solve_ivp: fun returned {} values for a state of length {}", f.totalsize, self.n);
+        }
+        f.data
+    }
+
+    // The analytic Jacobian as a row-major vector, if one was given
+    fn eval_jac(&self, t: f64, y: &[f64]) -> Option<Vec<f64>> {
+        let jac = self.jac?;
+        let j = jac(t, &self.state(y));
+        if j.shape != [self.n as i32, self.n as i32] {
+            panic!("solve_ivp: jac returned shape {:?} for a state of length {}", j.shape, self.n);
+        }
+        Some(j.data)
+    }
+}
+
+// A single-step integrator advancing (t, y) towards the problem's t_bound
+trait Stepper {
+    // Take one accepted step, or explain why no step is possible
+    fn step(&mut self) -> Result<(), String>;
+    fn t(&self) -> f64;
+    fn t_old(&self) -> f64;
+    fn y(&self) -> &[f64];
+    // Interpolant over the last step
+    fn dense_output(&self) -> Interpolant;
+}
+
+// Root-mean-square norm used by all error and convergence tests
+fn rms_norm(v: &[f64]) -> f64 {
+    (v.iter().map(|x| x * x).sum::<f64>() / v.len() as f64).sqrt()
+}
+
+// Smallest step that still changes t in floating point, with a margin
+fn min_step(t: f64, direction: f64) -> f64 {
+    let next = if direction > 0.0 { t.next_up() } else { t.next_down() };
+    10.0 * (next - t).abs()
+}
+
+// Hairer, Norsett and Wanner's starting step for a method whose error
+// estimate has the given order; f0 = f(t0, y0)
+fn initial_step(p: &Problem, t0: f64, y0: &[f64], f0: &[f64], order: usize) -> f64 {
+    let interval = (p.t_bound - t0).abs();
+    if interval == 0.0 {
+        return 0.0;
+    }
+    let scale: Vec<f64> = y0.iter().map(|y| p.atol + y.abs() * p.rtol).collect();
+    let scaled = |v: &[f64]| rms_norm(&v.iter().zip(&scale).map(|(x, s)| x / s).collect::<Vec<_>>());
+    let (d0, d1) = (scaled(y0), scaled(f0));
+    let h0 = if d0 < 1e-5 || d1 < 1e-5 { 1e-6 } else { 0.01 * d0 / d1 }.min(interval);
+
+    let y1: Vec<f64> = y0.iter().zip(f0).map(|(y, f)| y + h0 * p.direction * f).collect();
+    let f1 = p.eval(t0 + h0 * p.direction, &y1);
+    let d2 = scaled(&f1.iter().zip(f0).map(|(a, b)| a - b).collect::<Vec<_>>()) / h0;
+    let h1 = if d1 <= 1e-15 && d2 <= 1e-15 { (h0 * 1e-3).max(1e-6) } else { (0.01 / d1.max(d2)).powf(1.0 / (order + 1) as f64) };
+    (100.0 * h0).min(h1).min(interval).min(p.max_step)
+}
+
+// Zero of g between a and b by bisection refined with secant steps
+// (Illinois variant), to within a few ulps. ga and gb are the values taken
+// for g at the ends; they must not have the same strict sign, and a zero
+// at either end is returned as the root.
+fn event_root<G: Fn(f64) -> f64>(g: G, (a, ga): (f64, f64), (b, gb): (f64, f64)) -> f64 {
+    if ga == 0.0 {
+        return a;
+    }
+    if gb == 0.0 {
+        return b;
+    }
+    let (mut a, mut b, mut ga, mut gb) = (a, b, ga, gb);
+    let mut side = 0;
+    for _ in 0..200 {
+        if (b - a).abs() <= 4.0 * f64::EPSILON * a.abs().max(b.abs()) {
+            break;
+        }
+        let mut c = (a * gb - b * ga) / (gb - ga);
+        if !(c - a.min(b) > 0.0 && a.max(b) - c > 0.0) {
+            c = 0.5 * (a + b);
+        }
+        let gc = g(c);
+        if gc == 0.0 {
+            return c;
+        }
+        if (gc > 0.0) == (gb > 0.0) {
+            b = c;
+            gb = gc;
+            if side == -1 {
+                ga *= 0.5;
+            }
+            side = -1;
+        } else {
+            a = c;
+            ga = gc;
+            if side == 1 {
+                gb *= 0.5;
+            }
+            side = 1;
+        }
+    }
+    0.5 * (a + b)
+}
+
+// Solve dy/dt = fun(t, y) from t_span.0 to t_span.1 starting at y0, which
+// may run backwards in time (scipy.integrate.solve_ivp). fun maps a 1-D
+// state to its derivative of the same length.
+pub fn nr_solve_ivp<'a, F>(fun: F, t_span: (f64, f64), y0: &Array<f64>, method: OdeMethod, options: IvpOptions<'a>) -> OdeResult
+where
+    F: Fn(f64, &Array<f64>) -> Array<f64>,
+{
+    let (t0, t1) = t_span;
+    if y0.ndim != 1 {
+        panic!("solve_ivp: y0 must be 1-D, got shape {:?}", y0.shape);
+    }
+    if !t0.is_finite() || !t1.is_finite() {
+        panic!("solve_ivp: t_span must be finite, got {:?}", t_span);
+    }
+    if !(options.rtol > 0.0 && options.atol >= 0.0) {
+        panic!("solve_ivp: rtol must be positive and atol non-negative");
+    }
+    if options.max_step.is_nan() || options.max_step <= 0.0 {
+        panic!("solve_ivp: max_step must be positive");
+    }
+    let direction = if t1 >= t0 { 1.0 } else { -1.0 };
+    if let Some(t_eval) = &options.t_eval {
+        if t_eval.iter().any(|&t| (t - t0) * direction < 0.0 || (t - t1) * direction > 0.0) {
+            panic!("solve_ivp: values in t_eval must lie within t_span");
+        }
+        if t_eval.windows(2).any(|w| (w[1] - w[0]) * direction <= 0.0) {
+            panic!("solve_ivp: t_eval must be sorted in the direction of integration");
+        }
+    }
+
+    let problem = Problem {
+        fun: &fun,
+        jac: options.jac.as_deref(),
+        n: y0.totalsize,
+        t_bound: t1,
+        direction,
+        rtol: options.rtol.max(100.0 * f64::EPSILON),
+        atol: options.atol,
+        max_step: options.max_step,
+        nfev: Cell::new(0),
+        njev: Cell::new(0),
+        nlu: Cell::new(0),
+    };
+    let y0 = y0.data.clone();
+    let mut solver: Box<dyn Stepper + '_> = match method {
+        OdeMethod::Rk45 => Box::new(RungeKutta::new(&problem, RkMethod::Rk45, t0, y0.clone(), options.first_step)),
+        OdeMethod::Dop853 => Box::new(RungeKutta::new(&problem, RkMethod::Dop853, t0, y0.clone(), options.first_step)),
+        OdeMethod::Bdf => Box::new(Bdf::new(&problem, t0, y0.clone(), options.first_step)),
+    };
+
+    let events = &options.events;
+    let event_values = |t: f64, y: &[f64]| -> Vec<f64> { events.iter().map(|e| (e.fun)(t, &problem.state(y))).collect() };
+    let mut g = event_values(t0, &y0);
+    let mut t_events = vec![Vec::new(); events.len()];
+    let mut y_events: Vec<Vec<Array<f64>>> = events.iter().map(|_| Vec::new()).collect();
+
+    let (mut ts, mut ys) = (Vec::new(), Vec::new());
+    let mut t_eval_i = 0;
+    if options.t_eval.is_none() {
+        ts.push(t0);
+        ys.push(y0.clone());
+    }
+    let mut step_ts = vec![t0];
+    let mut interpolants = Vec::new();
+
+    let mut status = if t0 == t1 { Some(IvpStatus::Finished) } else { None };
+    while status.is_none() {
+        if let Err(message) = solver.step() {
+            status = Some(IvpStatus::Failed(message));
+            break;
+        }
+        let (t_old, mut t) = (solver.t_old(), solver.t());
+        let mut y = solver.y().to_vec();
+        if direction * (t - t1) >= 0.0 {
+            status = Some(IvpStatus::Finished);
+        }
+        // The interpolant over this step, built on first use
+        let mut sol: Option<Interpolant> = None;
+
+        if !events.is_empty() {
+            let g_new = event_values(t, &y);
+            let mut found: Vec<(usize, f64)> = Vec::new();
+            for (i, e) in events.iter().enumerate() {
+                // A zero at the end of a step belongs to that step, so after
+                // the first step a zero at t_old was already seen
+                if g[i] == 0.0 && t_old != t0 {
+                    continue;
+                }
+                let up = g[i] <= 0.0 && g_new[i] >= 0.0;
+                let down = g[i] >= 0.0 && g_new[i] <= 0.0;
+                if (up && e.direction > 0.0) || (down && e.direction < 0.0) || ((up || down) && e.direction == 0.0) {
+                    // Bracket with the same step values the crossing was
+                    // detected on; the interpolant, which may differ from them
+                    // by rounding, is only evaluated inside the step
+                    let interp = sol.get_or_insert_with(|| solver.dense_output());
+                    let root = event_root(|s| (e.fun)(s, &problem.state(&interp.eval(s))), (t_old, g[i]), (t, g_new[i]));
+                    found.push((i, root));
+                }
+            }
+            // Report zeros in time order, up to and including the first
+            // terminal one
+            found.sort_by(|a, b| (direction * a.1).total_cmp(&(direction * b.1)));
+            if let Some(k) = found.iter().position(|&(i, _)| events[i].terminal) {
+                found.truncate(k + 1);
+                status = Some(IvpStatus::Terminated);
+                t = found[k].1;
+            }
+            for &(i, root) in &found {
+                let interp = sol.as_ref().unwrap();
+                t_events[i].push(root);
+                y_events[i].push(problem.state(&interp.eval(root)));
+            }
+            if status == Some(IvpStatus::Terminated) {
+                y = sol.as_ref().unwrap().eval(t);
+            }
+            g = g_new;
+        }
+
+        match &options.t_eval {
+            None => {
+                ts.push(t);
+                ys.push(y);
+            }
+            Some(t_eval) => {
+                while t_eval_i < t_eval.len() && direction * (t_eval[t_eval_i] - t) <= 0.0 {
+                    let interp = sol.get_or_insert_with(|| solver.dense_output());
+                    ts.push(t_eval[t_eval_i]);
+                    ys.push(interp.eval(t_eval[t_eval_i]));
+                    t_eval_i += 1;
+                }
+            }
+        }
+        step_ts.push(t);
+        if options.dense_output {
+            interpolants.push(sol.unwrap_or_else(|| solver.dense_output()));
+        }
+    }
+
+    OdeResult {
+        t: ts,
+        y: ys.iter().map(|y| problem.state(y)).collect(),
+        sol: if options.dense_output && !interpolants.is_empty() { Some(OdeSolution::new(step_ts, interpolants)) } else { None },
+        t_events,
+        y_events,
+        nfev: problem.nfev.get(),
+        njev: problem.njev.get(),
+        nlu: problem.nlu.get(),
+        status: status.unwrap(),
+    }
+}
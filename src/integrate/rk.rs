@@ -0,0 +1,271 @@
+use super::dense::Interpolant;
+use super::{initial_step, min_step, rms_norm, Problem, Stepper};
+
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 10.0;
+
+// Dormand and Prince's 5(4) pair. Rows 1-5 of A are the stages and row 6
+// the solution weights, so stage 6 is f(t + h, y_new).
+const RK45_C: [f64; 6] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0];
+const RK45_A: [&[f64]; 7] = [
+    &[],
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+    &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+    &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+// Difference between the 5th and 4th order solutions
+const RK45_E: [f64; 7] = [-71.0 / 57600.0, 0.0, 71.0 / 16695.0, -71.0 / 1920.0, 17253.0 / 339200.0, -22.0 / 525.0, 1.0 / 40.0];
+// Shampine's 4th-order dense output: coefficient of x^(k + 1) per stage
+const RK45_P: [[f64; 4]; 7] = [
+    [1.0, -8048581381.0 / 2820520608.0, 8663915743.0 / 2820520608.0, -12715105075.0 / 11282082432.0],
+    [0.0, 0.0, 0.0, 0.0],
+    [0.0, 131558114200.0 / 32700410799.0, -68118460800.0 / 10900136933.0, 87487479700.0 / 32700410799.0],
+    [0.0, -1754552775.0 / 470086768.0, 14199869525.0 / 1410260304.0, -10690763975.0 / 1880347072.0],
+    [0.0, 127303824393.0 / 49829197408.0, -318862633887.0 / 49829197408.0, 701980252875.0 / 199316789632.0],
+    [0.0, -282668133.0 / 205662961.0, 2019193451.0 / 616988883.0, -1453857185.0 / 822651844.0],
+    [0.0, 40617522.0 / 29380423.0, -110615467.0 / 29380423.0, 69997945.0 / 29380423.0],
+];
+
+// Dormand and Prince's 8(5,3) pair as in Hairer's DOP853. Rows 1-11 of A
+// are the stages, row 12 the solution weights (so stage 12 is f(t + h,
+// y_new)) and rows 13-15 the extra stages of the 7th-order dense output.
+const DOP853_C: [f64; 16] = [
+    0.0,
+    0.05260015195876773,
+    0.0789002279381516,
+    0.1183503419072274,
+    0.2816496580927726,
+    0.3333333333333333,
+    0.25,
+    0.3076923076923077,
+    0.6512820512820513,
+    0.6,
+    0.8571428571428571,
+    1.0,
+    1.0,
+    0.1,
+    0.2,
+    0.7777777777777778,
+];
+const DOP853_A: [&[f64]; 16] = [
+    &[],
+    &[0.05260015195876773],
+    &[0.0197250569845379, 0.0591751709536137],
+    &[0.02958758547680685, 0.0, 0.08876275643042054],
+    &[0.2413651341592667, 0.0, -0.8845494793282861, 0.924834003261792],
+    &[0.037037037037037035, 0.0, 0.0, 0.17082860872947386, 0.12546768756682242],
+    &[0.037109375, 0.0, 0.0, 0.17025221101954405, 0.06021653898045596, -0.017578125],
+    &[0.03709200011850479, 0.0, 0.0, 0.17038392571223998, 0.10726203044637328, -0.015319437748624402, 0.008273789163814023],
+    &[0.6241109587160757, 0.0, 0.0, -3.3608926294469414, -0.868219346841726, 27.59209969944671, 20.154067550477894, -43.48988418106996],
+    &[0.47766253643826434, 0.0, 0.0, -2.4881146199716677, -0.590290826836843, 21.230051448181193, 15.279233632882423, -33.28821096898486, -0.020331201708508627],
+    &[-0.9371424300859873, 0.0, 0.0, 5.186372428844064, 1.0914373489967295, -8.149787010746927, -18.52006565999696, 22.739487099350505, 2.4936055526796523, -3.0467644718982196],
+    &[2.273310147516538, 0.0, 0.0, -10.53449546673725, -2.0008720582248625, -17.9589318631188, 27.94888452941996, -2.8589982771350235, -8.87285693353063, 12.360567175794303, 0.6433927460157636],
+    &[0.054293734116568765, 0.0, 0.0, 0.0, 0.0, 4.450312892752409, 1.8915178993145003, -5.801203960010585, 0.3111643669578199, -0.1521609496625161, 0.20136540080403034, 0.04471061572777259],
+    &[0.056167502283047954, 0.0, 0.0, 0.0, 0.0, 0.0, 0.25350021021662483, -0.2462390374708025, -0.12419142326381637, 0.15329179827876568, 0.00820105229563469, 0.007567897660545699, -0.008298],
+    &[0.03183464816350214, 0.0, 0.0, 0.0, 0.0, 0.028300909672366776, 0.053541988307438566, -0.05492374857139099, 0.0, 0.0, -0.00010834732869724932, 0.0003825710908356584, -0.00034046500868740456, 0.1413124436746325],
+    &[-0.42889630158379194, 0.0, 0.0, 0.0, 0.0, -4.697621415361164, 7.683421196062599, 4.06898981839711, 0.3567271874552811, 0.0, 0.0, 0.0, -0.0013990241651590145, 2.9475147891527724, -9.15095847217987],
+];
+// Weights of the 5th and 3rd order error estimates
+const DOP853_E5: [f64; 13] = [0.01312004499419488, 0.0, 0.0, 0.0, 0.0, -1.2251564463762044, -0.4957589496572502, 1.6643771824549864, -0.35032884874997366, 0.3341791187130175, 0.08192320648511571, -0.022355307863886294, 0.0];
+const DOP853_E3: [f64; 13] = [-0.18980075407240762, 0.0, 0.0, 0.0, 0.0, 4.450312892752409, 1.8915178993145003, -5.801203960010585, -0.4226823213237919, -0.1521609496625161, 0.20136540080403034, 0.02265179219836082, 0.0];
+// Dense output coefficients applied to all 16 stages
+const DOP853_D: [[f64; 16]; 4] = [
+    [-8.428938276109013, 0.0, 0.0, 0.0, 0.0, 0.5667149535193777, -3.0689499459498917, 2.38466765651207, 2.117034582445028, -0.871391583777973, 2.2404374302607883, 0.6315787787694688, -0.08899033645133331, 18.148505520854727, -9.194632392478356, -4.436036387594894],
+    [10.427508642579134, 0.0, 0.0, 0.0, 0.0, 242.28349177525817, 165.20045171727028, -374.5467547226902, -22.113666853125306, 7.733432668472264, -30.674084731089398, -9.332130526430229, 15.697238121770845, -31.139403219565178, -9.35292435884448, 35.81684148639408],
+    [19.985053242002433, 0.0, 0.0, 0.0, 0.0, -387.0373087493518, -189.17813819516758, 527.8081592054236, -11.57390253995963, 6.8812326946963, -1.0006050966910838, 0.7777137798053443, -2.778205752353508, -60.19669523126412, 84.32040550667716, 11.99229113618279],
+    [-25.69393346270375, 0.0, 0.0, 0.0, 0.0, -154.18974869023643, -231.5293791760455, 357.6391179106141, 93.40532418362432, -37.45832313645163, 104.0996495089623, 29.8402934266605, -43.53345659001114, 96.32455395918828, -39.17726167561544, -149.72683625798564],
+];
+
+// Explicit embedded Runge-Kutta pairs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum RkMethod {
+    Rk45,
+    Dop853,
+}
+
+impl RkMethod {
+    fn stages(self) -> usize {
+        match self {
+            RkMethod::Rk45 => 6,
+            RkMethod::Dop853 => 12,
+        }
+    }
+
+    // Order of the error estimate, which drives the step size control
+    fn error_order(self) -> usize {
+        match self {
+            RkMethod::Rk45 => 4,
+            RkMethod::Dop853 => 7,
+        }
+    }
+
+    fn c(self) -> &'static [f64] {
+        match self {
+            RkMethod::Rk45 => &RK45_C,
+            RkMethod::Dop853 => &DOP853_C,
+        }
+    }
+
+    fn a(self, row: usize) -> &'static [f64] {
+        match self {
+            RkMethod::Rk45 => RK45_A[row],
+            RkMethod::Dop853 => DOP853_A[row],
+        }
+    }
+}
+
+// y + h * sum_j a[j] k[j]
+fn combine(y: &[f64], h: f64, a: &[f64], k: &[Vec<f64>]) -> Vec<f64> {
+    let mut out = y.to_vec();
+    for (&aj, kj) in a.iter().zip(k) {
+        if aj != 0.0 {
+            for (o, v) in out.iter_mut().zip(kj) {
+                *o += h * aj * v;
+            }
+        }
+    }
+    out
+}
+
+// sum_j w[j] k[j]
+fn weighted(w: &[f64], k: &[Vec<f64>]) -> Vec<f64> {
+    combine(&vec![0.0; k[0].len()], 1.0, w, k)
+}
+
+pub(super) struct RungeKutta<'p, 'f> {
+    problem: &'p Problem<'f>,
+    method: RkMethod,
+    t: f64,
+    y: Vec<f64>,
+    f: Vec<f64>,
+    t_old: f64,
+    y_old: Vec<f64>,
+    h_abs: f64,
+    // Stage derivatives of the last step, the last one being f(t, y)
+    k: Vec<Vec<f64>>,
+}
+
+impl<'p, 'f> RungeKutta<'p, 'f> {
+    pub(super) fn new(problem: &'p Problem<'f>, method: RkMethod, t0: f64, y0: Vec<f64>, first_step: Option<f64>) -> RungeKutta<'p, 'f> {
+        let f = problem.eval(t0, &y0);
+        let h_abs = first_step.unwrap_or_else(|| initial_step(problem, t0, &y0, &f, method.error_order()));
+        RungeKutta { problem, method, t: t0, y_old: y0.clone(), y: y0, f, t_old: t0, h_abs, k: Vec::new() }
+    }
+
+    // One step of size h from (t, y); fills the stages and returns y_new
+    fn rk_step(&mut self, h: f64) -> Vec<f64> {
+        let stages = self.method.stages();
+        let c = self.method.c();
+        let mut k = vec![self.f.clone()];
+        for (s, cs) in c.iter().enumerate().take(stages).skip(1) {
+            let ys = combine(&self.y, h, self.method.a(s), &k);
+            k.push(self.problem.eval(self.t + cs * h, &ys));
+        }
+        let y_new = combine(&self.y, h, self.method.a(stages), &k);
+        k.push(self.problem.eval(self.t + h, &y_new));
+        self.k = k;
+        y_new
+    }
+
+    fn error_norm(&self, h: f64, scale: &[f64]) -> f64 {
+        match self.method {
+            RkMethod::Rk45 => {
+                let err: Vec<f64> = weighted(&RK45_E, &self.k).iter().zip(scale).map(|(e, s)| h * e / s).collect();
+                rms_norm(&err)
+            }
+            RkMethod::Dop853 => {
+                // Hairer's blend of the 5th and 3rd order estimates, which
+                // stays reliable when the 5th order one is accidentally small
+                let sq = |w: &[f64]| weighted(w, &self.k).iter().zip(scale).map(|(e, s)| (e / s).powi(2)).sum::<f64>();
+                let (err5, err3) = (sq(&DOP853_E5), sq(&DOP853_E3));
+                if err5 == 0.0 && err3 == 0.0 {
+                    return 0.0;
+                }
+                h.abs() * err5 / ((err5 + 0.01 * err3) * scale.len() as f64).sqrt()
+            }
+        }
+    }
+}
+
+impl Stepper for RungeKutta<'_, '_> {
+    fn step(&mut self) -> Result<(), String> {
+        let p = self.problem;
+        let min_step = min_step(self.t, p.direction);
+        let mut h_abs = self.h_abs.min(p.max_step).max(min_step);
+        let exponent = -1.0 / (self.method.error_order() + 1) as f64;
+        let mut rejected = false;
+        loop {
+            if h_abs < min_step {
+                return Err("required step size is less than spacing between numbers".to_string());
+            }
+            let mut t_new = self.t + h_abs * p.direction;
+            if p.direction * (t_new - p.t_bound) > 0.0 {
+                t_new = p.t_bound;
+            }
+            let h = t_new - self.t;
+            h_abs = h.abs();
+
+            let y_new = self.rk_step(h);
+            let scale: Vec<f64> = self.y.iter().zip(&y_new).map(|(a, b)| p.atol + a.abs().max(b.abs()) * p.rtol).collect();
+            let error_norm = self.error_norm(h, &scale);
+            if error_norm < 1.0 {
+                let mut factor = if error_norm == 0.0 { MAX_FACTOR } else { MAX_FACTOR.min(SAFETY * error_norm.powf(exponent)) };
+                if rejected {
+                    factor = factor.min(1.0);
+                }
+                self.h_abs = h_abs * factor;
+                self.t_old = self.t;
+                self.y_old = std::mem::replace(&mut self.y, y_new);
+                self.t = t_new;
+                self.f = self.k[self.k.len() - 1].clone();
+                return Ok(());
+            }
+            h_abs *= MIN_FACTOR.max(SAFETY * error_norm.powf(exponent));
+            rejected = true;
+        }
+    }
+
+    fn t(&self) -> f64 {
+        self.t
+    }
+
+    fn t_old(&self) -> f64 {
+        self.t_old
+    }
+
+    fn y(&self) -> &[f64] {
+        &self.y
+    }
+
+    fn dense_output(&self) -> Interpolant {
+        let h = self.t - self.t_old;
+        match self.method {
+            RkMethod::Rk45 => {
+                let q = (0..4).map(|j| weighted(&RK45_P.map(|row| row[j]), &self.k)).collect();
+                Interpolant::Power { t_old: self.t_old, h, y_old: self.y_old.clone(), q }
+            }
+            RkMethod::Dop853 => {
+                let mut k = self.k.clone();
+                for s in 13..16 {
+                    let ys = combine(&self.y_old, h, DOP853_A[s], &k);
+                    k.push(self.problem.eval(self.t_old + DOP853_C[s] * h, &ys));
+                }
+                let f_old = &k[0];
+                let dy: Vec<f64> = self.y.iter().zip(&self.y_old).map(|(a, b)| a - b).collect();
+                let mut f = vec![
+                    dy.clone(),
+                    f_old.iter().zip(&dy).map(|(fo, d)| h * fo - d).collect(),
+                    dy.iter().zip(f_old).zip(&self.f).map(|((d, fo), fn_)| 2.0 * d - h * (fn_ + fo)).collect(),
+                ];
+                for row in &DOP853_D {
+                    f.push(weighted(row, &k).iter().map(|v| h * v).collect());
+                }
+                Interpolant::Nested { t_old: self.t_old, h, y_old: self.y_old.clone(), f }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::array::Array;
+
+// How RegularGridInterpolator combines the surrounding grid values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridMethod {
+    // Multilinear blend of the 2^ndim corners of the enclosing cell
+    Linear,
+    // Value at the closest grid point, the lower one on a tie
+    Nearest,
+}
+
+// Interpolation on a rectilinear grid in any number of dimensions
+// (scipy.interpolate.RegularGridInterpolator). values[i0, i1, ...] is the
+// sample at (points[0][i0], points[1][i1], ...).
+#[derive(Debug)]
+pub struct RegularGridInterpolator {
+    points: Vec<Vec<f64>>,
+    values: Array<f64>,
+    // Element (not byte) strides of values
+    strides: Vec<usize>,
+    method: GridMethod,
+    fill_value: Option<f64>,
+}
+
+impl RegularGridInterpolator {
+    // Query points outside the grid take fill_value, or are extrapolated
+    // from the edge cells when it is None
+    pub fn new(points: Vec<Vec<f64>>, values: Array<f64>, method: GridMethod, fill_value: Option<f64>) -> RegularGridInterpolator {
+        if points.len() != values.ndim {
+            panic!("RegularGridInterpolator: {} coordinate vectors for {}-D values", points.len(), values.ndim);
+        }
+        for (d, p) in points.iter().enumerate() {
+            if p.len() != values.shape[d] as usize {
+                panic!("RegularGridInterpolator: {} points in dimension {} but values have shape {:?}", p.len(), d, values.shape);
+            }
+            if p.windows(2).any(|w| w[0] >= w[1] || w[0].is_nan()) {
+                panic!("RegularGridInterpolator: points in dimension {} must be strictly increasing", d);
+            }
+        }
+        let strides = values.strides.iter().map(|&s| s as usize / values.itemsize).collect();
+        RegularGridInterpolator { points, values, strides, method, fill_value }
+    }
+
+    // Interpolated values at the query points xi of shape [..., ndim]; the
+    // result has shape [...]. A 1-D xi is a single point.
+    pub fn eval(&self, xi: &Array<f64>) -> Array<f64> {
+        let ndim = self.points.len();
+        if xi.shape[xi.ndim - 1] as usize != ndim {
+            panic!("RegularGridInterpolator: query points of shape {:?} do not have {} coordinates", xi.shape, ndim);
+        }
+        let data = xi.data.chunks(ndim).map(|q| self.eval_point(q)).collect();
+        let shape = if xi.ndim == 1 { vec![1] } else { xi.shape[..xi.ndim - 1].to_vec() };
+        Array::from_vec(data, &shape)
+    }
+
+    fn eval_point(&self, q: &[f64]) -> f64 {
+        if q.iter().any(|v| v.is_nan()) {
+            return f64::NAN;
+        }
+        if let Some(fill) = self.fill_value {
+            let outside = q.iter().zip(&self.points).any(|(&v, p)| v < p[0] || v > p[p.len() - 1]);
+            if outside {
+                return fill;
+            }
+        }
+
+        // Per dimension, the lower corner of the enclosing cell (edge cells
+        // cover everything beyond the grid) and the fractional offset in it
+        let cell: Vec<(usize, f64)> = q
+            .iter()
+            .zip(&self.points)
+            .map(|(&v, p)| {
+                if p.len() == 1 {
+                    return (0, 0.0);
+                }
+                let i = p[1..p.len() - 1].partition_point(|&b| b <= v);
+                (i, (v - p[i]) / (p[i + 1] - p[i]))
+            })
+            .collect();
+
+        match self.method {
+            GridMethod::Nearest => {
+                let offset = cell
+                    .iter()
+                    .zip(&self.strides)
+                    .map(|(&(i, t), &s)| if t > 0.5 { (i + 1) * s } else { i * s })
+                    .sum::<usize>();
+                self.values.data[offset]
+            }
+            GridMethod::Linear => {
+                // Only dimensions with more than one point have a second corner
+                let spans: Vec<usize> = self.points.iter().enumerate().filter(|(_, p)| p.len() > 1).map(|(d, _)| d).collect();
+                let base: usize = cell.iter().zip(&self.strides).map(|(&(i, _), &s)| i * s).sum();
+                let mut total = 0.0;
+                for corner in 0..1usize << spans.len() {
+                    let mut weight = 1.0;
+                    let mut offset = base;
+                    for (bit, &d) in spans.iter().enumerate() {
+                        let t = cell[d].1;
+                        if corner >> bit & 1 == 1 {
+                            weight *= t;
+                            offset += self.strides[d];
+                        } else {
+                            weight *= 1.0 - t;
+                        }
+                    }
+                    total += weight * self.values.data[offset];
+                }
+                total
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+// Interpolation (np.interp and scipy.interpolate).
+//
+// CubicSpline, PchipInterpolator and Akima1DInterpolator all build a
+// PiecewiseCubic and dereference to it for evaluation, derivatives and
+// integrals, the way their scipy counterparts subclass PPoly. They differ
+// only in how the slopes at the data points are chosen.
+mod grid;
+mod spline;
+
+pub use grid::{GridMethod, RegularGridInterpolator};
+pub use spline::{Akima1DInterpolator, CubicSpline, PchipInterpolator, PiecewiseCubic, SplineBoundary};
+
+use crate::array::Array;
+use num_traits::Float;
+
+// Piecewise linear interpolation of the points (xp, fp) at every element of
+// x (np.interp). xp must be increasing. Points below xp[0] take `left`
+// (default fp[0]) and points above the last xp take `right` (default the
+// last fp).
+pub fn nr_interp<T: Float>(x: &Array<T>, xp: &Array<T>, fp: &Array<T>, left: Option<T>, right: Option<T>) -> Array<T> {
+    if xp.ndim != 1 || fp.ndim != 1 || xp.totalsize != fp.totalsize {
+        panic!("interp: xp and fp must be 1-D arrays of the same length, got {:?} and {:?}", xp.shape, fp.shape);
+    }
+    let (xp, fp) = (&xp.data, &fp.data);
+    let n = xp.len();
+    let left = left.unwrap_or(fp[0]);
+    let right = right.unwrap_or(fp[n - 1]);
+    let data = x
+        .data
+        .iter()
+        .map(|&v| {
+            if v.is_nan() {
+                v
+            } else if v < xp[0] {
+                left
+            } else if v > xp[n - 1] {
+                right
+            } else if v == xp[n - 1] {
+                fp[n - 1]
+            } else {
+                // xp[i] <= v < xp[i + 1]
+                let i = xp.partition_point(|&b| b <= v) - 1;
+                let t = (v - xp[i]) / (xp[i + 1] - xp[i]);
+                fp[i] + t * (fp[i + 1] - fp[i])
+            }
+        })
+        .collect();
+    Array::from_vec(data, &x.shape)
+}
@@ -0,0 +1,300 @@
+use crate::array::Array;
+use std::ops::Deref;
+
+// Piecewise cubic on breakpoints x[0] < ... < x[n-1]. On [x[i], x[i+1]] it
+// is sum_k coef[i][k] (t - x[i])^k; beyond the ends the first and last
+// pieces are extended (scipy.interpolate.PPoly).
+#[derive(Debug, Clone)]
+pub struct PiecewiseCubic {
+    x: Vec<f64>,
+    coef: Vec<[f64; 4]>,
+}
+
+impl PiecewiseCubic {
+    // Cubic Hermite pieces matching values y and slopes d at every breakpoint
+    fn from_slopes(x: Vec<f64>, y: &[f64], d: &[f64]) -> PiecewiseCubic {
+        let coef = (0..x.len() - 1)
+            .map(|i| {
+                let h = x[i + 1] - x[i];
+                let s = (y[i + 1] - y[i]) / h;
+                [y[i], d[i], (3.0 * s - 2.0 * d[i] - d[i + 1]) / h, (d[i] + d[i + 1] - 2.0 * s) / (h * h)]
+            })
+            .collect();
+        PiecewiseCubic { x, coef }
+    }
+
+    pub fn breakpoints(&self) -> &[f64] {
+        &self.x
+    }
+
+    // Piece that covers t, with the end pieces covering everything beyond
+    fn piece(&self, t: f64) -> usize {
+        let n = self.x.len();
+        self.x[1..n - 1].partition_point(|&b| b <= t)
+    }
+
+    // nu-th derivative at t
+    pub fn derivative(&self, t: f64, nu: usize) -> f64 {
+        if t.is_nan() {
+            return f64::NAN;
+        }
+        let i = self.piece(t);
+        let h = t - self.x[i];
+        let c = &self.coef[i];
+        match nu {
+            0 => c[0] + h * (c[1] + h * (c[2] + h * c[3])),
+            1 => c[1] + h * (2.0 * c[2] + h * 3.0 * c[3]),
+            2 => 2.0 * c[2] + 6.0 * h * c[3],
+            3 => 6.0 * c[3],
+            _ => 0.0,
+        }
+    }
+
+    // Value at t
+    pub fn eval(&self, t: f64) -> f64 {
+        self.derivative(t, 0)
+    }
+
+    // Value at every element of t
+    pub fn eval_array(&self, t: &Array<f64>) -> Array<f64> {
+        Array::from_vec(t.data.iter().map(|&v| self.eval(v)).collect(), &t.shape)
+    }
+
+    // Antiderivative that vanishes at x[0]
+    fn antiderivative(&self, t: f64) -> f64 {
+        let i = self.piece(t);
+        let partial = |i: usize, h: f64| {
+            let c = &self.coef[i];
+            h * (c[0] + h * (c[1] / 2.0 + h * (c[2] / 3.0 + h * c[3] / 4.0)))
+        };
+        let whole: f64 = (0..i).map(|j| partial(j, self.x[j + 1] - self.x[j])).sum();
+        whole + partial(i, t - self.x[i])
+    }
+
+    // Definite integral from a to b
+    pub fn integrate(&self, a: f64, b: f64) -> f64 {
+        self.antiderivative(b) - self.antiderivative(a)
+    }
+}
+
+// Breakpoints and values as vectors, panicking on unusable input
+fn knots(x: &Array<f64>, y: &Array<f64>, name: &str) -> (Vec<f64>, Vec<f64>) {
+    if x.ndim != 1 || y.ndim != 1 || x.totalsize != y.totalsize {
+        panic!("{}: x and y must be 1-D arrays of the same length, got {:?} and {:?}", name, x.shape, y.shape);
+    }
+    if x.totalsize < 2 {
+        panic!("{}: at least 2 points are required", name);
+    }
+    if x.data.windows(2).any(|w| w[0] >= w[1] || w[0].is_nan()) {
+        panic!("{}: x must be strictly increasing", name);
+    }
+    (x.data.clone(), y.data.clone())
+}
+
+// Interval widths and secant slopes
+fn secants(x: &[f64], y: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let h: Vec<f64> = x.windows(2).map(|w| w[1] - w[0]).collect();
+    let s = y.windows(2).zip(&h).map(|(w, h)| (w[1] - w[0]) / h).collect();
+    (h, s)
+}
+
+// End conditions of a cubic spline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplineBoundary {
+    // The third derivative is continuous at the second and second-to-last
+    // points, so the first and last two pieces are one cubic each
+    NotAKnot,
+    // Zero second derivative at both ends
+    Natural,
+    // Zero first derivative at both ends
+    Clamped,
+}
+
+// Twice continuously differentiable interpolating cubic spline
+// (scipy.interpolate.CubicSpline)
+#[derive(Debug, Clone)]
+pub struct CubicSpline(PiecewiseCubic);
+
+impl CubicSpline {
+    pub fn new(x: &Array<f64>, y: &Array<f64>, bc: SplineBoundary) -> CubicSpline {
+        let (x, y) = knots(x, y, "CubicSpline");
+        let (h, s) = secants(&x, &y);
+        let n = x.len();
+
+        let d = if n == 2 && bc != SplineBoundary::Clamped {
+            vec![s[0]; 2]
+        } else if n == 3 && bc == SplineBoundary::NotAKnot {
+            // Not-a-knot on three points is the parabola through them
+            let lower = [0.0, h[1], 1.0];
+            let diag = [1.0, 2.0 * (h[0] + h[1]), 1.0];
+            let upper = [1.0, h[0], 0.0];
+            let rhs = [2.0 * s[0], 3.0 * (h[1] * s[0] + h[0] * s[1]), 2.0 * s[1]];
+            solve_tridiagonal(&lower, &diag, &upper, &rhs)
+        } else {
+            // Continuity of the second derivative at interior points:
+            // h[i] d[i-1] + 2 (h[i-1] + h[i]) d[i] + h[i-1] d[i+1]
+            //     = 3 (h[i] s[i-1] + h[i-1] s[i])
+            let mut lower = vec![0.0; n];
+            let mut diag = vec![0.0; n];
+            let mut upper = vec![0.0; n];
+            let mut rhs = vec![0.0; n];
+            for i in 1..n - 1 {
+                lower[i] = h[i];
+                diag[i] = 2.0 * (h[i - 1] + h[i]);
+                upper[i] = h[i - 1];
+                rhs[i] = 3.0 * (h[i] * s[i - 1] + h[i - 1] * s[i]);
+            }
+            match bc {
+                SplineBoundary::Clamped => {
+                    diag[0] = 1.0;
+                    diag[n - 1] = 1.0;
+                }
+                SplineBoundary::Natural => {
+                    diag[0] = 2.0;
+                    upper[0] = 1.0;
+                    rhs[0] = 3.0 * s[0];
+                    lower[n - 1] = 1.0;
+                    diag[n - 1] = 2.0;
+                    rhs[n - 1] = 3.0 * s[n - 2];
+                }
+                SplineBoundary::NotAKnot => {
+                    let w = x[2] - x[0];
+                    diag[0] = h[1];
+                    upper[0] = w;
+                    rhs[0] = ((h[0] + 2.0 * w) * h[1] * s[0] + h[0] * h[0] * s[1]) / w;
+                    let w = x[n - 1] - x[n - 3];
+                    lower[n - 1] = w;
+                    diag[n - 1] = h[n - 3];
+                    rhs[n - 1] = (h[n - 2] * h[n - 2] * s[n - 3] + (2.0 * w + h[n - 2]) * h[n - 3] * s[n - 2]) / w;
+                }
+            }
+            solve_tridiagonal(&lower, &diag, &upper, &rhs)
+        };
+        CubicSpline(PiecewiseCubic::from_slopes(x, &y, &d))
+    }
+}
+
+impl Deref for CubicSpline {
+    type Target = PiecewiseCubic;
+
+    fn deref(&self) -> &PiecewiseCubic {
+        &self.0
+    }
+}
+
+// Thomas algorithm; lower[0] and upper[n-1] are unused
+fn solve_tridiagonal(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &[f64]) -> Vec<f64> {
+    let n = diag.len();
+    let mut c = vec![0.0; n];
+    let mut d = vec![0.0; n];
+    c[0] = upper[0] / diag[0];
+    d[0] = rhs[0] / diag[0];
+    for i in 1..n {
+        let m = diag[i] - lower[i] * c[i - 1];
+        c[i] = upper[i] / m;
+        d[i] = (rhs[i] - lower[i] * d[i - 1]) / m;
+    }
+    for i in (0..n - 1).rev() {
+        d[i] -= c[i] * d[i + 1];
+    }
+    d
+}
+
+// Monotonicity-preserving piecewise cubic Hermite interpolation: the
+// interpolant never overshoots the data (scipy.interpolate.PchipInterpolator)
+#[derive(Debug, Clone)]
+pub struct PchipInterpolator(PiecewiseCubic);
+
+impl PchipInterpolator {
+    pub fn new(x: &Array<f64>, y: &Array<f64>) -> PchipInterpolator {
+        let (x, y) = knots(x, y, "PchipInterpolator");
+        let (h, s) = secants(&x, &y);
+        let n = x.len();
+        if n == 2 {
+            return PchipInterpolator(PiecewiseCubic::from_slopes(x, &y, &[s[0]; 2]));
+        }
+
+        let mut d = vec![0.0; n];
+        for k in 1..n - 1 {
+            // Weighted harmonic mean of the neighbouring secants, or a flat
+            // slope at a local extremum
+            if s[k - 1] * s[k] > 0.0 {
+                let w1 = 2.0 * h[k] + h[k - 1];
+                let w2 = h[k] + 2.0 * h[k - 1];
+                d[k] = (w1 + w2) / (w1 / s[k - 1] + w2 / s[k]);
+            }
+        }
+        d[0] = pchip_end(h[0], h[1], s[0], s[1]);
+        d[n - 1] = pchip_end(h[n - 2], h[n - 3], s[n - 2], s[n - 3]);
+        PchipInterpolator(PiecewiseCubic::from_slopes(x, &y, &d))
+    }
+}
+
+// One-sided three-point end slope, limited to keep the end monotone
+fn pchip_end(h0: f64, h1: f64, s0: f64, s1: f64) -> f64 {
+    // Unlike f64::signum, zero has its own sign here
+    let sign = |v: f64| if v > 0.0 { 1 } else if v < 0.0 { -1 } else { 0 };
+    let d = ((2.0 * h0 + h1) * s0 - h0 * s1) / (h0 + h1);
+    if sign(d) != sign(s0) {
+        0.0
+    } else if sign(s0) != sign(s1) && d.abs() > 3.0 * s0.abs() {
+        3.0 * s0
+    } else {
+        d
+    }
+}
+
+impl Deref for PchipInterpolator {
+    type Target = PiecewiseCubic;
+
+    fn deref(&self) -> &PiecewiseCubic {
+        &self.0
+    }
+}
+
+// Akima's piecewise cubic: slopes from locally weighted secants, which
+// avoids the wiggles a spline shows next to outliers
+// (scipy.interpolate.Akima1DInterpolator)
+#[derive(Debug, Clone)]
+pub struct Akima1DInterpolator(PiecewiseCubic);
+
+impl Akima1DInterpolator {
+    pub fn new(x: &Array<f64>, y: &Array<f64>) -> Akima1DInterpolator {
+        let (x, y) = knots(x, y, "Akima1DInterpolator");
+        let (_, s) = secants(&x, &y);
+        let n = x.len();
+        if n == 2 {
+            return Akima1DInterpolator(PiecewiseCubic::from_slopes(x, &y, &[s[0]; 2]));
+        }
+
+        // Secants extended by two linearly extrapolated ones at each end
+        let mut m = vec![0.0; n + 3];
+        m[2..n + 1].copy_from_slice(&s);
+        m[1] = 2.0 * m[2] - m[3];
+        m[0] = 2.0 * m[1] - m[2];
+        m[n + 1] = 2.0 * m[n] - m[n - 1];
+        m[n + 2] = 2.0 * m[n + 1] - m[n];
+
+        let dm: Vec<f64> = m.windows(2).map(|w| (w[1] - w[0]).abs()).collect();
+        let f12: Vec<f64> = (0..n).map(|i| dm[i + 2] + dm[i]).collect();
+        let big = f12.iter().cloned().fold(0.0, f64::max);
+        let d: Vec<f64> = (0..n)
+            .map(|i| {
+                if f12[i] > 1e-9 * big {
+                    (dm[i + 2] * m[i + 1] + dm[i] * m[i + 2]) / f12[i]
+                } else {
+                    0.5 * (m[i] + m[i + 3])
+                }
+            })
+            .collect();
+        Akima1DInterpolator(PiecewiseCubic::from_slopes(x, &y, &d))
+    }
+}
+
+impl Deref for Akima1DInterpolator {
+    type Target = PiecewiseCubic;
+
+    fn deref(&self) -> &PiecewiseCubic {
+        &self.0
+    }
+}
@@ -1,9 +1,44 @@
 mod array;
 mod ops;
-mod util;
+mod gemm;
+mod product;
+mod einsum;
+mod linalg;
+mod random;
+mod fft;
+mod complex;
+mod convolve;
+mod polynomial;
+mod interpolate;
+mod quadrature;
+mod special;
+pub mod sparse;
+pub mod signal;
+pub mod integrate;
+pub mod optimize;
 
 pub use array::{Array, ArrayIndices, LinearIndices};
 pub use ops::{nr_arange, nr_random, nr_reshape_new, nr_mul, nr_add, nr_matmul};
+pub use product::{nr_dot, nr_vdot, nr_inner, nr_outer, nr_kron, nr_cross, nr_tensordot};
+pub use einsum::nr_einsum;
+pub use random::Generator;
+pub use linalg::{LinalgError, nr_lu, nr_solve, nr_inv, nr_det, nr_slogdet, nr_matrix_rank};
+pub use linalg::{QrMode, LstsqResult, nr_qr, nr_cholesky, nr_cho_solve, nr_lstsq, nr_pinv};
+pub use linalg::{nr_eigh, nr_eigvalsh, nr_eig, nr_eigvals};
+pub use linalg::{SvdResult, nr_svd, nr_svdvals, nr_cond};
+pub use linalg::{NormOrd, nr_norm};
+pub use linalg::{nr_matrix_power, nr_expm, nr_sqrtm, nr_logm};
+pub use sparse::{nr_cg, nr_gmres, nr_bicgstab};
+pub use fft::{FftNorm, nr_fft, nr_ifft, nr_rfft, nr_irfft, nr_fft2, nr_ifft2, nr_fftn, nr_ifftn};
+pub use complex::{Conjugate, nr_complex, nr_real, nr_imag, nr_conj, nr_angle, nr_abs, nr_conj_transpose};
+pub use convolve::{ConvolveMode, Boundary, nr_convolve, nr_correlate, nr_convolve2d, nr_correlate2d};
+pub use polynomial::{Polynomial, OrthoSeries, Basis, nr_polyval, nr_polyfit, nr_roots, nr_polyder, nr_polyint, nr_polymul, nr_polydiv};
+pub use fft::{nr_fftfreq, nr_rfftfreq, nr_fftshift, nr_ifftshift};
+pub use interpolate::{nr_interp, PiecewiseCubic, CubicSpline, SplineBoundary, PchipInterpolator, Akima1DInterpolator};
+pub use interpolate::{RegularGridInterpolator, GridMethod};
+pub use quadrature::{nr_trapezoid, nr_cumulative_trapezoid, nr_simpson, Spacing, nr_gradient, QuadResult, nr_quad};
+pub use special::{nr_gamma, nr_lgamma, nr_digamma, nr_beta, nr_betainc, nr_gammainc, nr_erf, nr_erfc, nr_erfinv};
+pub use special::{nr_j0, nr_j1, nr_y0, nr_y1, nr_i0, nr_expit, nr_logit, nr_xlogy, nr_sinc};
 
 #[cfg(test)]
 mod test {
@@ -79,6 +114,651 @@ mod test {
         assert_eq!(c.data, vec![19, 22, 43, 50]);
     }
 
+    #[test]
+    fn test_matmul_blocked_f64() {
+        // Sizes that are not multiples of the kernel tiles
+        let (m, k, n) = (67, 301, 13);
+        let a = nr_reshape_new(&nr_arange(0.0f64, (m * k) as f64, 1.0), &[m as i32, k as i32], 2);
+        let b = nr_reshape_new(&nr_arange(0.0f64, (k * n) as f64, 1.0), &[k as i32, n as i32], 2);
+        let c = nr_matmul(&a, &b);
+        assert_eq!(c.shape, vec![m as i32, n as i32]);
+        for i in 0..m {
+            for j in 0..n {
+                let expected: f64 = (0..k).map(|p| a.data[i * k + p] * b.data[p * n + j]).sum();
+                assert_eq!(c.data[i * n + j], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matmul_batched() {
+        let a = nr_reshape_new(&nr_arange(1i32, 9, 1), &[2, 2, 2], 3);
+        let b = nr_reshape_new(&nr_arange(1i32, 9, 1), &[2, 2, 2], 3);
+        let c = nr_matmul(&a, &b);
+        assert_eq!(c.shape, vec![2, 2, 2]);
+        assert_eq!(c.data, vec![7, 10, 15, 22, 67, 78, 91, 106]);
+    }
+
+    #[test]
+    fn test_matmul_broadcast_batch() {
+        // (3, 1, 2, 2) @ (4, 2, 2) -> (3, 4, 2, 2)
+        let a = nr_reshape_new(&nr_arange(0i32, 12, 1), &[3, 1, 2, 2], 4);
+        let b = nr_reshape_new(&nr_arange(0i32, 16, 1), &[4, 2, 2], 3);
+        let c = nr_matmul(&a, &b);
+        assert_eq!(c.shape, vec![3, 4, 2, 2]);
+        for i in 0..3 {
+            for j in 0..4 {
+                let am = &a.data[i * 4..i * 4 + 4];
+                let bm = &b.data[j * 4..j * 4 + 4];
+                let expected = [
+                    am[0] * bm[0] + am[1] * bm[2],
+                    am[0] * bm[1] + am[1] * bm[3],
+                    am[2] * bm[0] + am[3] * bm[2],
+                    am[2] * bm[1] + am[3] * bm[3],
+                ];
+                assert_eq!(&c.data[(i * 4 + j) * 4..(i * 4 + j) * 4 + 4], &expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matmul_vectors() {
+        let m = nr_reshape_new(&nr_arange(1i32, 7, 1), &[2, 3], 2); // [[1, 2, 3], [4, 5, 6]]
+        let v = nr_arange(1i32, 4, 1); // [1, 2, 3]
+        let mv = nr_matmul(&m, &v);
+        assert_eq!(mv.shape, vec![2]);
+        assert_eq!(mv.data, vec![14, 32]);
+
+        let w = nr_arange(1i32, 3, 1); // [1, 2]
+        let wm = nr_matmul(&w, &m);
+        assert_eq!(wm.shape, vec![3]);
+        assert_eq!(wm.data, vec![9, 12, 15]);
+
+        let vv = nr_matmul(&v, &v);
+        assert_eq!(vv.shape, vec![1]);
+        assert_eq!(vv.data, vec![14]);
+    }
+
+    #[test]
+    #[should_panic(expected = "batch dimensions")]
+    fn test_matmul_batch_mismatch() {
+        let a = Array::<f32>::nr_create(&[3, 2, 2], 3);
+        let b = Array::<f32>::nr_create(&[4, 2, 2], 3);
+        nr_matmul(&a, &b);
+    }
+
+    // --- product tests ---
+    #[test]
+    fn test_dot_nd() {
+        // dot(a, b)[i, j, k] = sum(a[i, :] * b[j, :, k])
+        let a = nr_reshape_new(&nr_arange(0i32, 6, 1), &[2, 3], 2);
+        let b = nr_reshape_new(&nr_arange(0i32, 24, 1), &[2, 3, 4], 3);
+        let c = nr_dot(&a, &b);
+        assert_eq!(c.shape, vec![2, 2, 4]);
+        for i in 0..2 {
+            for j in 0..2 {
+                for k in 0..4 {
+                    let expected: i32 = (0..3).map(|p| a.data[i * 3 + p] * b.data[j * 12 + p * 4 + k]).sum();
+                    assert_eq!(c.data[i * 8 + j * 4 + k], expected);
+                }
+            }
+        }
+
+        let v = nr_arange(1.0f64, 4.0, 1.0);
+        assert_eq!(nr_dot(&v, &v).data, vec![14.0]);
+        assert_eq!(nr_vdot(&v, &v), 14.0);
+    }
+
+    #[test]
+    fn test_inner_outer() {
+        let a = nr_reshape_new(&nr_arange(1i32, 7, 1), &[2, 3], 2);
+        let b = nr_arange(1i32, 4, 1);
+        let inner = nr_inner(&a, &b);
+        assert_eq!(inner.shape, vec![2]);
+        assert_eq!(inner.data, vec![14, 32]);
+
+        let outer = nr_outer(&nr_arange(1i32, 3, 1), &b);
+        assert_eq!(outer.shape, vec![2, 3]);
+        assert_eq!(outer.data, vec![1, 2, 3, 2, 4, 6]);
+    }
+
+    #[test]
+    fn test_kron() {
+        let a = nr_reshape_new(&nr_arange(1i32, 5, 1), &[2, 2], 2); // [[1, 2], [3, 4]]
+        let b = nr_reshape_new(&nr_arange(0i32, 2, 1), &[1, 2], 2); // [[0, 1]]
+        let c = nr_kron(&a, &b);
+        assert_eq!(c.shape, vec![2, 4]);
+        assert_eq!(c.data, vec![0, 1, 0, 2, 0, 3, 0, 4]);
+    }
+
+    #[test]
+    fn test_cross() {
+        let x = nr_reshape_new(&nr_arange(1.0f64, 7.0, 1.0), &[2, 3], 2);
+        let y = nr_arange(0.0f64, 3.0, 1.0); // broadcast against both rows
+        let c = nr_cross(&x, &y);
+        assert_eq!(c.shape, vec![2, 3]);
+        assert_eq!(c.data, vec![1.0, -2.0, 1.0, 4.0, -8.0, 4.0]);
+    }
+
+    #[test]
+    fn test_tensordot() {
+        let a = nr_reshape_new(&nr_arange(0.0f64, 60.0, 1.0), &[3, 4, 5], 3);
+        let b = nr_reshape_new(&nr_arange(0.0f64, 24.0, 1.0), &[4, 3, 2], 3);
+        let c = nr_tensordot(&a, &b, &[1, 0], &[0, 1]);
+        assert_eq!(c.shape, vec![5, 2]);
+        for i in 0..5 {
+            for j in 0..2 {
+                let mut expected = 0.0;
+                for k in 0..3 {
+                    for l in 0..4 {
+                        expected += a.data[k * 20 + l * 5 + i] * b.data[l * 6 + k * 2 + j];
+                    }
+                }
+                assert_eq!(c.data[i * 2 + j], expected);
+            }
+        }
+    }
+
+    // --- nr_einsum tests ---
+    #[test]
+    fn test_einsum_batched_matmul() {
+        let a = nr_reshape_new(&nr_arange(0.0f64, 24.0, 1.0), &[2, 3, 4], 3);
+        let b = nr_reshape_new(&nr_arange(0.0f64, 40.0, 1.0), &[2, 4, 5], 3);
+        let c = nr_einsum("bij,bjk->bik", &[&a, &b]);
+        let expected = nr_matmul(&a, &b);
+        assert_eq!(c.shape, vec![2, 3, 5]);
+        assert_eq!(c.data, expected.data);
+
+        // Implicit output "ba,cb" is "ba,cb->ac", i.e. x^T @ y^T
+        let x = nr_reshape_new(&nr_arange(0.0f64, 6.0, 1.0), &[3, 2], 2);
+        let y = nr_reshape_new(&nr_arange(0.0f64, 12.0, 1.0), &[4, 3], 2);
+        let t = nr_einsum("ba,cb", &[&x, &y]);
+        assert_eq!(t.shape, vec![2, 4]);
+        assert_eq!(t.data, nr_einsum("ba,cb->ac", &[&x, &y]).data);
+        assert_eq!(t.data[0], 0.0 * 0.0 + 2.0 * 1.0 + 4.0 * 2.0);
+    }
+
+    #[test]
+    fn test_einsum_trace_and_diagonal() {
+        let a = nr_reshape_new(&nr_arange(0i32, 9, 1), &[3, 3], 2);
+        assert_eq!(nr_einsum("ii", &[&a]).data, vec![12]);
+        assert_eq!(nr_einsum("ii->i", &[&a]).data, vec![0, 4, 8]);
+        assert_eq!(nr_einsum("ij->ji", &[&a]).data, vec![0, 3, 6, 1, 4, 7, 2, 5, 8]);
+        assert_eq!(nr_einsum("ij->", &[&a]).data, vec![36]);
+    }
+
+    #[test]
+    fn test_einsum_ellipsis_broadcast() {
+        // "...ij,...jk" with batch dims (2, 1) and (3,) broadcasting to (2, 3)
+        let a = nr_reshape_new(&nr_arange(0i32, 8, 1), &[2, 1, 2, 2], 4);
+        let b = nr_reshape_new(&nr_arange(0i32, 12, 1), &[3, 2, 2], 3);
+        let c = nr_einsum("...ij,...jk->...ik", &[&a, &b]);
+        assert_eq!(c.shape, vec![2, 3, 2, 2]);
+        assert_eq!(c.data, nr_matmul(&a, &b).data);
+    }
+
+    #[test]
+    fn test_einsum_chain() {
+        // Three-operand chain exercises the greedy path
+        let a = nr_reshape_new(&nr_arange(0.0f64, 6.0, 1.0), &[2, 3], 2);
+        let b = nr_reshape_new(&nr_arange(0.0f64, 12.0, 1.0), &[3, 4], 2);
+        let c = nr_reshape_new(&nr_arange(0.0f64, 8.0, 1.0), &[4, 2], 2);
+        let d = nr_einsum("ij,jk,kl->il", &[&a, &b, &c]);
+        let expected = nr_matmul(&nr_matmul(&a, &b), &c);
+        assert_eq!(d.shape, vec![2, 2]);
+        assert_eq!(d.data, expected.data);
+    }
+
+    // --- linalg tests ---
+    fn assert_close(actual: &[f64], expected: &[f64], tol: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tol, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    fn matrix(data: &[f64], shape: &[i32]) -> Array<f64> {
+        let mut arr = Array::nr_create(shape, shape.len());
+        arr.data.copy_from_slice(data);
+        arr
+    }
+
+    #[test]
+    fn test_lu_reconstructs() {
+        let a = matrix(&[2.0, 1.0, 1.0, 4.0, -6.0, 0.0, -2.0, 7.0, 2.0], &[3, 3]);
+        let (p, l, u) = nr_lu(&a).unwrap();
+        let plu = nr_matmul(&p, &nr_matmul(&l, &u));
+        assert_close(&plu.data, &a.data, 1e-12);
+        // L is unit lower triangular, U is upper triangular
+        assert_eq!((l.data[0], l.data[4], l.data[8]), (1.0, 1.0, 1.0));
+        assert_eq!((u.data[3], u.data[6], u.data[7]), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_solve_and_inv() {
+        let a = matrix(&[3.0, 1.0, 1.0, 2.0], &[2, 2]);
+        let b = matrix(&[9.0, 8.0], &[2]);
+        let x = nr_solve(&a, &b).unwrap();
+        assert_eq!(x.shape, vec![2]);
+        assert_close(&x.data, &[2.0, 3.0], 1e-12);
+
+        let inv = nr_inv(&a).unwrap();
+        assert_close(&inv.data, &[0.4, -0.2, -0.2, 0.6], 1e-12);
+    }
+
+    #[test]
+    fn test_solve_batched_broadcast() {
+        // One right-hand side shared by two systems
+        let a = matrix(&[2.0, 0.0, 0.0, 4.0, 1.0, 1.0, 0.0, 1.0], &[2, 2, 2]);
+        let b = matrix(&[2.0, 4.0], &[2, 1]);
+        let x = nr_solve(&a, &b).unwrap();
+        assert_eq!(x.shape, vec![2, 2, 1]);
+        assert_close(&x.data, &[1.0, 1.0, -2.0, 4.0], 1e-12);
+    }
+
+    #[test]
+    fn test_singular() {
+        let a = matrix(&[1.0, 2.0, 2.0, 4.0], &[2, 2]);
+        assert_eq!(nr_inv(&a).unwrap_err(), LinalgError::Singular);
+        assert_eq!(nr_det(&a).unwrap().data, vec![0.0]);
+        let (sign, logdet) = nr_slogdet(&a).unwrap();
+        assert_eq!(sign.data, vec![0.0]);
+        assert_eq!(logdet.data, vec![f64::NEG_INFINITY]);
+        assert_eq!(nr_matrix_rank(&a, None).unwrap().data, vec![1]);
+        assert!(matches!(nr_det(&matrix(&[1.0; 6], &[2, 3])), Err(LinalgError::NotSquare(_))));
+    }
+
+    #[test]
+    fn test_det_slogdet_f32() {
+        let mut a = Array::<f32>::nr_create(&[2, 3, 3], 3);
+        a.data.copy_from_slice(&[
+            2.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 4.0, // det 24
+            0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -5.0, // det 5
+        ]);
+        let det = nr_det(&a).unwrap();
+        assert_eq!(det.shape, vec![2]);
+        assert!((det.data[0] - 24.0).abs() < 1e-5 && (det.data[1] - 5.0).abs() < 1e-5);
+        let (sign, logdet) = nr_slogdet(&a).unwrap();
+        assert_eq!(sign.data, vec![1.0, 1.0]);
+        assert!((logdet.data[0] - 24f32.ln()).abs() < 1e-5);
+        assert_eq!(nr_matrix_rank(&a, None).unwrap().data, vec![3, 3]);
+    }
+
+    #[test]
+    fn test_qr_modes() {
+        let a = matrix(&[12.0, -51.0, 4.0, 6.0, 167.0, -68.0, -4.0, 24.0, -41.0, 1.0, 2.0, 3.0], &[4, 3]);
+        let (q, r) = nr_qr(&a, QrMode::Reduced).unwrap();
+        assert_eq!((q.shape.clone(), r.shape.clone()), (vec![4, 3], vec![3, 3]));
+        assert_close(&nr_matmul(&q, &r).data, &a.data, 1e-10);
+        // Columns of Q are orthonormal
+        let mut qt = Array::<f64>::nr_create(&[3, 4], 2);
+        for i in 0..4 {
+            for j in 0..3 {
+                qt.data[j * 4 + i] = q.data[i * 3 + j];
+            }
+        }
+        assert_close(&nr_matmul(&qt, &q).data, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 1e-12);
+        assert_eq!((r.data[3], r.data[6], r.data[7]), (0.0, 0.0, 0.0));
+
+        let (q, r) = nr_qr(&a, QrMode::Complete).unwrap();
+        assert_eq!((q.shape.clone(), r.shape.clone()), (vec![4, 4], vec![4, 3]));
+        assert_close(&nr_matmul(&q, &r).data, &a.data, 1e-10);
+    }
+
+    #[test]
+    fn test_cholesky_and_cho_solve() {
+        let a = matrix(&[4.0, 12.0, -16.0, 12.0, 37.0, -43.0, -16.0, -43.0, 98.0], &[3, 3]);
+        let l = nr_cholesky(&a).unwrap();
+        assert_close(&l.data, &[2.0, 0.0, 0.0, 6.0, 1.0, 0.0, -8.0, 5.0, 3.0], 1e-12);
+
+        let b = matrix(&[1.0, 2.0, 3.0], &[3]);
+        let x = nr_cho_solve(&l, &b).unwrap();
+        assert_close(&x.data, &nr_solve(&a, &b).unwrap().data, 1e-10);
+
+        let indefinite = matrix(&[1.0, 2.0, 2.0, 1.0], &[2, 2]);
+        assert_eq!(nr_cholesky(&indefinite).unwrap_err(), LinalgError::NotPositiveDefinite);
+    }
+
+    #[test]
+    fn test_lstsq_line_fit() {
+        // Fit y = c0 + c1 * x through four points
+        let a = matrix(&[1.0, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0], &[4, 2]);
+        let y = matrix(&[-1.0, 0.2, 0.9, 2.1], &[4]);
+        let fit = nr_lstsq(&a, &y, None).unwrap();
+        assert_eq!(fit.solution.shape, vec![2]);
+        assert_close(&fit.solution.data, &[-0.95, 1.0], 1e-12);
+        assert_eq!(fit.rank.data, vec![2]);
+        assert_close(&fit.residuals.unwrap().data, &[0.05], 1e-12);
+        assert_eq!(fit.singular_values.shape, vec![2]);
+
+        // Rank deficient: no residuals, minimum-norm solution
+        let a = matrix(&[1.0, 1.0, 1.0, 1.0], &[2, 2]);
+        let fit = nr_lstsq(&a, &matrix(&[2.0, 2.0], &[2]), None).unwrap();
+        assert!(fit.residuals.is_none());
+        assert_eq!(fit.rank.data, vec![1]);
+        assert_close(&fit.solution.data, &[1.0, 1.0], 1e-12);
+    }
+
+    #[test]
+    fn test_pinv() {
+        let a = matrix(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+        let p = nr_pinv(&a, None).unwrap();
+        assert_eq!(p.shape, vec![3, 2]);
+        // A @ pinv(A) @ A == A
+        let apa = nr_matmul(&nr_matmul(&a, &p), &a);
+        assert_close(&apa.data, &a.data, 1e-10);
+        let sq = matrix(&[3.0, 1.0, 1.0, 2.0], &[2, 2]);
+        assert_close(&nr_pinv(&sq, None).unwrap().data, &nr_inv(&sq).unwrap().data, 1e-12);
+    }
+
+    #[test]
+    fn test_eigh() {
+        let a = matrix(&[2.0, -1.0, 0.0, -1.0, 2.0, -1.0, 0.0, -1.0, 2.0], &[3, 3]);
+        let (w, v) = nr_eigh(&a).unwrap();
+        let r2 = 2f64.sqrt();
+        assert_close(&w.data, &[2.0 - r2, 2.0, 2.0 + r2], 1e-12);
+        // A V = V diag(w)
+        let av = nr_matmul(&a, &v);
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!((av.data[i * 3 + j] - v.data[i * 3 + j] * w.data[j]).abs() < 1e-12);
+            }
+        }
+        assert_close(&nr_eigvalsh(&a).unwrap().data, &w.data, 0.0);
+    }
+
+    #[test]
+    fn test_eig_complex_pair() {
+        // Rotation by 90 degrees has eigenvalues +i and -i
+        let a = matrix(&[0.0, -1.0, 1.0, 0.0], &[2, 2]);
+        let w = nr_eigvals(&a).unwrap();
+        let mut im: Vec<f64> = w.data.iter().map(|z| z.im).collect();
+        im.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert_close(&im, &[-1.0, 1.0], 1e-12);
+        assert!(w.data.iter().all(|z| z.re.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_eig_vectors() {
+        let a = matrix(&[1.0, 2.0, 0.0, -2.0, 1.0, 3.0, 0.5, 0.0, 4.0], &[3, 3]);
+        let (w, v) = nr_eig(&a).unwrap();
+        assert_eq!((w.shape.clone(), v.shape.clone()), (vec![3], vec![3, 3]));
+        // A v_j = w_j v_j column by column, with unit-norm columns
+        for j in 0..3 {
+            let mut norm = 0.0;
+            for i in 0..3 {
+                let mut av = num_complex::Complex::new(0.0, 0.0);
+                for k in 0..3 {
+                    av += v.data[k * 3 + j] * a.data[i * 3 + k];
+                }
+                assert!((av - w.data[j] * v.data[i * 3 + j]).norm() < 1e-10);
+                norm += v.data[i * 3 + j].norm_sqr();
+            }
+            assert!((norm - 1.0).abs() < 1e-12);
+        }
+        // Trace is preserved
+        let trace: f64 = w.data.iter().map(|z| z.re).sum();
+        assert!((trace - 6.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_svd_economy_and_full() {
+        let a = matrix(&[3.0, 2.0, 2.0, 2.0, 3.0, -2.0], &[2, 3]);
+        let svd = nr_svd(&a, false, true).unwrap();
+        assert_close(&svd.s.data, &[5.0, 3.0], 1e-12);
+        let (u, vt) = (svd.u.unwrap(), svd.vt.unwrap());
+        assert_eq!((u.shape.clone(), vt.shape.clone()), (vec![2, 2], vec![2, 3]));
+        let mut us = u;
+        for i in 0..2 {
+            for j in 0..2 {
+                us.data[i * 2 + j] *= svd.s.data[j];
+            }
+        }
+        assert_close(&nr_matmul(&us, &vt).data, &a.data, 1e-12);
+
+        let full = nr_svd(&a, true, true).unwrap();
+        let vt = full.vt.unwrap();
+        assert_eq!(vt.shape, vec![3, 3]);
+        // Full Vt is orthogonal
+        let mut v = Array::<f64>::nr_create(&[3, 3], 2);
+        for i in 0..3 {
+            for j in 0..3 {
+                v.data[j * 3 + i] = vt.data[i * 3 + j];
+            }
+        }
+        assert_close(&nr_matmul(&vt, &v).data, &[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], 1e-12);
+
+        let values_only = nr_svd(&a, false, false).unwrap();
+        assert!(values_only.u.is_none() && values_only.vt.is_none());
+        assert_close(&values_only.s.data, &[5.0, 3.0], 1e-12);
+    }
+
+    #[test]
+    fn test_svd_batched_and_cond() {
+        let a = matrix(&[2.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0], &[2, 2, 2]);
+        let s = nr_svdvals(&a).unwrap();
+        assert_eq!(s.shape, vec![2, 2]);
+        assert_close(&s.data, &[2.0, 0.5, 2.0, 0.0], 1e-12);
+        let cond = nr_cond(&a).unwrap();
+        assert_eq!(cond.data[0], 4.0);
+        assert!(cond.data[1] > 1e15);
+        assert_eq!(nr_matrix_rank(&a, None).unwrap().data, vec![2, 1]);
+    }
+
+    #[test]
+    fn test_norm_vector() {
+        let v = matrix(&[3.0, -4.0, 0.0], &[3]);
+        assert_eq!(nr_norm(&v, None, None, false).unwrap().data, vec![5.0]);
+        assert_eq!(nr_norm(&v, Some(NormOrd::P(1.0)), None, false).unwrap().data, vec![7.0]);
+        assert_eq!(nr_norm(&v, Some(NormOrd::Inf), None, false).unwrap().data, vec![4.0]);
+        assert_eq!(nr_norm(&v, Some(NormOrd::NegInf), None, false).unwrap().data, vec![0.0]);
+        assert_eq!(nr_norm(&v, Some(NormOrd::P(0.0)), None, false).unwrap().data, vec![2.0]);
+        assert_close(&nr_norm(&v, Some(NormOrd::P(3.0)), None, false).unwrap().data, &[91f64.cbrt()], 1e-12);
+        assert!(nr_norm(&v, Some(NormOrd::Nuc), None, false).is_err());
+
+        // Scaled L2 does not overflow
+        let big = matrix(&[3e300, 4e300], &[2]);
+        assert_close(&nr_norm(&big, None, None, false).unwrap().data, &[5e300], 1e286);
+    }
+
+    #[test]
+    fn test_norm_axes() {
+        let a = matrix(&[1.0, -2.0, 3.0, 4.0, 5.0, -6.0], &[2, 3]);
+        let rows = nr_norm(&a, Some(NormOrd::P(1.0)), Some(&[1]), false).unwrap();
+        assert_eq!((rows.shape, rows.data), (vec![2], vec![6.0, 15.0]));
+        let cols = nr_norm(&a, Some(NormOrd::Inf), Some(&[0]), true).unwrap();
+        assert_eq!((cols.shape, cols.data), (vec![1, 3], vec![4.0, 5.0, 6.0]));
+
+        assert_close(&nr_norm(&a, None, None, false).unwrap().data, &[91f64.sqrt()], 1e-12);
+        assert_eq!(nr_norm(&a, Some(NormOrd::P(1.0)), None, false).unwrap().data, vec![9.0]);
+        assert_eq!(nr_norm(&a, Some(NormOrd::Inf), None, false).unwrap().data, vec![15.0]);
+        assert!(nr_norm(&a, Some(NormOrd::P(3.0)), None, false).is_err());
+
+        // Matrix norms over the last two axes of a stack
+        let d = matrix(&[3.0, 0.0, 0.0, -1.0, 1.0, 1.0, 1.0, 1.0], &[2, 2, 2]);
+        let two = nr_norm(&d, Some(NormOrd::P(2.0)), Some(&[1, 2]), false).unwrap();
+        assert_close(&two.data, &[3.0, 2.0], 1e-12);
+        let nuc = nr_norm(&d, Some(NormOrd::Nuc), Some(&[1, 2]), true).unwrap();
+        assert_eq!(nuc.shape, vec![2, 1, 1]);
+        assert_close(&nuc.data, &[4.0, 2.0], 1e-12);
+        // Swapping the axes transposes each matrix
+        let transposed = nr_norm(&a, Some(NormOrd::P(1.0)), Some(&[1, 0]), false).unwrap();
+        assert_eq!(transposed.data, vec![15.0]);
+    }
+
+    #[test]
+    fn test_matrix_power() {
+        let a = matrix(&[1.0, 1.0, 0.0, 2.0], &[2, 2]);
+        assert_eq!(nr_matrix_power(&a, 3).unwrap().data, vec![1.0, 7.0, 0.0, 8.0]);
+        assert_eq!(nr_matrix_power(&a, 0).unwrap().data, vec![1.0, 0.0, 0.0, 1.0]);
+        let inv_sq = nr_matrix_power(&a, -2).unwrap();
+        assert_close(&nr_matmul(&inv_sq, &nr_matrix_power(&a, 2).unwrap()).data, &[1.0, 0.0, 0.0, 1.0], 1e-12);
+        assert!(nr_matrix_power(&matrix(&[1.0, 2.0, 2.0, 4.0], &[2, 2]), -1).is_err());
+    }
+
+    #[test]
+    fn test_expm() {
+        // Nilpotent: exp(N) = I + N
+        let n = matrix(&[0.0, 1.0, 0.0, 0.0], &[2, 2]);
+        assert_close(&nr_expm(&n).unwrap().data, &[1.0, 1.0, 0.0, 1.0], 1e-14);
+        // Rotation generators, small and large enough to need squaring
+        for t in [0.1, 20.0] {
+            let g = matrix(&[0.0, -t, t, 0.0], &[2, 2]);
+            let (c, s) = (t.cos(), t.sin());
+            assert_close(&nr_expm(&g).unwrap().data, &[c, -s, s, c], 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sqrtm_and_logm() {
+        let a = matrix(&[4.0, 1.0, 0.0, 1.0, 3.0, 1.0, 0.0, 1.0, 2.0], &[3, 3]);
+        let r = nr_sqrtm(&a).unwrap();
+        assert_close(&nr_matmul(&r, &r).data, &a.data, 1e-12);
+
+        let l = nr_logm(&a).unwrap();
+        assert_close(&nr_expm(&l).unwrap().data, &a.data, 1e-12);
+        let g = matrix(&[0.0, -0.5, 0.5, 0.0], &[2, 2]);
+        assert_close(&nr_logm(&nr_expm(&g).unwrap()).unwrap().data, &g.data, 1e-12);
+
+        // No real square root
+        assert!(nr_sqrtm(&matrix(&[-1.0, 0.0, 0.0, -4.0], &[2, 2])).is_err());
+    }
+
+    // --- sparse tests ---
+    #[test]
+    fn test_sparse_conversions() {
+        let dense = matrix(&[1.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 3.0, 4.0], &[3, 3]);
+        // Duplicates are summed on compression
+        let coo = sparse::CooMatrix::new((3, 3), vec![2, 0, 0, 2, 2], vec![1, 2, 0, 2, 1], vec![1.0, 2.0, 1.0, 4.0, 2.0]);
+        let csr = coo.to_csr();
+        assert_eq!(csr.indptr, vec![0, 2, 2, 4]);
+        assert_eq!(csr.indices, vec![0, 2, 1, 2]);
+        assert_eq!(csr.to_dense().data, dense.data);
+        assert_eq!(coo.to_csc().to_dense().data, dense.data);
+        assert_eq!(sparse::CscMatrix::from_dense(&dense).to_csr().data, csr.data);
+        assert_eq!(csr.to_coo().to_dense().data, dense.data);
+        assert_eq!(csr.get(2, 1), 3.0);
+        assert_eq!(csr.get(1, 1), 0.0);
+
+        let t = csr.transpose();
+        assert_eq!(t.get(1, 2), 3.0);
+        assert_eq!(t.to_dense().data, vec![1.0, 0.0, 0.0, 0.0, 0.0, 3.0, 2.0, 0.0, 4.0]);
+
+        let rows = csr.slice_rows(1, 3);
+        assert_eq!(rows.shape, (2, 3));
+        assert_eq!(rows.to_dense().data, dense.data[3..].to_vec());
+    }
+
+    #[test]
+    fn test_sparse_products_and_elementwise() {
+        let a = matrix(&[1.0, 0.0, 2.0, 0.0, -1.0, 0.0], &[2, 3]);
+        let b = matrix(&[0.0, 1.0, 3.0, 0.0, 0.0, 2.0], &[3, 2]);
+        let sa = sparse::CsrMatrix::from_dense(&a);
+        let sb = sparse::CsrMatrix::from_dense(&b);
+        let expected = nr_matmul(&a, &b);
+
+        assert_eq!(sa.matmul(&sb).to_dense().data, expected.data);
+        assert_eq!(sa.to_csc().matmul(&sb.to_csc()).to_dense().data, expected.data);
+        assert_eq!(sa.matmul_dense(&b).data, expected.data);
+        assert_eq!(sb.dense_matmul(&a).data, expected.data);
+        assert_eq!(sa.to_csc().matmul_dense(&b).data, expected.data);
+        assert_eq!(sb.to_csc().dense_matmul(&a).data, expected.data);
+        let x = matrix(&[1.0, 2.0, 3.0], &[3]);
+        let y = sa.matmul_dense(&x);
+        assert_eq!((y.shape, y.data), (vec![2], vec![7.0, -2.0]));
+
+        let c = sparse::CsrMatrix::from_dense(&matrix(&[1.0, 1.0, 0.0, 0.0, 1.0, 5.0], &[2, 3]));
+        assert_eq!(sa.add(&c).to_dense().data, vec![2.0, 1.0, 2.0, 0.0, 0.0, 5.0]);
+        assert_eq!(sa.sub(&c).to_dense().data, vec![0.0, -1.0, 2.0, 0.0, -2.0, -5.0]);
+        let prod = sa.multiply(&c);
+        assert_eq!(prod.nnz(), 2);
+        assert_eq!(prod.to_dense().data, vec![1.0, 0.0, 0.0, 0.0, -1.0, 0.0]);
+        assert_eq!(sa.scale(2.0).to_csc().add(&c.to_csc()).to_dense().data, vec![3.0, 1.0, 4.0, 0.0, -1.0, 5.0]);
+    }
+
+    // 1-D Poisson matrix tridiag(-1, 2, -1), symmetric positive definite
+    fn poisson_csr(n: usize) -> sparse::CsrMatrix<f64> {
+        let mut coo = sparse::CooMatrix::zeros((n, n));
+        for i in 0..n {
+            coo.push(i, i, 2.0);
+            if i > 0 {
+                coo.push(i, i - 1, -1.0);
+                coo.push(i - 1, i, -1.0);
+            }
+        }
+        coo.to_csr()
+    }
+
+    #[test]
+    fn test_cg_with_preconditioners() {
+        let a = poisson_csr(50);
+        let b = matrix(&[1.0; 50], &[50]);
+        let plain = nr_cg(&a, &b, None, 1e-10, None, None);
+        assert!(plain.converged);
+        assert!(plain.residual_norm <= 1e-10 * 50f64.sqrt());
+        assert_close(&a.matmul_dense(&plain.solution).data, &b.data, 1e-8);
+
+        let jacobi = sparse::JacobiPreconditioner::new(&a);
+        assert!(nr_cg(&a, &b, None, 1e-10, None, Some(&jacobi)).converged);
+        // ILU(0) of a tridiagonal matrix is its exact LU factorization
+        let ilu = sparse::Ilu0Preconditioner::new(&a);
+        let res = nr_cg(&a, &b, None, 1e-10, None, Some(&ilu));
+        assert!(res.converged && res.iterations <= 2);
+
+        // Dense arrays are operators too, and maxiter is honored
+        let dense = a.to_dense();
+        let capped = nr_cg(&dense, &b, None, 1e-12, Some(3), None);
+        assert!(!capped.converged);
+        assert_eq!(capped.iterations, 3);
+
+        // Preconditioners built without a CSR matrix match the CSR ones
+        let from_dense = sparse::JacobiPreconditioner::from_dense(&dense);
+        let from_diag = sparse::JacobiPreconditioner::from_diagonal(&[2.0; 50]);
+        let expected = nr_cg(&a, &b, None, 1e-10, None, Some(&jacobi));
+        for res in [
+            nr_cg(&dense, &b, None, 1e-10, None, Some(&from_dense)),
+            nr_cg(&dense, &b, None, 1e-10, None, Some(&from_diag)),
+        ] {
+            assert_eq!(res.iterations, expected.iterations);
+            assert_close(&res.solution.data, &expected.solution.data, 1e-12);
+        }
+        let ilu_dense = sparse::Ilu0Preconditioner::from_dense(&dense);
+        assert!(nr_cg(&dense, &b, None, 1e-10, None, Some(&ilu_dense)).iterations <= 2);
+    }
+
+    #[test]
+    fn test_gmres_and_bicgstab_nonsymmetric() {
+        // Convection-diffusion style: Poisson plus an upwind term
+        let n = 40;
+        let mut coo = sparse::CooMatrix::zeros((n, n));
+        for i in 0..n {
+            coo.push(i, i, 3.0);
+            if i > 0 {
+                coo.push(i, i - 1, -1.5);
+            }
+            if i + 1 < n {
+                coo.push(i, i + 1, -0.5);
+            }
+        }
+        let a = coo.to_csr();
+        let b = matrix(&(0..n).map(|i| (i as f64).sin()).collect::<Vec<_>>(), &[n as i32]);
+
+        for res in [
+            nr_gmres(&a, &b, None, 1e-10, Some(10), None, None),
+            nr_gmres(&a.to_csc(), &b, None, 1e-10, None, None, Some(&sparse::Ilu0Preconditioner::new(&a))),
+            nr_bicgstab(&a, &b, None, 1e-10, None, None),
+            nr_bicgstab(&a, &b, None, 1e-10, None, Some(&sparse::JacobiPreconditioner::new(&a))),
+        ] {
+            assert!(res.converged, "{:?}", res);
+            assert_close(&a.matmul_dense(&res.solution).data, &b.data, 1e-8);
+        }
+
+        // A good starting point converges immediately
+        let exact = nr_solve(&a.to_dense(), &b).unwrap();
+        let warm = nr_gmres(&a, &b, Some(&exact), 1e-8, None, None, None);
+        assert_eq!(warm.iterations, 0);
+    }
+
     // --- nr_random tests ---
     #[test]
     fn test_random() {
@@ -87,7 +767,1256 @@ mod test {
         assert_eq!(arr.totalsize, 25);
         // Check if values are within the expected range [0, 1)
         for val in arr.data {
-            assert!(val >= 0.0 && val < 1.0);
+            assert!((0.0..1.0).contains(&val));
+        }
+    }
+
+    #[test]
+    fn test_generator_reproducible() {
+        let a = Generator::new(42).random::<f64>(&[3, 4]);
+        let b = Generator::new(42).random::<f64>(&[3, 4]);
+        let c = Generator::new(43).random::<f64>(&[3, 4]);
+        assert_eq!(a.data, b.data);
+        assert_ne!(a.data, c.data);
+        assert!(a.data.iter().all(|v| (0.0..1.0).contains(v)));
+
+        // Successive draws differ, and multi-chunk fills stay deterministic
+        let mut g = Generator::new(7);
+        let first = g.random::<f32>(&[40000]);
+        let second = g.random::<f32>(&[40000]);
+        assert_ne!(first.data, second.data);
+        assert_eq!(Generator::new(7).random::<f32>(&[40000]).data, first.data);
+        let mean = first.data.iter().map(|&v| v as f64).sum::<f64>() / 40000.0;
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_generator_spawn() {
+        let children = Generator::new(1).spawn(3);
+        let again = Generator::new(1).spawn(3);
+        let draws: Vec<u64> = children.into_iter().map(|mut g| g.next_u64()).collect();
+        assert_eq!(draws, again.into_iter().map(|mut g| g.next_u64()).collect::<Vec<_>>());
+        assert!(draws[0] != draws[1] && draws[1] != draws[2]);
+        // Child i of a 128-bit seed drawn from the parent is PCG64 seeded by
+        // numpy's SeedSequence(seed, spawn_key=(i,)), so children differ in
+        // state as well as increment
+        assert_eq!(draws, vec![833946362278529509, 5770439492258792744, 6711151098581490994]);
+    }
+
+    fn mean_var(data: &[f64]) -> (f64, f64) {
+        let n = data.len() as f64;
+        let mean = data.iter().sum::<f64>() / n;
+        (mean, data.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n)
+    }
+
+    #[test]
+    fn test_continuous_distributions() {
+        let mut g = Generator::new(2024);
+        let shape = [200_000];
+        let (m, v) = mean_var(&g.normal(1.5, 2.0, &shape).data);
+        assert!((m - 1.5).abs() < 0.02 && (v - 4.0).abs() < 0.05, "{} {}", m, v);
+        let u = g.uniform(-2.0, 3.0, &shape);
+        assert!(u.data.iter().all(|x| (-2.0..3.0).contains(x)));
+        assert!((mean_var(&u.data).0 - 0.5).abs() < 0.02);
+        let (m, v) = mean_var(&g.standard_exponential(&shape).data);
+        assert!((m - 1.0).abs() < 0.01 && (v - 1.0).abs() < 0.03);
+        for (k, scale) in [(0.5, 2.0), (3.0, 0.5)] {
+            let (m, v) = mean_var(&g.gamma(k, scale, &shape).data);
+            assert!((m - k * scale).abs() < 0.02 && (v - k * scale * scale).abs() < 0.05, "{} {}", m, v);
+        }
+        for (a, b) in [(0.5, 0.5), (2.0, 5.0)] {
+            let (m, v) = mean_var(&g.beta(a, b, &shape).data);
+            let var = a * b / ((a + b) * (a + b) * (a + b + 1.0));
+            assert!((m - a / (a + b)).abs() < 0.005 && (v - var).abs() < 0.002, "{} {}", m, v);
+        }
+    }
+
+    #[test]
+    fn test_discrete_distributions() {
+        let mut g = Generator::new(99);
+        let ints = g.integers(-3i32, 4, &[10_000]);
+        assert!(ints.data.iter().all(|x| (-3..4).contains(x)));
+        assert!((-3..4).all(|k| ints.data.contains(&k)));
+
+        let shape = [100_000];
+        for (n, p) in [(20, 0.3), (1000, 0.6)] {
+            let draws: Vec<f64> = g.binomial(n, p, &shape).data.iter().map(|&x| x as f64).collect();
+            let (m, v) = mean_var(&draws);
+            let n = n as f64;
+            assert!((m - n * p).abs() < 0.05 * (n * p).sqrt() && (v / (n * p * (1.0 - p)) - 1.0).abs() < 0.03, "{} {}", m, v);
+        }
+        for lam in [3.0, 250.0] {
+            let draws: Vec<f64> = g.poisson(lam, &shape).data.iter().map(|&x| x as f64).collect();
+            let (m, v) = mean_var(&draws);
+            assert!((m - lam).abs() < 0.02 * lam.sqrt() * 3.0 && (v / lam - 1.0).abs() < 0.03, "{} {}", m, v);
+        }
+    }
+
+    #[test]
+    fn test_vector_distributions() {
+        let mut g = Generator::new(5);
+        let counts = g.multinomial(10, &[0.2, 0.3, 0.5], &[4000]);
+        assert_eq!(counts.shape, vec![4000, 3]);
+        assert!(counts.data.chunks(3).all(|c| c.iter().sum::<i64>() == 10));
+        let first: f64 = counts.data.iter().step_by(3).map(|&x| x as f64).sum::<f64>() / 4000.0;
+        assert!((first - 2.0).abs() < 0.1);
+
+        let alpha = [1.0, 2.0, 7.0];
+        let d = g.dirichlet(&alpha, &[20_000]);
+        assert!(d.data.chunks(3).all(|c| (c.iter().sum::<f64>() - 1.0).abs() < 1e-12));
+        let last = d.data.iter().skip(2).step_by(3).sum::<f64>() / 20_000.0;
+        assert!((last - 0.7).abs() < 0.01);
+
+        let mean = matrix(&[1.0, -1.0], &[2]);
+        let cov = matrix(&[2.0, 0.8, 0.8, 1.0], &[2, 2]);
+        let x = g.multivariate_normal(&mean, &cov, &[50_000]).unwrap();
+        assert_eq!(x.shape, vec![50_000, 2]);
+        let (xs, ys): (Vec<f64>, Vec<f64>) = x.data.chunks(2).map(|c| (c[0], c[1])).unzip();
+        let ((mx, vx), (my, vy)) = (mean_var(&xs), mean_var(&ys));
+        let cxy = xs.iter().zip(&ys).map(|(a, b)| (a - mx) * (b - my)).sum::<f64>() / 50_000.0;
+        assert_close(&[mx, my, vx, vy, cxy], &[1.0, -1.0, 2.0, 1.0, 0.8], 0.05);
+        assert!(g.multivariate_normal(&mean, &matrix(&[1.0, 2.0, 2.0, 1.0], &[2, 2]), &[1]).is_err());
+    }
+
+    #[test]
+    fn test_choice() {
+        let mut g = Generator::new(11);
+        let a = matrix(&[10.0, 20.0, 30.0, 40.0], &[4]);
+        let picked = g.choice(&a, &[3], false, None);
+        let mut vals = picked.data.clone();
+        vals.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        vals.dedup();
+        assert_eq!(vals.len(), 3);
+
+        // Weighted with replacement follows p; zero weights never appear
+        let p = [0.1, 0.0, 0.6, 0.3];
+        let draws = g.choice(&a, &[20_000], true, Some(&p));
+        assert!(!draws.data.contains(&20.0));
+        let frac = draws.data.iter().filter(|&&v| v == 30.0).count() as f64 / 20_000.0;
+        assert!((frac - 0.6).abs() < 0.02);
+
+        // Weighted without replacement takes distinct entries only
+        let distinct = g.choice(&a, &[3], false, Some(&p));
+        assert!(!distinct.data.contains(&20.0));
+        assert_eq!(distinct.data.iter().sum::<f64>(), 80.0);
+
+        // Rows of a 2-D array are sampled whole
+        let rows = matrix(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], &[3, 2]);
+        let batch = g.choice(&rows, &[2, 2], true, None);
+        assert_eq!(batch.shape, vec![2, 2, 2]);
+        assert!(batch.data.chunks(2).all(|r| r[1] == r[0] + 1.0 && r[0] % 2.0 == 0.0));
+    }
+
+    #[test]
+    fn test_shuffle_and_permutation() {
+        let mut g = Generator::new(3);
+        let mut data = Array::<f32>::nr_create(&[50, 3], 2);
+        for (i, v) in data.data.iter_mut().enumerate() {
+            *v = i as f32;
+        }
+        g.shuffle(&mut data);
+        assert!(data.data.chunks(3).all(|r| (r[0] as usize).is_multiple_of(3) && r[1] == r[0] + 1.0 && r[2] == r[0] + 2.0));
+        let mut firsts: Vec<usize> = data.data.chunks(3).map(|r| r[0] as usize / 3).collect();
+        assert_ne!(firsts, (0..50).collect::<Vec<_>>());
+        firsts.sort();
+        assert_eq!(firsts, (0..50).collect::<Vec<_>>());
+
+        let mut perm = g.permutation(10).data;
+        perm.sort();
+        assert_eq!(perm, (0..10).collect::<Vec<i64>>());
+        let a = matrix(&[1.0, 2.0, 3.0], &[3]);
+        let mut shuffled = Generator::new(8).permutation_of(&a).data;
+        assert_eq!(a.data, vec![1.0, 2.0, 3.0]);
+        shuffled.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        assert_eq!(shuffled, a.data);
+    }
+
+    // --- fft tests ---
+    fn complex_array(data: &[(f64, f64)], shape: &[i32]) -> Array<num_complex::Complex<f64>> {
+        let mut arr = Array::nr_create(shape, shape.len());
+        for (v, &(re, im)) in arr.data.iter_mut().zip(data) {
+            *v = num_complex::Complex::new(re, im);
+        }
+        arr
+    }
+
+    fn naive_dft(x: &[num_complex::Complex<f64>]) -> Vec<num_complex::Complex<f64>> {
+        let n = x.len();
+        (0..n)
+            .map(|k| {
+                x.iter().enumerate().fold(num_complex::Complex::new(0.0, 0.0), |acc, (j, &v)| {
+                    let angle = -2.0 * std::f64::consts::PI * ((j * k) % n) as f64 / n as f64;
+                    acc + v * num_complex::Complex::new(angle.cos(), angle.sin())
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        // Powers of two, mixed radices, a small prime leaf and Bluestein primes
+        for &n in &[1usize, 8, 12, 17, 34, 97, 360] {
+            let data: Vec<(f64, f64)> = (0..n).map(|i| ((i as f64 * 0.7).sin(), (i as f64 * 0.3).cos() - 0.5)).collect();
+            let x = complex_array(&data, &[n as i32]);
+            let y = nr_fft(&x, None, None, FftNorm::Backward);
+            let expected = naive_dft(&x.data);
+            assert!(y.data.iter().zip(&expected).all(|(a, b)| (a - b).norm() < 1e-9), "n = {}", n);
+
+            let back = nr_ifft(&y, None, None, FftNorm::Backward);
+            assert!(back.data.iter().zip(&x.data).all(|(a, b)| (a - b).norm() < 1e-12), "n = {}", n);
+        }
+
+        // Padding and cropping with n; ortho and forward scaling
+        let x = complex_array(&[(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)], &[3]);
+        let padded = nr_fft(&x, Some(4), None, FftNorm::Backward);
+        let re: Vec<f64> = padded.data.iter().map(|v| v.re).collect();
+        let im: Vec<f64> = padded.data.iter().map(|v| v.im).collect();
+        assert_close(&re, &[6.0, -2.0, 2.0, -2.0], 1e-12);
+        assert_close(&im, &[0.0, -2.0, 0.0, 2.0], 1e-12);
+        assert_eq!(nr_fft(&x, Some(2), None, FftNorm::Backward).data[0].re, 3.0);
+        assert!((nr_fft(&x, None, None, FftNorm::Ortho).data[0].re - 6.0 / 3f64.sqrt()).abs() < 1e-12);
+        assert!((nr_fft(&x, None, None, FftNorm::Forward).data[0].re - 2.0).abs() < 1e-12);
+        let ortho = nr_ifft(&nr_fft(&x, None, None, FftNorm::Ortho), None, None, FftNorm::Ortho);
+        assert!(ortho.data.iter().zip(&x.data).all(|(a, b)| (a - b).norm() < 1e-12));
+    }
+
+    #[test]
+    fn test_rfft_irfft() {
+        for &n in &[6usize, 7, 19] {
+            let x = matrix(&(0..n).map(|i| (i as f64).powi(2) - 3.0).collect::<Vec<_>>(), &[n as i32]);
+            let spec = nr_rfft(&x, None, None, FftNorm::Backward);
+            assert_eq!(spec.shape, vec![(n / 2 + 1) as i32]);
+            let full = naive_dft(&x.data.iter().map(|&v| num_complex::Complex::new(v, 0.0)).collect::<Vec<_>>());
+            assert!(spec.data.iter().zip(&full).all(|(a, b)| (a - b).norm() < 1e-9));
+            let back = nr_irfft(&spec, Some(n), None, FftNorm::Backward);
+            assert_close(&back.data, &x.data, 1e-10);
+        }
+
+        // Along the first axis of a 2-D array
+        let x = matrix(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[4, 2]);
+        let spec = nr_rfft(&x, None, Some(0), FftNorm::Backward);
+        assert_eq!(spec.shape, vec![3, 2]);
+        assert_close(&[spec.data[0].re, spec.data[1].re, spec.data[4].re], &[16.0, 20.0, -4.0], 1e-12);
+        assert_close(&nr_irfft(&spec, None, Some(0), FftNorm::Backward).data, &x.data, 1e-12);
+    }
+
+    #[test]
+    fn test_fftn() {
+        let data: Vec<(f64, f64)> = (0..24).map(|i| (i as f64, (i % 5) as f64)).collect();
+        let x = complex_array(&data, &[2, 3, 4]);
+        let y = nr_fftn(&x, None, None, FftNorm::Backward);
+
+        // Same as transforming each axis in turn
+        let mut z = nr_fft(&x, None, Some(0), FftNorm::Backward);
+        for axis in 1..3 {
+            z = nr_fft(&z, None, Some(axis), FftNorm::Backward);
+        }
+        assert!(y.data.iter().zip(&z.data).all(|(a, b)| (a - b).norm() < 1e-9));
+        let dc: f64 = data.iter().map(|d| d.0).sum();
+        assert!((y.data[0].re - dc).abs() < 1e-9);
+
+        let back = nr_ifftn(&y, None, None, FftNorm::Backward);
+        assert!(back.data.iter().zip(&x.data).all(|(a, b)| (a - b).norm() < 1e-12));
+
+        // fft2 covers the last two axes, with padding through s
+        let y2 = nr_fft2(&x, Some(&[4, 4]), None, FftNorm::Backward);
+        assert_eq!(y2.shape, vec![2, 4, 4]);
+        let back2 = nr_ifft2(&y2, None, None, FftNorm::Backward);
+        assert!((back2.data[4] - x.data[4]).norm() < 1e-12);
+        assert!(back2.data[12].norm() < 1e-12);
+
+        // 1-D input works with explicit axes
+        let v = complex_array(&data[..5], &[5]);
+        let y1 = nr_fft2(&v, None, Some(&[0]), FftNorm::Backward);
+        assert_eq!(y1.data, nr_fft(&v, None, Some(0), FftNorm::Backward).data);
+    }
+
+    #[test]
+    #[should_panic(expected = "fft2: input of shape [5] needs at least 2 dimensions")]
+    fn test_fft2_needs_two_axes() {
+        let v = complex_array(&[(1.0, 0.0); 5], &[5]);
+        nr_fft2(&v, None, None, FftNorm::Backward);
+    }
+
+    #[test]
+    fn test_fftfreq_and_shift() {
+        assert_close(&nr_fftfreq(5, 0.1).data, &[0.0, 2.0, 4.0, -4.0, -2.0], 1e-12);
+        assert_close(&nr_fftfreq(4, 1.0).data, &[0.0, 0.25, -0.5, -0.25], 1e-12);
+        assert_close(&nr_rfftfreq(5, 0.1).data, &[0.0, 2.0, 4.0], 1e-12);
+
+        let f = nr_fftfreq(5, 1.0);
+        let shifted = nr_fftshift(&f, None);
+        assert_close(&shifted.data, &[-0.4, -0.2, 0.0, 0.2, 0.4], 1e-12);
+        assert_eq!(nr_ifftshift(&shifted, None).data, f.data);
+
+        let a = matrix(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0], &[2, 3]);
+        assert_eq!(nr_fftshift(&a, Some(&[1])).data, vec![2.0, 0.0, 1.0, 5.0, 3.0, 4.0]);
+        assert_eq!(nr_fftshift(&a, None).data, vec![5.0, 3.0, 4.0, 2.0, 0.0, 1.0]);
+    }
+
+    // --- complex tests ---
+    #[test]
+    fn test_complex_parts() {
+        let z = nr_complex(&matrix(&[3.0, -1.0, 0.0, 1e300], &[2, 2]), &matrix(&[4.0, 0.0, -2.0, 1e300], &[2, 2]));
+        assert_eq!(z.shape, vec![2, 2]);
+        assert_eq!(nr_real(&z).data, vec![3.0, -1.0, 0.0, 1e300]);
+        assert_eq!(nr_imag(&z).data, vec![4.0, 0.0, -2.0, 1e300]);
+        assert_eq!(nr_imag(&nr_conj(&z)).data, vec![-4.0, -0.0, 2.0, -1e300]);
+        let pi = std::f64::consts::PI;
+        assert_close(&nr_angle(&z).data, &[(4.0f64).atan2(3.0), pi, -pi / 2.0, pi / 4.0], 1e-15);
+        let abs = nr_abs(&z).data;
+        assert_close(&abs[..3], &[5.0, 1.0, 2.0], 1e-15);
+        assert!((abs[3] / (1e300 * 2f64.sqrt()) - 1.0).abs() < 1e-15);
+        // Elements display the way nr_show prints them
+        assert_eq!(z.data[..3].iter().map(|v| v.to_string()).collect::<Vec<_>>(), ["3+4i", "-1+0i", "0-2i"]);
+    }
+
+    #[test]
+    fn test_complex_matmul_and_conj_transpose() {
+        let a = complex_array(&[(1.0, 1.0), (2.0, 0.0), (0.0, -1.0), (3.0, 2.0), (1.0, 0.0), (0.0, 1.0)], &[2, 3]);
+        let ah = nr_conj_transpose(&a);
+        assert_eq!(ah.shape, vec![3, 2]);
+        assert_eq!(ah.data[1], num_complex::Complex::new(3.0, -2.0));
+
+        // vdot conjugates its first argument, as np.vdot does
+        let u = complex_array(&[(1.0, 2.0), (3.0, -1.0)], &[2]);
+        let v = complex_array(&[(2.0, 1.0), (1.0, 1.0)], &[2]);
+        assert_eq!(nr_vdot(&u, &v), num_complex::Complex::new(6.0, 1.0));
+        assert_eq!(nr_vdot(&v, &u), num_complex::Complex::new(6.0, -1.0));
+
+        // A A^H is Hermitian with a real, positive diagonal
+        let g = nr_matmul(&a, &ah);
+        assert_eq!(g.data[0], num_complex::Complex::new(7.0, 0.0));
+        assert_eq!(g.data[3], num_complex::Complex::new(15.0, 0.0));
+        assert_eq!(g.data[1], g.data[2].conj());
+        assert_eq!(g.data[1], num_complex::Complex::new(6.0, 1.0));
+
+        // Large products take the split real/imaginary path
+        let n = 70;
+        let data: Vec<(f64, f64)> = (0..n * n).map(|i| (((i * 7) % 11) as f64 - 5.0, ((i * 3) % 13) as f64 - 6.0)).collect();
+        let x = complex_array(&data, &[n as i32, n as i32]);
+        let y = nr_matmul(&x, &nr_conj_transpose(&x));
+        for &(i, j) in &[(0, 0), (3, 41), (69, 12)] {
+            let expected = (0..n).fold(num_complex::Complex::new(0.0, 0.0), |acc, k| acc + x.data[i * n + k] * x.data[j * n + k].conj());
+            assert!((y.data[i * n + j] - expected).norm() < 1e-9);
+        }
+
+        // Stacks transpose matrix by matrix; vectors are only conjugated
+        let stack = complex_array(&(0..12).map(|i| (i as f64, 1.0)).collect::<Vec<_>>(), &[2, 3, 2]);
+        let sh = nr_conj_transpose(&stack);
+        assert_eq!(sh.shape, vec![2, 2, 3]);
+        assert_eq!(sh.data[7], num_complex::Complex::new(8.0, -1.0));
+        let v = complex_array(&[(1.0, 2.0)], &[1]);
+        assert_eq!(nr_conj_transpose(&v).data[0], num_complex::Complex::new(1.0, -2.0));
+    }
+
+    // --- convolve tests ---
+    #[test]
+    fn test_convolve_and_correlate() {
+        let a = matrix(&[1.0, 2.0, 3.0], &[3]);
+        let v = matrix(&[0.0, 1.0, 0.5], &[3]);
+        assert_eq!(nr_convolve(&a, &v, ConvolveMode::Full).data, vec![0.0, 1.0, 2.5, 4.0, 1.5]);
+        assert_eq!(nr_convolve(&a, &v, ConvolveMode::Same).data, vec![1.0, 2.5, 4.0]);
+        assert_eq!(nr_convolve(&a, &v, ConvolveMode::Valid).data, vec![2.5]);
+        let even = matrix(&[1.0, 1.0], &[2]);
+        assert_eq!(nr_convolve(&matrix(&[1.0, 2.0, 3.0, 4.0], &[4]), &even, ConvolveMode::Same).data, vec![1.0, 3.0, 5.0, 7.0]);
+
+        assert_eq!(nr_correlate(&a, &v, ConvolveMode::Full).data, vec![0.5, 2.0, 3.5, 3.0, 0.0]);
+        assert_eq!(nr_correlate(&a, &v, ConvolveMode::Same).data, vec![2.0, 3.5, 3.0]);
+        assert_eq!(nr_correlate(&a, &v, ConvolveMode::Valid).data, vec![3.5]);
+        assert_eq!(nr_correlate(&v, &a, ConvolveMode::Full).data, vec![0.0, 3.0, 3.5, 2.0, 0.5]);
+        assert_eq!(nr_correlate(&even, &a, ConvolveMode::Valid).data, vec![5.0, 3.0]);
+
+        // Long inputs take the FFT path and agree with the direct sum
+        let x: Vec<f64> = (0..300).map(|i| (i as f64 * 0.37).sin()).collect();
+        let h: Vec<f64> = (0..90).map(|i| 1.0 / (1.0 + i as f64)).collect();
+        let full = nr_convolve(&matrix(&x, &[300]), &matrix(&h, &[90]), ConvolveMode::Full);
+        assert_eq!(full.shape, vec![389]);
+        for &k in &[0, 45, 200, 388] {
+            let direct: f64 = (0..90).filter(|&j| k >= j && k - j < 300).map(|j| h[j] * x[k - j]).sum();
+            assert!((full.data[k] - direct).abs() < 1e-12);
+        }
+        let valid = nr_correlate(&matrix(&x, &[300]), &matrix(&h, &[90]), ConvolveMode::Valid);
+        assert_eq!(valid.shape, vec![211]);
+        let direct: f64 = (0..90).map(|j| x[10 + j] * h[j]).sum();
+        assert!((valid.data[10] - direct).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_convolve2d() {
+        let a = matrix(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0], &[3, 3]);
+        let k = matrix(&[1.0, 0.0, 0.0, -1.0], &[2, 2]);
+        let full = nr_convolve2d(&a, &k, ConvolveMode::Full, Boundary::Fill(0.0));
+        assert_eq!(full.shape, vec![4, 4]);
+        assert_eq!(
+            full.data,
+            vec![1.0, 2.0, 3.0, 0.0, 4.0, 4.0, 4.0, -3.0, 7.0, 4.0, 4.0, -6.0, 0.0, -7.0, -8.0, -9.0]
+        );
+        let valid = nr_convolve2d(&a, &k, ConvolveMode::Valid, Boundary::Fill(0.0));
+        assert_eq!(valid.data, vec![4.0; 4]);
+        // The larger input may come second in valid mode
+        assert_eq!(nr_convolve2d(&k, &a, ConvolveMode::Valid, Boundary::Fill(0.0)).data, valid.data);
+
+        // Fill value, periodic and mirrored edges
+        let ones = matrix(&[1.0; 9], &[3, 3]);
+        let filled = nr_convolve2d(&a, &ones, ConvolveMode::Same, Boundary::Fill(1.0));
+        assert_eq!(filled.data[0], 1.0 + 2.0 + 4.0 + 5.0 + 5.0);
+        assert_eq!(nr_convolve2d(&a, &ones, ConvolveMode::Same, Boundary::Wrap).data, vec![45.0; 9]);
+        let small = matrix(&[1.0, 2.0, 3.0, 4.0], &[2, 2]);
+        let sym = nr_convolve2d(&small, &ones, ConvolveMode::Same, Boundary::Symmetric);
+        assert_eq!(sym.data, vec![18.0, 21.0, 24.0, 27.0]);
+
+        // Correlation is convolution with the kernel rotated by 180 degrees
+        let corr = nr_correlate2d(&a, &k, ConvolveMode::Same, Boundary::Symmetric);
+        let rotated = matrix(&[-1.0, 0.0, 0.0, 1.0], &[2, 2]);
+        assert_eq!(corr.data, nr_convolve2d(&a, &rotated, ConvolveMode::Same, Boundary::Symmetric).data);
+        assert_eq!(nr_correlate2d(&a, &k, ConvolveMode::Valid, Boundary::Wrap).data, vec![-4.0; 4]);
+    }
+
+    // --- signal tests ---
+    // |H(e^{iw})| of a (b, a) filter at w in rad/sample
+    fn freq_response(b: &[f64], a: &[f64], w: f64) -> f64 {
+        let eval = |c: &[f64]| {
+            c.iter().enumerate().fold(num_complex::Complex::new(0.0, 0.0), |acc, (k, &v)| acc + num_complex::Complex::from_polar(v, -w * k as f64))
+        };
+        (eval(b) / eval(a)).norm()
+    }
+
+    #[test]
+    fn test_windows() {
+        use signal::*;
+        assert_close(&nr_hann(5, true).data, &[0.0, 0.5, 1.0, 0.5, 0.0], 1e-15);
+        assert_close(&nr_hann(4, false).data, &[0.0, 0.5, 1.0, 0.5], 1e-15);
+        assert_close(&nr_hamming(3, true).data, &[0.08, 1.0, 0.08], 1e-15);
+        assert_close(&nr_blackman(3, true).data, &[0.0, 1.0, 0.0], 1e-15);
+        assert_close(&nr_bartlett(5, true).data, &[0.0, 0.5, 1.0, 0.5, 0.0], 1e-15);
+        assert_close(&nr_kaiser(3, 5.0, true).data, &[1.0 / 27.239871823604442, 1.0, 1.0 / 27.239871823604442], 1e-12);
+        assert_eq!(nr_kaiser(4, 0.0, true).data, vec![1.0; 4]);
+        // scipy.signal.windows.kaiser values, with beta on both sides of
+        // the switch of i0 to its asymptotic expansion at 30
+        let k = [7.726866835270368e-6, 0.03288552597786782, 0.46271649780079144, 1.0];
+        assert_rel_close(&nr_kaiser(7, 14.0, true).data, &[k[0], k[1], k[2], k[3], k[2], k[1], k[0]], 1e-13);
+        let k = [6.713763812271753e-17, 4.3719635994450625e-5, 0.10455961162188725, 1.0];
+        assert_rel_close(&nr_kaiser(6, 40.0, false).data, &[k[0], k[1], k[2], k[3], k[2], k[1]], 1e-13);
+        assert_eq!(nr_get_window(Window::Hann, 1, true).data, vec![1.0]);
+    }
+
+    #[test]
+    fn test_iir_design() {
+        use signal::*;
+        let (b, a) = nr_butter(2, &[0.5], BandType::Lowpass, None).to_ba();
+        assert_close(&b, &[0.2928932188134524, 0.5857864376269049, 0.2928932188134524], 1e-12);
+        assert_close(&a, &[1.0, 0.0, 0.1715728752538099], 1e-12);
+        let (b, a) = nr_butter(2, &[0.5], BandType::Highpass, None).to_ba();
+        assert_close(&b, &[0.2928932188134524, -0.5857864376269049, 0.2928932188134524], 1e-12);
+        assert_close(&a, &[1.0, 0.0, 0.1715728752538099], 1e-12);
+        let (b, a) = nr_butter(4, &[100.0], BandType::Lowpass, Some(1000.0)).to_ba();
+        assert_close(&b, &[0.00482434, 0.01929737, 0.02894606, 0.01929737, 0.00482434], 1e-8);
+        assert_close(&a, &[1.0, -2.36951301, 2.31398841, -1.05466541, 0.18737949], 1e-8);
+
+        let pi = std::f64::consts::PI;
+        let half_power = 0.5f64.sqrt();
+        let (b, a) = nr_butter(3, &[0.2, 0.5], BandType::Bandpass, None).to_ba();
+        assert_eq!(a.len(), 7);
+        assert!((freq_response(&b, &a, 0.2 * pi) - half_power).abs() < 1e-9);
+        assert!((freq_response(&b, &a, 0.5 * pi) - half_power).abs() < 1e-9);
+        assert!(freq_response(&b, &a, 0.0) < 1e-9);
+        let (b, a) = nr_butter(2, &[0.2, 0.5], BandType::Bandstop, None).to_ba();
+        assert!((freq_response(&b, &a, 0.0) - 1.0).abs() < 1e-9);
+        assert!((freq_response(&b, &a, 0.5 * pi) - half_power).abs() < 1e-9);
+
+        // Chebyshev I: rp dB down at DC (even order) and at the cutoff;
+        // Chebyshev II: rs dB down at the stopband edge
+        let ripple = 10f64.powf(-1.0 / 20.0);
+        let (b, a) = nr_cheby1(4, 1.0, &[0.3], BandType::Lowpass, None).to_ba();
+        assert!((freq_response(&b, &a, 0.0) - ripple).abs() < 1e-9);
+        assert!((freq_response(&b, &a, 0.3 * pi) - ripple).abs() < 1e-9);
+        let (b, a) = nr_cheby2(5, 40.0, &[0.3], BandType::Lowpass, None).to_ba();
+        assert!((freq_response(&b, &a, 0.0) - 1.0).abs() < 1e-9);
+        assert!((freq_response(&b, &a, 0.3 * pi) - 0.01).abs() < 1e-9);
+        assert!((0..50).all(|i| freq_response(&b, &a, (0.3 + 0.014 * i as f64) * pi) <= 0.01 + 1e-9));
+
+        // Second-order sections describe the same filter
+        let zpk = nr_cheby1(5, 0.5, &[0.1, 0.4], BandType::Bandpass, None);
+        let sos = zpk.to_sos();
+        assert_eq!(sos.shape, vec![5, 6]);
+        let (b, a) = zpk.to_ba();
+        for &w in &[0.1, 0.7, 1.3, 2.9] {
+            let cascade: f64 = sos.data.chunks(6).map(|s| freq_response(&s[..3], &s[3..], w)).product();
+            assert!((cascade - freq_response(&b, &a, w)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_firwin() {
+        use signal::*;
+        let h = nr_firwin(31, &[0.3], Window::Hamming, true, None);
+        assert!((h.data.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!((0..31).all(|i| (h.data[i] - h.data[30 - i]).abs() < 1e-15));
+        assert!(freq_response(&h.data, &[1.0], 0.6 * std::f64::consts::PI) < 5e-3);
+
+        let hp = nr_firwin(31, &[300.0], Window::Hann, false, Some(2000.0));
+        assert!((freq_response(&hp.data, &[1.0], std::f64::consts::PI) - 1.0).abs() < 1e-12);
+        assert!(freq_response(&hp.data, &[1.0], 0.0) < 5e-3);
+        let bp = nr_firwin(41, &[0.2, 0.4], Window::Blackman, false, None);
+        assert!((freq_response(&bp.data, &[1.0], 0.3 * std::f64::consts::PI) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "odd number of taps")]
+    fn test_firwin_even_highpass() {
+        signal::nr_firwin(30, &[0.3], signal::Window::Hamming, false, None);
+    }
+
+    #[test]
+    fn test_lfilter_and_filtfilt() {
+        use signal::*;
+        // y[n] = x[n] + 0.5 y[n - 1] along the first axis
+        let mut x = Array::<f64>::nr_create(&[6, 2], 2);
+        x.data[0] = 1.0;
+        x.data[1] = 2.0;
+        let y = nr_lfilter(&[1.0], &[1.0, -0.5], &x, Some(0));
+        let col0: Vec<f64> = y.data.iter().step_by(2).copied().collect();
+        assert_close(&col0, &[1.0, 0.5, 0.25, 0.125, 0.0625, 0.03125], 1e-15);
+        assert_eq!(y.data[11], 2.0 * 0.03125);
+        assert_eq!(nr_lfilter(&[2.0, 2.0], &[2.0], &matrix(&[1.0, 2.0, 3.0], &[3]), None).data, vec![1.0, 3.0, 5.0]);
+
+        let (b, a) = nr_butter(4, &[0.2], BandType::Lowpass, None).to_ba();
+        let zi = nr_lfilter_zi(&b, &a);
+        assert_eq!(zi.len(), 4);
+        let step = matrix(&[1.0; 20], &[20]);
+        let sos = nr_butter(4, &[0.2], BandType::Lowpass, None).to_sos();
+        let direct = nr_lfilter(&b, &a, &step, None);
+        let cascade = nr_sosfilt(&sos, &step, None);
+        assert_close(&direct.data, &cascade.data, 1e-12);
+
+        // Zero-phase filtering leaves a slow sine in place and a constant
+        // untouched at the edges
+        let n = 400usize;
+        let slow: Vec<f64> = (0..n).map(|i| (2.0 * std::f64::consts::PI * i as f64 / 100.0).sin()).collect();
+        let noisy: Vec<f64> = slow.iter().enumerate().map(|(i, v)| v + 0.3 * (2.8 * i as f64).sin()).collect();
+        let smooth = nr_filtfilt(&b, &a, &matrix(&noisy, &[n as i32]), None);
+        let smooth_sos = nr_sosfiltfilt(&sos, &matrix(&noisy, &[n as i32]), None);
+        for ((y, y_sos), s) in smooth.data.iter().zip(&smooth_sos.data).zip(&slow).skip(50).take(300) {
+            assert!((y - s).abs() < 0.01);
+            assert!((y_sos - y).abs() < 1e-9);
         }
+        assert_close(&nr_filtfilt(&b, &a, &matrix(&[3.0; 40], &[40]), None).data, &[3.0; 40], 1e-12);
+    }
+
+    #[test]
+    fn test_resample_and_decimate() {
+        use signal::*;
+        let tone = |n: usize| -> Array<f64> {
+            let data: Vec<f64> = (0..n).map(|i| (2.0 * std::f64::consts::PI * 3.0 * i as f64 / n as f64).cos()).collect();
+            matrix(&data, &[n as i32])
+        };
+        // Band-limited periodic signals resample exactly
+        assert_close(&nr_resample(&tone(32), 48, None).data, &tone(48).data, 1e-12);
+        assert_close(&nr_resample(&tone(32), 16, None).data, &tone(16).data, 1e-12);
+
+        let n = 800;
+        let slow: Vec<f64> = (0..n).map(|i| (2.0 * std::f64::consts::PI * i as f64 / 160.0).sin()).collect();
+        let fast: Vec<f64> = slow.iter().enumerate().map(|(i, v)| v + 0.5 * (2.0 * std::f64::consts::PI * 0.4 * i as f64).cos()).collect();
+        let mut two = Array::<f64>::nr_create(&[2, n as i32], 2);
+        two.data[..n].copy_from_slice(&fast);
+        two.data[n..].copy_from_slice(&slow);
+        let d = nr_decimate(&two, 4, None);
+        assert_eq!(d.shape, vec![2, 200]);
+        for i in 20..180 {
+            assert!((d.data[i] - slow[4 * i]).abs() < 0.02);
+            assert!((d.data[200 + i] - slow[4 * i]).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_spectral_estimates() {
+        use signal::*;
+        // A bin-centered tone of amplitude 2 has power 2^2 / 2 in its bin
+        let n = 64usize;
+        let x: Vec<f64> = (0..n).map(|i| 2.0 * (2.0 * std::f64::consts::PI * 8.0 * i as f64 / n as f64).sin()).collect();
+        let x = matrix(&x, &[n as i32]);
+        let (f, p) = nr_periodogram(&x, 64.0, Window::Boxcar, None, Scaling::Spectrum, None);
+        assert_eq!(f.shape, vec![33]);
+        assert_eq!(f.data[8], 8.0);
+        assert!((p.data[8] - 2.0).abs() < 1e-12);
+        assert!(p.data.iter().enumerate().all(|(k, &v)| k == 8 || v < 1e-20));
+        // Parseval: the density integrates to the mean power
+        let (f, d) = nr_periodogram(&x, 64.0, Window::Boxcar, None, Scaling::Density, None);
+        assert!((d.data.iter().sum::<f64>() * f.data[1] - 2.0).abs() < 1e-12);
+        let (f, d) = nr_periodogram(&x, 64.0, Window::Hann, Some(100), Scaling::Density, None);
+        assert_eq!((f.data.len(), d.data.len()), (51, 51));
+
+        // White noise of unit variance has a one-sided density of 2 / fs
+        let mut g = Generator::new(5);
+        let noise = g.normal(0.0, 1.0, &[2, 20_000]);
+        let (f, w) = nr_welch(&noise, 1.0, Window::Hann, Some(128), None, Scaling::Density, Some(1));
+        assert_eq!(w.shape, vec![2, 65]);
+        assert_eq!(f.data[64], 0.5);
+        let mean = w.data[1..64].iter().sum::<f64>() / 63.0;
+        assert!((mean - 2.0).abs() < 0.05);
+    }
+
+    // --- polynomial tests ---
+    #[test]
+    fn test_polyval_polyfit() {
+        assert_eq!(nr_polyval(&matrix(&[3.0, 0.0, 1.0], &[3]), &matrix(&[5.0, -1.0], &[2])).data, vec![76.0, 4.0]);
+
+        let x = nr_arange(0.0, 10.0, 1.0);
+        let y = nr_polyval(&matrix(&[2.0, -3.0, 1.0], &[3]), &x);
+        assert_close(&nr_polyfit(&x, &y, 2, None).unwrap().data, &[2.0, -3.0, 1.0], 1e-10);
+
+        // A zero-weight outlier is ignored
+        let mut bad = y.data.clone();
+        bad[4] += 100.0;
+        let mut w = vec![1.0; 10];
+        w[4] = 0.0;
+        let fit = nr_polyfit(&x, &matrix(&bad, &[10]), 2, Some(&matrix(&w, &[10]))).unwrap();
+        assert_close(&fit.data, &[2.0, -3.0, 1.0], 1e-10);
+
+        // One column of coefficients per data set; a line through a line
+        let mut two = Array::<f64>::nr_create(&[10, 2], 2);
+        for i in 0..10 {
+            two.data[2 * i] = y.data[i];
+            two.data[2 * i + 1] = 0.5 * i as f64 - 1.0;
+        }
+        let both = nr_polyfit(&x, &two, 2, None).unwrap();
+        assert_eq!(both.shape, vec![3, 2]);
+        assert_close(&both.data, &[2.0, 0.0, -3.0, 0.5, 1.0, -1.0], 1e-10);
+        let line = Polynomial::fit(&x, &matrix(&two.data.iter().skip(1).step_by(2).copied().collect::<Vec<_>>(), &[10]), 1, None).unwrap();
+        assert_close(&line.coef, &[-1.0, 0.5], 1e-12);
+    }
+
+    #[test]
+    fn test_roots() {
+        let mut r: Vec<f64> = nr_roots(&matrix(&[1.0, -6.0, 11.0, -6.0], &[4])).unwrap().iter().map(|z| z.re).collect();
+        r.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_close(&r, &[1.0, 2.0, 3.0], 1e-10);
+
+        let i = nr_roots(&matrix(&[1.0, 0.0, 1.0], &[3])).unwrap();
+        assert!(i.iter().all(|z| z.re.abs() < 1e-12 && (z.im.abs() - 1.0).abs() < 1e-12));
+        assert!((i[0].im + i[1].im).abs() < 1e-12);
+
+        // Trailing zero coefficients give exact zero roots
+        let z = nr_roots(&matrix(&[1.0, -1.0, 0.0], &[3])).unwrap();
+        assert!(z.contains(&num_complex::Complex::new(0.0, 0.0)));
+        assert!(z.iter().any(|v| (v.re - 1.0).abs() < 1e-12));
+        // A constant polynomial has no roots
+        assert!(nr_roots(&matrix(&[4.0], &[1])).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_polynomial_calculus_and_arithmetic() {
+        let p = matrix(&[1.0, 1.0, 1.0, 1.0], &[4]);
+        assert_eq!(nr_polyder(&p, 1).data, vec![3.0, 2.0, 1.0]);
+        assert_eq!(nr_polyder(&p, 2).data, vec![6.0, 2.0]);
+        assert_eq!(nr_polyder(&p, 5).data, vec![0.0]);
+        assert_eq!(nr_polyint(&matrix(&[3.0, 2.0, 1.0], &[3]), 1, None).data, vec![1.0, 1.0, 1.0, 0.0]);
+        assert_eq!(nr_polyint(&matrix(&[1.0], &[1]), 2, Some(&[1.0, 2.0])).data, vec![0.5, 1.0, 2.0]);
+
+        assert_eq!(nr_polymul(&matrix(&[1.0, 2.0], &[2]), &matrix(&[1.0, 3.0], &[2])).data, vec![1.0, 5.0, 6.0]);
+        let (q, r) = nr_polydiv(&matrix(&[1.0, 5.0, 7.0], &[3]), &matrix(&[1.0, 2.0], &[2]));
+        assert_eq!((q.data, r.data), (vec![1.0, 3.0], vec![1.0]));
+        let (q, r) = nr_polydiv(&matrix(&[4.0, 2.0], &[2]), &matrix(&[2.0], &[1]));
+        assert_eq!((q.data, r.data), (vec![2.0, 1.0], vec![0.0]));
+
+        let a = Polynomial::new(vec![1.0, 1.0]);
+        let b = Polynomial::new(vec![1.0, -1.0]);
+        assert_eq!((&a * &b).coef, vec![1.0, 0.0, -1.0]);
+        assert_eq!((&a - &a).coef, vec![0.0]);
+        assert_eq!((&a + &b).degree(), 0);
+        let (q, r) = (&a * &b).divrem(&b);
+        assert_eq!((q, r.coef), (a, vec![0.0]));
+    }
+
+    #[test]
+    fn test_orthogonal_bases() {
+        let t3 = OrthoSeries::new(Basis::Chebyshev, vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(t3.to_polynomial().coef, vec![0.0, -3.0, 0.0, 4.0]);
+        assert!((t3.eval(0.3) - (3.0 * 0.3f64.acos()).cos()).abs() < 1e-14);
+        let p2 = OrthoSeries::new(Basis::Legendre, vec![0.0, 0.0, 1.0]);
+        assert_close(&p2.to_polynomial().coef, &[-0.5, 0.0, 1.5], 1e-15);
+        let h3 = OrthoSeries::new(Basis::Hermite, vec![0.0, 0.0, 0.0, 1.0]);
+        assert_eq!(h3.to_polynomial().coef, vec![0.0, -12.0, 0.0, 8.0]);
+
+        // Conversions round-trip and evaluation agrees with the power form
+        let p = Polynomial::new(vec![1.0, -2.0, 0.5, 3.0, -1.0]);
+        for basis in [Basis::Chebyshev, Basis::Legendre, Basis::Hermite] {
+            let s = OrthoSeries::from_polynomial(basis, &p);
+            assert_close(&s.to_polynomial().coef, &p.coef, 1e-12);
+            for &x in &[-0.9, 0.2, 1.7] {
+                assert!((s.eval(x) - p.eval(x)).abs() < 1e-12);
+            }
+        }
+
+        // Fitting in a basis recovers an exactly representable series
+        let x = nr_arange(-1.0, 1.0, 0.1);
+        let series = OrthoSeries::new(Basis::Legendre, vec![0.5, -1.0, 2.0, 0.25]);
+        let fit = OrthoSeries::fit(Basis::Legendre, &x, &series.eval_array(&x), 3, None).unwrap();
+        assert_close(&fit.coef, &series.coef, 1e-12);
+
+        // Errors name the fitting function
+        let err = OrthoSeries::fit(Basis::Chebyshev, &x, &x, 2, Some(&matrix(&[1.0], &[1]))).unwrap_err();
+        assert!(err.to_string().starts_with("chebfit: weights"));
+    }
+
+    // --- interpolate tests ---
+    #[test]
+    fn test_interp() {
+        let xp = matrix(&[1.0, 2.0, 3.0], &[3]);
+        let fp = matrix(&[3.0, 2.0, 0.0], &[3]);
+        let x = matrix(&[0.0, 1.0, 1.5, 2.72, 3.0, 3.5], &[2, 3]);
+        let y = nr_interp(&x, &xp, &fp, None, None);
+        assert_eq!(y.shape, vec![2, 3]);
+        assert_close(&y.data, &[3.0, 3.0, 2.5, 0.56, 0.0, 0.0], 1e-12);
+        let y = nr_interp(&x, &xp, &fp, Some(-1.0), Some(-99.0));
+        assert_close(&y.data, &[-1.0, 3.0, 2.5, 0.56, 0.0, -99.0], 1e-12);
+    }
+
+    #[test]
+    fn test_cubic_spline() {
+        let x = matrix(&[0.0, 1.0, 2.0], &[3]);
+        let y = matrix(&[0.0, 1.0, 0.0], &[3]);
+        let natural = CubicSpline::new(&x, &y, SplineBoundary::Natural);
+        assert!((natural.eval(0.5) - 0.6875).abs() < 1e-14);
+        assert!(natural.derivative(0.0, 2).abs() < 1e-14 && natural.derivative(2.0, 2).abs() < 1e-14);
+        assert!((natural.integrate(0.0, 2.0) - 1.25).abs() < 1e-14);
+        let clamped = CubicSpline::new(&x, &y, SplineBoundary::Clamped);
+        assert!((clamped.eval(0.5) - 0.5).abs() < 1e-14);
+        assert!(clamped.derivative(0.0, 1).abs() < 1e-14);
+        // Not-a-knot through three points is the parabola 1 - (x - 1)^2
+        let parabola = CubicSpline::new(&x, &y, SplineBoundary::NotAKnot);
+        assert_close(&parabola.eval_array(&matrix(&[-1.0, 0.5, 2.5], &[3])).data, &[-3.0, 0.75, -1.25], 1e-14);
+
+        // Not-a-knot reproduces a cubic exactly, including beyond the ends
+        let x = matrix(&[0.0, 1.0, 2.5, 3.0, 4.5, 6.0], &[6]);
+        let f = |v: f64| v * v * v - 2.0 * v;
+        let y = Array::from_vec(x.data.iter().map(|&v| f(v)).collect(), &[6]);
+        let spline = CubicSpline::new(&x, &y, SplineBoundary::NotAKnot);
+        for &t in &[-0.5, 0.3, 2.7, 5.9, 7.0] {
+            assert!((spline.eval(t) - f(t)).abs() < 1e-10);
+            assert!((spline.derivative(t, 1) - (3.0 * t * t - 2.0)).abs() < 1e-10);
+        }
+        assert!((spline.integrate(0.0, 6.0) - 288.0).abs() < 1e-10);
+        assert!((spline.integrate(6.0, 1.0) + spline.integrate(1.0, 6.0)).abs() < 1e-10);
+        assert_eq!(spline.breakpoints(), &x.data[..]);
+    }
+
+    #[test]
+    fn test_pchip_and_akima() {
+        let x = matrix(&[0.0, 1.0, 2.0], &[3]);
+        let y = matrix(&[0.0, 1.0, 3.0], &[3]);
+        let pchip = PchipInterpolator::new(&x, &y);
+        assert!((pchip.eval(0.5) - 19.0 / 48.0).abs() < 1e-14);
+        assert!((pchip.derivative(1.0, 1) - 4.0 / 3.0).abs() < 1e-14);
+
+        // A step: neither interpolant overshoots, unlike a cubic spline
+        let x = nr_arange(0.0, 10.0, 1.0);
+        let y = matrix(&[0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 1.0], &[10]);
+        let t = nr_arange(0.0, 9.0, 0.125);
+        let akima = Akima1DInterpolator::new(&x, &y);
+        let pchip = PchipInterpolator::new(&x, &y);
+        for interp in [&*akima, &*pchip] {
+            let v = interp.eval_array(&t);
+            assert!(v.data.iter().zip(&t.data).all(|(&v, &t)| if t <= 4.0 { v == 0.0 } else if t >= 5.0 { v == 1.0 } else { v > 0.0 && v < 1.0 }));
+            assert!((interp.eval(4.25) - 0.15625).abs() < 1e-14);
+        }
+        let spline = CubicSpline::new(&x, &y, SplineBoundary::NotAKnot);
+        assert!(spline.eval_array(&t).data.iter().any(|&v| v < 0.0));
+    }
+
+    #[test]
+    fn test_regular_grid_interpolator() {
+        let points = vec![vec![0.0, 1.0, 3.0], vec![-1.0, 0.0], vec![0.0, 0.5, 1.0, 2.0]];
+        let f = |p: &[f64]| 1.0 + 2.0 * p[0] - p[1] + 3.0 * p[2];
+        let mut values = Array::nr_create(&[3, 2, 4], 3);
+        for i in 0..3 {
+            for j in 0..2 {
+                for k in 0..4 {
+                    values.data[i * 8 + j * 4 + k] = f(&[points[0][i], points[1][j], points[2][k]]);
+                }
+            }
+        }
+        let xi = matrix(&[0.5, -0.25, 0.7, 2.9, 0.0, 1.5, 0.0, -1.0, 0.0, 4.0, 1.0, 2.5], &[2, 2, 3]);
+        let expected: Vec<f64> = xi.data.chunks(3).map(f).collect();
+
+        // Multilinear interpolation is exact for a linear function, and so
+        // is extrapolation from the edge cells
+        let linear = RegularGridInterpolator::new(points.clone(), values, GridMethod::Linear, None);
+        let v = linear.eval(&xi);
+        assert_eq!(v.shape, vec![2, 2]);
+        assert_close(&v.data, &expected, 1e-12);
+        assert!((linear.eval(&matrix(&[2.0, -0.5, 1.5], &[3])).data[0] - f(&[2.0, -0.5, 1.5])).abs() < 1e-12);
+
+        let values = Array::from_vec((0..24).map(|v| v as f64).collect(), &[3, 2, 4]);
+        let nearest = RegularGridInterpolator::new(points.clone(), values, GridMethod::Nearest, Some(f64::NAN));
+        let v = nearest.eval(&xi);
+        // (0.5, -0.25, 0.7) ties to the lower x and lands on [0, 1, 1];
+        // (2.9, 0, 1.5) on [2, 1, 2]; the last point is off the grid
+        assert_eq!(v.data[..3], [5.0, 22.0, 0.0]);
+        assert!(v.data[3].is_nan());
+    }
+
+    // --- quadrature tests ---
+    #[test]
+    fn test_trapezoid_and_simpson() {
+        let y = matrix(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0], &[2, 4]);
+        assert_eq!(nr_trapezoid(&y, Spacing::Uniform(1.0), None).data, vec![7.5, 19.5]);
+        assert_eq!(nr_trapezoid(&y, Spacing::Uniform(0.5), Some(0)).data, vec![1.5, 2.0, 2.5, 3.0]);
+        let x = [0.0, 1.0, 3.0, 6.0];
+        assert_eq!(nr_trapezoid(&y, Spacing::Coords(&x), None).data, vec![17.0, 41.0]);
+        let c = nr_cumulative_trapezoid(&y, Spacing::Coords(&x), None, None);
+        assert_eq!((c.shape, c.data), (vec![2, 3], vec![1.5, 6.5, 17.0, 5.5, 18.5, 41.0]));
+        let c = nr_cumulative_trapezoid(&y, Spacing::Uniform(1.0), Some(1), Some(0.0));
+        assert_eq!(c.data[..4], [0.0, 1.5, 4.0, 7.5]);
+
+        // Simpson's rule is exact for quadratics with odd and even sample
+        // counts on uneven grids, and for cubics on uniform odd ones
+        let f = |v: f64| 3.0 * v * v - 2.0 * v + 1.0;
+        let exact = |v: f64| v * v * v - v * v + v;
+        for x in [vec![0.0, 0.5, 1.0, 1.7, 2.0], vec![0.0, 0.3, 1.0, 1.2, 2.0, 2.5]] {
+            let n = x.len() as i32;
+            let y = Array::from_vec(x.iter().map(|&v| f(v)).collect(), &[n]);
+            let s = nr_simpson(&y, Spacing::Coords(&x), None);
+            assert!((s.data[0] - exact(x[n as usize - 1])).abs() < 1e-12);
+        }
+        let y = Array::from_vec((0..5).map(|v| (v as f64 * 0.5).powi(3)).collect(), &[5]);
+        assert!((nr_simpson(&y, Spacing::Uniform(0.5), None).data[0] - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gradient() {
+        let f = matrix(&[1.0, 2.0, 4.0, 7.0, 11.0, 16.0], &[6]);
+        let g = nr_gradient(&f, &[], 1);
+        assert_eq!(g[0].data, vec![1.0, 1.5, 2.5, 3.5, 4.5, 5.0]);
+        let g = nr_gradient(&f, &[Spacing::Uniform(2.0)], 2);
+        assert_eq!(g[0].data, vec![0.25, 0.75, 1.25, 1.75, 2.25, 2.75]);
+        let x = [0.0, 1.0, 1.5, 3.5, 4.0, 6.0];
+        let g = nr_gradient(&f, &[Spacing::Coords(&x)], 1);
+        assert_close(&g[0].data, &[1.0, 3.0, 3.5, 6.7, 6.9, 2.5], 1e-12);
+
+        // Second-order differences are exact for quadratics on uneven grids
+        let y = [0.0, 0.5, 2.0, 2.5];
+        let q = |a: f64, b: f64| a * a - 3.0 * a * b + 2.0 * b * b;
+        let mut data = Vec::new();
+        for &a in &x {
+            for &b in &y {
+                data.push(q(a, b));
+            }
+        }
+        let f = Array::from_vec(data, &[6, 4]);
+        let g = nr_gradient(&f, &[Spacing::Coords(&x), Spacing::Coords(&y)], 2);
+        for (i, &a) in x.iter().enumerate() {
+            for (j, &b) in y.iter().enumerate() {
+                assert!((g[0].data[i * 4 + j] - (2.0 * a - 3.0 * b)).abs() < 1e-12);
+                assert!((g[1].data[i * 4 + j] - (4.0 * b - 3.0 * a)).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quad() {
+        let r = nr_quad(f64::sin, 0.0, std::f64::consts::PI, None, None, None);
+        assert!(r.converged && (r.value - 2.0).abs() < 1e-14 && r.abserr < 1e-10);
+        assert_eq!(r.neval, 15);
+        let r = nr_quad(|x| x.sqrt(), 1.0, 0.0, None, None, None);
+        assert!(r.converged && (r.value + 2.0 / 3.0).abs() < 1e-10);
+        let r = nr_quad(|x| 1.0 / x.sqrt(), 0.0, 1.0, None, None, Some(200));
+        assert!(r.converged && (r.value - 2.0).abs() < 1e-7);
+        let r = nr_quad(|x| (-x).exp(), 0.0, f64::INFINITY, None, None, None);
+        assert!(r.converged && (r.value - 1.0).abs() < 1e-10);
+        let r = nr_quad(|x| (-x * x).exp(), f64::NEG_INFINITY, f64::INFINITY, None, None, None);
+        assert!(r.converged && (r.value - std::f64::consts::PI.sqrt()).abs() < 1e-10);
+        let r = nr_quad(|x| 1.0 / (1.0 + x * x), f64::NEG_INFINITY, 0.0, None, None, None);
+        assert!(r.converged && (r.value - std::f64::consts::FRAC_PI_2).abs() < 1e-10);
+        // A too small subdivision limit is reported rather than hidden
+        let r = nr_quad(|x| (50.0 * x).sin(), 0.0, 10.0, Some(1e-12), Some(1e-12), Some(2));
+        assert!(!r.converged && r.abserr > 1e-12);
+    }
+
+    // --- integrate tests ---
+    #[test]
+    fn test_solve_ivp_runge_kutta() {
+        use integrate::{nr_solve_ivp, IvpOptions, IvpStatus, OdeMethod};
+
+        let decay = |_t: f64, y: &Array<f64>| Array::from_vec(y.data.iter().map(|v| -0.5 * v).collect(), &[3]);
+        let y0 = matrix(&[2.0, 4.0, 8.0], &[3]);
+        for method in [OdeMethod::Rk45, OdeMethod::Dop853] {
+            let options = IvpOptions { rtol: 1e-8, atol: 1e-10, dense_output: true, ..Default::default() };
+            let r = nr_solve_ivp(decay, (0.0, 10.0), &y0, method, options);
+            assert_eq!(r.status, IvpStatus::Finished);
+            assert_eq!((r.t[0], r.t[r.t.len() - 1]), (0.0, 10.0));
+            for (t, y) in r.t.iter().zip(&r.y) {
+                assert_close(&y.data, &[2.0, 4.0, 8.0].map(|v| v * (-0.5 * t).exp()), 1e-7);
+            }
+            // The dense output is accurate between steps too
+            let sol = r.sol.unwrap();
+            assert_eq!(sol.t_span(), (0.0, 10.0));
+            for &t in &[0.1, 3.3, 7.77] {
+                assert_close(&sol.eval(t).data, &[2.0, 4.0, 8.0].map(|v| v * (-0.5 * t).exp()), 1e-6);
+            }
+        }
+
+        // Harmonic oscillator, backwards in time and sampled at t_eval
+        let oscillator = |_t: f64, y: &Array<f64>| Array::from_vec(vec![y.data[1], -y.data[0]], &[2]);
+        let t_eval = vec![0.0, -1.0, -2.5, -10.0];
+        let options = IvpOptions { rtol: 1e-10, atol: 1e-12, t_eval: Some(t_eval.clone()), ..Default::default() };
+        let r = nr_solve_ivp(oscillator, (0.0, -10.0), &matrix(&[1.0, 0.0], &[2]), OdeMethod::Dop853, options);
+        assert_eq!(r.t, t_eval);
+        for (t, y) in r.t.iter().zip(&r.y) {
+            assert_close(&y.data, &[t.cos(), -t.sin()], 1e-8);
+        }
+        // The 8th order method needs far fewer evaluations at tight tolerances
+        let options = IvpOptions { rtol: 1e-10, atol: 1e-12, ..Default::default() };
+        let rk45 = nr_solve_ivp(oscillator, (0.0, -10.0), &matrix(&[1.0, 0.0], &[2]), OdeMethod::Rk45, options);
+        assert!(rk45.nfev > 3 * r.nfev);
+        let y = &rk45.y[rk45.y.len() - 1];
+        assert_close(&y.data, &[10f64.cos(), 10f64.sin()], 1e-8);
+    }
+
+    #[test]
+    fn test_solve_ivp_bdf() {
+        use integrate::{nr_solve_ivp, IvpOptions, IvpStatus, OdeMethod};
+
+        // Robertson's chemical kinetics, a classic stiff problem
+        let robertson = |_t: f64, y: &Array<f64>| {
+            let (a, b, c) = (y.data[0], y.data[1], y.data[2]);
+            Array::from_vec(vec![-0.04 * a + 1e4 * b * c, 0.04 * a - 1e4 * b * c - 3e7 * b * b, 3e7 * b * b], &[3])
+        };
+        let options = IvpOptions { rtol: 1e-6, atol: 1e-10, dense_output: true, ..Default::default() };
+        let r = nr_solve_ivp(robertson, (0.0, 40.0), &matrix(&[1.0, 0.0, 0.0], &[3]), OdeMethod::Bdf, options);
+        assert_eq!(r.status, IvpStatus::Finished);
+        let y = &r.y[r.y.len() - 1].data;
+        assert!((y[0] - 0.7158271).abs() < 1e-5 && (y[1] - 9.185535e-6).abs() < 1e-9 && (y[2] - 0.2841637).abs() < 1e-5);
+        assert!(r.t.len() < 300 && r.nlu > 0 && r.njev > 0);
+        let mid = r.sol.unwrap().eval(20.0);
+        assert!((mid.data.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+
+        // Linear system with eigenvalues -1 and -1000 and an analytic Jacobian
+        let a = [-500.5, 499.5, 499.5, -500.5];
+        let linear = move |_t: f64, y: &Array<f64>| Array::from_vec(vec![a[0] * y.data[0] + a[1] * y.data[1], a[2] * y.data[0] + a[3] * y.data[1]], &[2]);
+        let jac = move |_t: f64, _y: &Array<f64>| Array::from_vec(a.to_vec(), &[2, 2]);
+        let options = IvpOptions { rtol: 1e-8, atol: 1e-10, jac: Some(Box::new(jac)), ..Default::default() };
+        let r = nr_solve_ivp(linear, (0.0, 5.0), &matrix(&[2.0, 0.0], &[2]), OdeMethod::Bdf, options);
+        let (slow, fast) = ((-5.0f64).exp(), (-5000.0f64).exp());
+        assert_close(&r.y[r.y.len() - 1].data, &[slow + fast, slow - fast], 1e-7);
+        let explicit = nr_solve_ivp(linear, (0.0, 5.0), &matrix(&[2.0, 0.0], &[2]), OdeMethod::Rk45, IvpOptions::default());
+        assert!(explicit.nfev > 5 * r.nfev);
+    }
+
+    #[test]
+    fn test_solve_ivp_events() {
+        use integrate::{nr_solve_ivp, Event, IvpOptions, IvpStatus, OdeMethod};
+
+        // A ball thrown upwards: the apex is reported, landing stops the run
+        let ball = |_t: f64, y: &Array<f64>| Array::from_vec(vec![y.data[1], -9.81], &[2]);
+        let apex = Event::new(|_t, y: &Array<f64>| y.data[1], false, 0.0);
+        let landing = Event::new(|_t, y: &Array<f64>| y.data[0], true, -1.0);
+        let options = IvpOptions { events: vec![apex, landing], ..Default::default() };
+        let r = nr_solve_ivp(ball, (0.0, 100.0), &matrix(&[0.0, 10.0], &[2]), OdeMethod::Rk45, options);
+        assert_eq!(r.status, IvpStatus::Terminated);
+        assert_eq!((r.t_events[0].len(), r.t_events[1].len()), (1, 1));
+        assert!((r.t_events[0][0] - 10.0 / 9.81).abs() < 1e-10);
+        assert!((r.y_events[0][0].data[0] - 50.0 / 9.81).abs() < 1e-10);
+        let landed = 20.0 / 9.81;
+        assert!((r.t_events[1][0] - landed).abs() < 1e-10);
+        assert_eq!(r.t[r.t.len() - 1], r.t_events[1][0]);
+        assert!(r.y[r.y.len() - 1].data[0].abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_solve_ivp_event_on_step_boundary() {
+        use integrate::{nr_solve_ivp, Event, IvpOptions, OdeMethod};
+
+        // An event whose zero falls exactly on the end of the second step is
+        // reported once, there, and nowhere else
+        let rising = |t: f64, _y: &Array<f64>| Array::from_vec(vec![t.cos() + 1.5], &[1]);
+        let y0 = Array::from_vec(vec![0.0], &[1]);
+        let plain = nr_solve_ivp(rising, (0.0, 20.0), &y0, OdeMethod::Rk45, IvpOptions::default());
+        let (t2, c) = (plain.t[2], plain.y[2].data[0]);
+        for direction in [1.0, 0.0] {
+            let level = Event::new(move |_t, y: &Array<f64>| y.data[0] - c, false, direction);
+            let options = IvpOptions { events: vec![level], ..Default::default() };
+            let r = nr_solve_ivp(rising, (0.0, 20.0), &y0, OdeMethod::Rk45, options);
+            assert_eq!(r.t_events[0], vec![t2]);
+            assert_eq!(r.t, plain.t);
+        }
+
+        // A zero at the start is reported at t0
+        let start = Event::new(|_t, y: &Array<f64>| y.data[0], false, 0.0);
+        let options = IvpOptions { events: vec![start], ..Default::default() };
+        let r = nr_solve_ivp(rising, (0.0, 20.0), &y0, OdeMethod::Rk45, options);
+        assert_eq!(r.t_events[0], vec![0.0]);
+    }
+
+    // --- optimize tests ---
+    #[test]
+    fn test_scalar_roots() {
+        use optimize::{nr_brentq, nr_newton};
+
+        let r = nr_brentq(|x| x.cos() - x, 0.0, 1.0, None, None, None);
+        assert!(r.converged && (r.root - 0.7390851332151607).abs() < 1e-11);
+        let r = nr_brentq(|x| x * x * x - 2.0 * x - 5.0, 3.0, 2.0, Some(1e-14), None, None);
+        assert!(r.converged && (r.root - 2.0945514815423265).abs() < 1e-13);
+        assert_eq!(nr_brentq(|x| x - 1.0, 1.0, 4.0, None, None, None).root, 1.0);
+        let r = nr_brentq(|x| x.cos() - x, 0.0, 1.0, None, None, Some(2));
+        assert!(!r.converged && r.iterations == 2);
+
+        let r = nr_newton(|x| x * x - 2.0, 1.0, Some(&|x| 2.0 * x), None, None);
+        assert!(r.converged && (r.root - std::f64::consts::SQRT_2).abs() < 1e-15);
+        // The secant method needs no derivative
+        let r = nr_newton(|x| x * x * x - 2.0 * x - 5.0, 2.0, None, None, None);
+        assert!(r.converged && (r.root - 2.0945514815423265).abs() < 1e-12);
+        // A vanishing derivative stops Newton's method
+        let r = nr_newton(|x| x * x + 1.0, 0.0, Some(&|x| 2.0 * x), None, None);
+        assert!(!r.converged && r.root == 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "different signs")]
+    fn test_brentq_needs_bracket() {
+        optimize::nr_brentq(|x| x * x + 1.0, -1.0, 1.0, None, None, None);
+    }
+
+    #[test]
+    fn test_minimize() {
+        use optimize::{nr_minimize, MinimizeMethod};
+
+        let rosen = |x: &Array<f64>| x.data.windows(2).map(|w| 100.0 * (w[1] - w[0] * w[0]).powi(2) + (1.0 - w[0]).powi(2)).sum::<f64>();
+        let rosen_der = |x: &Array<f64>| {
+            let d = &x.data;
+            let mut g = vec![0.0; d.len()];
+            for i in 0..d.len() - 1 {
+                g[i] += -400.0 * d[i] * (d[i + 1] - d[i] * d[i]) - 2.0 * (1.0 - d[i]);
+                g[i + 1] += 200.0 * (d[i + 1] - d[i] * d[i]);
+            }
+            Array::from_vec(g, &x.shape)
+        };
+        let x0 = matrix(&[1.3, 0.7, 0.8, 1.9, 1.2], &[5]);
+
+        let r = nr_minimize(rosen, &x0, MinimizeMethod::NelderMead, None, None, Some(1e-8), Some(5000));
+        assert!(r.success && r.jac.is_none());
+        assert_close(&r.x.data, &[1.0; 5], 1e-6);
+        for method in [MinimizeMethod::Bfgs, MinimizeMethod::LBfgsB] {
+            let r = nr_minimize(rosen, &x0, method, Some(&rosen_der), None, None, None);
+            assert!(r.success && r.fun < 1e-10, "{:?}: {}", method, r.message);
+            assert_close(&r.x.data, &[1.0; 5], 1e-4);
+            assert_eq!(r.njev, r.nfev);
+            // Finite-difference gradients cost extra evaluations
+            let fd = nr_minimize(rosen, &x0, method, None, None, None, None);
+            assert!(fd.success && fd.nfev > 5 * fd.njev);
+            assert_close(&fd.x.data, &[1.0; 5], 1e-4);
+        }
+
+        // On the bound y <= 0.5 the constrained minimum has
+        // d/dx = -400 x (0.5 - x^2) - 2 (1 - x) = 0
+        let bounds = [(-1.5, 1.5), (-0.5, 0.5)];
+        let x0 = matrix(&[-1.0, 0.0], &[2]);
+        let r = nr_minimize(rosen, &x0, MinimizeMethod::LBfgsB, Some(&rosen_der), Some(&bounds), None, None);
+        let x = r.x.data[0];
+        assert!(r.success && r.x.data[1] == 0.5);
+        assert!((-400.0 * x * (0.5 - x * x) - 2.0 * (1.0 - x)).abs() < 1e-4);
+        assert!(r.jac.unwrap().data[1] < 0.0);
+        let nm = nr_minimize(rosen, &x0, MinimizeMethod::NelderMead, None, Some(&bounds), None, None);
+        assert!(nm.success && nm.x.data[1] <= 0.5 && (nm.x.data[0] - x).abs() < 1e-3);
+
+        // Variables can start on or outside a bound
+        let f = |x: &Array<f64>| (x.data[0] - 3.0).powi(2) + (x.data[1] + 1.0).powi(2) + x.data[2].powi(2);
+        let bounds = [(f64::NEG_INFINITY, 1.0), (0.0, f64::INFINITY), (-1.0, 1.0)];
+        let r = nr_minimize(f, &matrix(&[5.0, 0.0, 2.0], &[3]), MinimizeMethod::LBfgsB, None, Some(&bounds), None, None);
+        assert!(r.success);
+        assert_close(&r.x.data, &[1.0, 0.0, 0.0], 1e-6);
+
+        let r = nr_minimize(rosen, &matrix(&[-1.2, 1.0], &[2]), MinimizeMethod::Bfgs, None, None, None, Some(3));
+        assert!(!r.success && r.nit == 3);
+
+        // Finite differences stay inside the bounds, also from a start on
+        // the lower bound where the objective is undefined just below it
+        let root_slope = |x: &Array<f64>| (x.data[0] + 1.0).sqrt() + x.data[0];
+        let root_hump = |x: &Array<f64>| 3.0 * (x.data[0] + 2.0).sqrt() - x.data[0];
+        for (f, lo, hi) in [(&root_slope as &dyn Fn(&Array<f64>) -> f64, -1.0, 1.0), (&root_hump, -2.0, 5.0)] {
+            for x0 in [lo, -0.5] {
+                let r = nr_minimize(f, &matrix(&[x0], &[1]), MinimizeMethod::LBfgsB, None, Some(&[(lo, hi)]), None, None);
+                let g = r.jac.unwrap().data[0];
+                assert!(r.success && r.x.data[0] == lo, "{}", r.message);
+                assert!(g.is_finite() && g > 0.0);
+            }
+        }
+        // A variable fixed by equal bounds stays put with a zero gradient
+        let f = |x: &Array<f64>| (x.data[0] - 3.0).powi(2) + (x.data[1] - 1.0).powi(2);
+        let r = nr_minimize(f, &matrix(&[0.0, 2.0], &[2]), MinimizeMethod::LBfgsB, None, Some(&[(-10.0, 10.0), (2.0, 2.0)]), None, None);
+        assert!(r.success, "{}", r.message);
+        assert!((r.x.data[0] - 3.0).abs() < 1e-6 && r.x.data[1] == 2.0);
+        assert_eq!(r.jac.unwrap().data[1], 0.0);
+
+        // A NaN objective is a failure, not convergence
+        let r = nr_minimize(|x: &Array<f64>| x.data[0].ln(), &matrix(&[-0.5], &[1]), MinimizeMethod::LBfgsB, None, Some(&[(-1.0, 1.0)]), None, None);
+        assert!(!r.success);
+    }
+
+    #[test]
+    fn test_least_squares_and_curve_fit() {
+        use optimize::{nr_curve_fit, nr_least_squares};
+
+        // Exponential decay with an offset, recovered exactly
+        let t: Vec<f64> = (0..10).map(|i| 0.5 * i as f64).collect();
+        let y: Vec<f64> = t.iter().map(|t| 2.5 * (-1.3 * t).exp() + 0.5).collect();
+        let residuals = |p: &Array<f64>| Array::from_vec(t.iter().zip(&y).map(|(t, y)| p.data[0] * (-p.data[1] * t).exp() + p.data[2] - y).collect(), &[10]);
+        let jac = |p: &Array<f64>| {
+            let mut j = Vec::new();
+            for t in &t {
+                let e = (-p.data[1] * t).exp();
+                j.extend([e, -p.data[0] * t * e, 1.0]);
+            }
+            Array::from_vec(j, &[10, 3])
+        };
+        let x0 = matrix(&[1.0, 1.0, 0.0], &[3]);
+        for r in [nr_least_squares(residuals, &x0, None, None, None, None, None), nr_least_squares(residuals, &x0, Some(&jac), None, None, None, None)] {
+            assert!(r.success && r.cost < 1e-20, "{}", r.message);
+            assert_close(&r.x.data, &[2.5, 1.3, 0.5], 1e-8);
+            assert_eq!(r.jac.shape, vec![10, 3]);
+            assert!(r.grad.data.iter().all(|g| g.abs() < 1e-10));
+        }
+
+        // A straight line: the ordinary least-squares slope and intercept with
+        // covariance s^2 (X'X)^-1, s^2 = 0.092 / 3
+        let x = matrix(&[0.0, 1.0, 2.0, 3.0, 4.0], &[5]);
+        let y = matrix(&[1.1, 2.9, 5.2, 7.1, 8.8], &[5]);
+        let line = |x: &Array<f64>, p: &Array<f64>| Array::from_vec(x.data.iter().map(|x| p.data[0] * x + p.data[1]).collect(), &x.shape);
+        let p0 = matrix(&[1.0, 0.0], &[2]);
+        let (popt, pcov) = nr_curve_fit(line, &x, &y, &p0, None, false).unwrap();
+        assert_close(&popt.data, &[1.96, 1.1], 1e-7);
+        let s2 = 0.092 / 3.0;
+        assert_close(&pcov.data, &[s2 / 10.0, -s2 / 5.0, -s2 / 5.0, 0.6 * s2], 1e-8);
+        // With absolute sigma the covariance is (J'J)^-1 of the weighted
+        // residuals, whatever the fit quality
+        let sigma = matrix(&[0.5; 5], &[5]);
+        let (_, pcov) = nr_curve_fit(line, &x, &y, &p0, Some(&sigma), true).unwrap();
+        assert_close(&pcov.data, &[0.025, -0.05, -0.05, 0.15], 1e-8);
+        // Two points cannot pin down the spread of the data
+        let (_, pcov) = nr_curve_fit(line, &matrix(&[0.0, 1.0], &[2]), &matrix(&[1.0, 3.0], &[2]), &p0, None, false).unwrap();
+        assert!(pcov.data.iter().all(|v| v.is_infinite()));
+    }
+
+    // --- special function tests (references from mpmath) ---
+    fn assert_rel_close(actual: &[f64], expected: &[f64], tol: f64) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() <= tol * e.abs(), "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_gamma_family() {
+        let x = matrix(&[0.5, 4.5, -1.5, 1e-3, 30.2], &[5]);
+        assert_rel_close(&nr_gamma(&x).data, &[1.772453850905516, 11.63172839656745, 2.363271801207355, 999.4237724845955, 1.7410094445911312e31], 1e-14);
+        assert_eq!(nr_gamma(&matrix(&[5.0, 1.0], &[2])).data, vec![24.0, 1.0]);
+        let poles = nr_gamma(&matrix(&[0.0, -0.0, -3.0, 172.0], &[4])).data;
+        assert!(poles[0] == f64::INFINITY && poles[1] == f64::NEG_INFINITY && poles[2].is_nan() && poles[3] == f64::INFINITY);
+
+        let x = matrix(&[0.5, -2.5, 100.0, 1e-10], &[4]);
+        assert_rel_close(&nr_lgamma(&x).data, &[0.5723649429247001, -0.056243716497674051, 359.1342053695754, 23.025850929882735], 1e-14);
+        assert_eq!(nr_lgamma(&matrix(&[-2.0], &[1])).data, vec![f64::INFINITY]);
+
+        let x = matrix(&[1.0, 0.5, -0.5, 10.0], &[4]);
+        assert_rel_close(&nr_digamma(&x).data, &[-0.5772156649015329, -1.9635100260214235, 0.03648997397857652, 2.251752589066721], 1e-14);
+
+        // Broadcasting a column against a row
+        let b = nr_beta(&matrix(&[2.5, -0.5], &[2, 1]), &matrix(&[1.5], &[1]));
+        assert_eq!(b.shape, vec![2, 1]);
+        assert_rel_close(&b.data, &[0.19634954084936208, -std::f64::consts::PI], 1e-14);
+        assert_rel_close(&nr_beta(&matrix(&[300.0], &[1]), &matrix(&[200.0], &[1])).data, &[1.6485491608664746e-147], 1e-12);
+
+        // f32 arrays are evaluated in f64 and rounded; 0.3f32 is 0.30000001192...
+        let x = Array::from_vec(vec![0.3f32], &[1]);
+        assert_eq!(nr_gamma(&x).data, vec![2.9915688627795915f64 as f32]);
+    }
+
+    #[test]
+    fn test_incomplete_gamma_and_beta() {
+        let a = matrix(&[2.0, 0.5, 10.0, 5.0], &[4]);
+        let b = matrix(&[3.0, 0.5, 20.0, 1.5], &[4]);
+        let x = matrix(&[0.4, 0.1, 0.3, 0.9], &[4]);
+        assert_rel_close(&nr_betainc(&a, &b, &x).data, &[0.5248, 0.20483276469913346, 0.3640040810719442, 0.7761721343162157], 1e-13);
+        let ends = nr_betainc(&matrix(&[2.0], &[1]), &matrix(&[3.0], &[1]), &matrix(&[0.0, 1.0, 1.5], &[3])).data;
+        assert!(ends[0] == 0.0 && ends[1] == 1.0 && ends[2].is_nan());
+
+        let a = matrix(&[1.0, 0.5, 3.0, 50.0], &[4]);
+        let x = matrix(&[2.0, 0.3, 5.0, 40.0], &[4]);
+        assert_rel_close(&nr_gammainc(&a, &x).data, &[0.8646647167633873, 0.5614219739190002, 0.8753479805169189, 0.07033506665939496], 1e-13);
+        // P(1, x) = 1 - e^-x, broadcast over x
+        let p = nr_gammainc(&matrix(&[1.0], &[1]), &matrix(&[0.0, 0.7, f64::INFINITY], &[3]));
+        assert_close(&p.data, &[0.0, 1.0 - (-0.7f64).exp(), 1.0], 1e-15);
+        assert!(nr_gammainc(&matrix(&[-1.0], &[1]), &matrix(&[1.0], &[1])).data[0].is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be broadcast")]
+    fn test_special_broadcast_mismatch() {
+        nr_gammainc(&matrix(&[1.0, 2.0], &[2]), &matrix(&[1.0, 2.0, 3.0], &[3]));
+    }
+
+    #[test]
+    fn test_erf_family() {
+        let x = matrix(&[0.1, 1.0, -2.0, 3.5], &[4]);
+        assert_rel_close(&nr_erf(&x).data, &[0.1124629160182849, 0.8427007929497149, -0.9953222650189527, 0.9999992569016276], 1e-15);
+        // erfc keeps its relative accuracy far into the tail
+        let x = matrix(&[0.5, -1.0, 5.0, 10.0, 26.0], &[5]);
+        assert_rel_close(&nr_erfc(&x).data, &[0.4795001221869535, 1.842700792949715, 1.537459794428035e-12, 2.088487583762545e-45, 5.663192408856143e-296], 1e-14);
+
+        let y = matrix(&[0.5, -0.9, 0.999999], &[3]);
+        assert_rel_close(&nr_erfinv(&y).data, &[0.4769362762044699, -1.1630871536766742, 3.458910737275499], 1e-14);
+        let round_trip = nr_erf(&nr_erfinv(&matrix(&[-0.3, 1e-12, 0.75], &[3])));
+        assert_rel_close(&round_trip.data, &[-0.3, 1e-12, 0.75], 1e-15);
+        let ends = nr_erfinv(&matrix(&[1.0, -1.0, 1.5], &[3])).data;
+        assert!(ends[0] == f64::INFINITY && ends[1] == f64::NEG_INFINITY && ends[2].is_nan());
+    }
+
+    #[test]
+    fn test_bessel() {
+        // Both sides of the switch to the asymptotic expansions at x = 25
+        let x = matrix(&[0.5, 2.4, 10.0, -7.0, 30.0, 100.0], &[6]);
+        assert_close(&nr_j0(&x).data, &[0.9384698072408129, 0.002507683297243859, -0.24593576445134834, 0.3000792705195556, -0.08636798358104021, 0.019985850304223122], 1e-15);
+        assert_close(&nr_j1(&x).data, &[0.2422684576748739, 0.520185268181931, 0.04347274616886144, 0.004682823482345833, -0.11875106261662294, -0.07714535201411216], 1e-15);
+        let x = matrix(&[0.5, 2.4, 10.0, 30.0, 100.0], &[5]);
+        assert_close(&nr_y0(&x).data, &[-0.44451873350670656, 0.5104147486657438, 0.05567116728359939, -0.11729573168666403, -0.07724431336508315], 1e-15);
+        assert_close(&nr_y1(&x).data, &[-1.471472392670243, 0.10048893833108442, 0.24901542420695388, 0.08442557066174723, -0.020372312002759793], 1e-15);
+        let edges = matrix(&[0.0, -1.0], &[2]);
+        assert_eq!(nr_j0(&edges).data[0], 1.0);
+        assert_eq!(nr_y0(&edges).data[0], f64::NEG_INFINITY);
+        assert!(nr_y1(&edges).data[1].is_nan());
+        // Tiny arguments, where the backward recurrence would overflow
+        let x = matrix(&[1e-300, 1e-60, 1e-10, 3e-5], &[4]);
+        assert_rel_close(&nr_j0(&x).data, &[1.0, 1.0, 1.0, 0.999999999775], 1e-15);
+        assert_rel_close(&nr_j1(&x).data, &[5e-301, 5e-61, 5e-11, 1.49999999983125e-5], 1e-15);
+        assert_rel_close(&nr_y0(&x).data, &[-439.8351636227653, -88.02607616064002, -14.732516272697241, -6.703761977119259], 1e-15);
+        assert_rel_close(&nr_y1(&x).data, &[-6.366197723675813e299, -6.366197723675814e59, -6366197723.675814, -21220.659184250457], 1e-15);
+        let inf = matrix(&[f64::INFINITY, f64::NEG_INFINITY], &[2]);
+        assert_eq!(nr_j0(&inf).data, vec![0.0, 0.0]);
+        assert_eq!(nr_j1(&inf).data, vec![0.0, 0.0]);
+        assert_eq!((nr_y0(&inf).data[0], nr_y1(&inf).data[0]), (0.0, 0.0));
+
+        let x = matrix(&[0.5, -3.0, 20.0, 50.0, 700.0], &[5]);
+        assert_rel_close(&nr_i0(&x).data, &[1.0634833707413235, 4.8807925858650241, 43558282.559553533, 2.9325537838493363e20, 1.5295933476718737e302], 1e-14);
+        assert_eq!(nr_i0(&inf).data, vec![f64::INFINITY; 2]);
+    }
+
+    #[test]
+    fn test_expit_logit_xlogy_sinc() {
+        let x = matrix(&[-750.0, -40.0, 0.0, 2.0], &[4]);
+        assert_rel_close(&nr_expit(&x).data, &[0.0, 4.248354255291589e-18, 0.5, 0.8807970779778824], 1e-15);
+        let p = matrix(&[0.1, 0.5, 0.6, 0.999], &[4]);
+        assert_close(&nr_logit(&p).data, &[-2.197224577336219, 0.0, 0.4054651081081643, 6.906754778648553], 1e-15);
+        let ends = nr_logit(&matrix(&[0.0, 1.0, -0.1], &[3])).data;
+        assert!(ends[0] == f64::NEG_INFINITY && ends[1] == f64::INFINITY && ends[2].is_nan());
+
+        let r = nr_xlogy(&matrix(&[0.0, 2.0, 0.0], &[3]), &matrix(&[0.0, 3.0, f64::NAN], &[3]));
+        assert_eq!(&r.data[..2], &[0.0, 2.0 * 3.0f64.ln()]);
+        assert!(r.data[2].is_nan());
+
+        let s = nr_sinc(&matrix(&[0.0, 0.5, 1.0, -3.0, 2.5], &[5]));
+        assert_close(&s.data, &[1.0, 2.0 / std::f64::consts::PI, 0.0, 0.0, 0.4 / std::f64::consts::PI], 1e-16);
+        assert_eq!(s.data[2], 0.0);
+        assert!(nr_sinc(&matrix(&[f64::INFINITY, f64::NEG_INFINITY], &[2])).data.iter().all(|v| v.is_nan()));
     }
-}
\ No newline at end of file
+}
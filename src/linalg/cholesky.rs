@@ -0,0 +1,86 @@
+use super::{pair_systems, square_dims, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_traits::Float;
+
+// Lower Cholesky factor of a row-major n x n matrix, reading only its
+// lower triangle. Fails on the first pivot that is not positive.
+pub(crate) fn cholesky_lower<T: Float>(a: &[T], n: usize) -> Result<Vec<T>, LinalgError> {
+    let mut l = vec![T::zero(); n * n];
+    for j in 0..n {
+        let mut d = a[j * n + j];
+        for k in 0..j {
+            d = d - l[j * n + k] * l[j * n + k];
+        }
+        if d <= T::zero() || d.is_nan() {
+            return Err(LinalgError::NotPositiveDefinite);
+        }
+        let d = d.sqrt();
+        l[j * n + j] = d;
+        for i in j + 1..n {
+            let mut s = a[i * n + j];
+            for k in 0..j {
+                s = s - l[i * n + k] * l[j * n + k];
+            }
+            l[i * n + j] = s / d;
+        }
+    }
+    Ok(l)
+}
+
+// Solve L L^T X = B given the lower factor; B is n x nrhs, row-major
+pub(crate) fn cholesky_solve<T: Float>(l: &[T], n: usize, b: &[T], nrhs: usize) -> Vec<T> {
+    let mut x = b.to_vec();
+    for i in 0..n {
+        for k in 0..i {
+            let lik = l[i * n + k];
+            for c in 0..nrhs {
+                x[i * nrhs + c] = x[i * nrhs + c] - lik * x[k * nrhs + c];
+            }
+        }
+        for c in 0..nrhs {
+            x[i * nrhs + c] = x[i * nrhs + c] / l[i * n + i];
+        }
+    }
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            let lki = l[k * n + i];
+            for c in 0..nrhs {
+                x[i * nrhs + c] = x[i * nrhs + c] - lki * x[k * nrhs + c];
+            }
+        }
+        for c in 0..nrhs {
+            x[i * nrhs + c] = x[i * nrhs + c] / l[i * n + i];
+        }
+    }
+    x
+}
+
+// Cholesky factorization A = L @ L^T of a (stack of) symmetric
+// positive-definite matrices (np.linalg.cholesky). Only the lower triangle
+// of A is read. Returns LinalgError::NotPositiveDefinite otherwise.
+pub fn nr_cholesky<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut out = Vec::with_capacity(a.totalsize);
+    for src in a.data.chunks(n * n) {
+        out.extend(cholesky_lower(src, n)?);
+    }
+    Ok(Array::from_vec(out, &stacked_shape(&batch, &[n, n])))
+}
+
+// Solve A @ x = b given the lower Cholesky factor L of A (scipy.linalg.cho_solve).
+// Shapes and broadcasting follow nr_solve.
+pub fn nr_cho_solve<T: Float>(l: &Array<T>, b: &Array<T>) -> Result<Array<T>, LinalgError> {
+    square_dims(l)?;
+    let systems = pair_systems(l, b, "cho_solve")?;
+    let (n, k) = (systems.n, systems.k);
+
+    let mut out = Vec::with_capacity(systems.offsets.len() * n * k);
+    for &(l_off, b_off) in &systems.offsets {
+        let lm = &l.data[l_off..l_off + n * n];
+        if (0..n).any(|i| lm[i * n + i] == T::zero()) {
+            return Err(LinalgError::Singular);
+        }
+        out.extend(cholesky_solve(lm, n, &b.data[b_off..b_off + n * k], k));
+    }
+    Ok(Array::from_vec(out, &systems.out_shape(n)))
+}
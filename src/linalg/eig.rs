@@ -0,0 +1,606 @@
+use super::{square_dims, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_complex::Complex;
+use num_traits::Float;
+
+// Sweeps of cyclic Jacobi before reporting non-convergence
+const MAX_JACOBI_SWEEPS: usize = 100;
+// QR iterations allowed per eigenvalue in the Hessenberg QR algorithm
+const MAX_QR_ITERATIONS: usize = 100;
+
+// Eigen-decomposition of a symmetric row-major n x n matrix by cyclic Jacobi
+// rotations, reading only the lower triangle. Returns ascending eigenvalues
+// and the eigenvectors as the columns of a row-major n x n matrix.
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn jacobi_eigh<T: Float>(src: &[T], n: usize) -> Result<(Vec<T>, Vec<T>), LinalgError> {
+    let mut a = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            a[i][j] = src[i * n + j];
+            a[j][i] = src[i * n + j];
+        }
+    }
+    let mut v = vec![vec![T::zero(); n]; n];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+
+    let frob = a.iter().flatten().fold(T::zero(), |acc, &x| acc + x * x).sqrt();
+    let tol = T::epsilon() * frob;
+    let mut converged = false;
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let mut off = T::zero();
+        for i in 0..n {
+            for j in i + 1..n {
+                off = off + a[i][j] * a[i][j];
+            }
+        }
+        if off.sqrt() <= tol {
+            converged = true;
+            break;
+        }
+
+        let two = T::one() + T::one();
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q] == T::zero() {
+                    continue;
+                }
+                // Rotation J with J^T A J zeroing a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (two * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + T::one()).sqrt());
+                let c = T::one() / (t * t + T::one()).sqrt();
+                let s = t * c;
+                for row in a.iter_mut() {
+                    let (akp, akq) = (row[p], row[q]);
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[p][k], a[q][k]);
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+                a[p][q] = T::zero();
+                a[q][p] = T::zero();
+                for row in v.iter_mut() {
+                    let (vkp, vkq) = (row[p], row[q]);
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    if !converged {
+        return Err(LinalgError::NoConvergence);
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&x, &y| a[x][x].partial_cmp(&a[y][y]).unwrap_or(std::cmp::Ordering::Equal));
+    let w = order.iter().map(|&i| a[i][i]).collect();
+    let mut vecs = vec![T::zero(); n * n];
+    for i in 0..n {
+        for (c, &j) in order.iter().enumerate() {
+            vecs[i * n + c] = v[i][j];
+        }
+    }
+    Ok((w, vecs))
+}
+
+// Reduce h to upper Hessenberg form by Householder similarity transforms,
+// returning the accumulated orthogonal transform
+#[allow(clippy::needless_range_loop)]
+fn hessenberg<T: Float>(h: &mut [Vec<T>]) -> Vec<Vec<T>> {
+    let n = h.len();
+    let mut ort = vec![T::zero(); n];
+    for m in 1..n.saturating_sub(1) {
+        let scale = (m..n).fold(T::zero(), |acc, i| acc + h[i][m - 1].abs());
+        if scale == T::zero() {
+            continue;
+        }
+        let mut hh = T::zero();
+        for i in (m..n).rev() {
+            ort[i] = h[i][m - 1] / scale;
+            hh = hh + ort[i] * ort[i];
+        }
+        let mut g = hh.sqrt();
+        if ort[m] > T::zero() {
+            g = -g;
+        }
+        hh = hh - ort[m] * g;
+        ort[m] = ort[m] - g;
+
+        // H = (I - u u^T / hh) H (I - u u^T / hh)
+        for j in m..n {
+            let f = (m..n).rev().fold(T::zero(), |acc, i| acc + ort[i] * h[i][j]) / hh;
+            for i in m..n {
+                h[i][j] = h[i][j] - f * ort[i];
+            }
+        }
+        for row in h.iter_mut() {
+            let f = (m..n).rev().fold(T::zero(), |acc, j| acc + ort[j] * row[j]) / hh;
+            for j in m..n {
+                row[j] = row[j] - f * ort[j];
+            }
+        }
+        ort[m] = scale * ort[m];
+        h[m][m - 1] = scale * g;
+    }
+
+    let mut v = vec![vec![T::zero(); n]; n];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    for m in (1..n.saturating_sub(1)).rev() {
+        if h[m][m - 1] == T::zero() {
+            continue;
+        }
+        for i in m + 1..n {
+            ort[i] = h[i][m - 1];
+        }
+        for j in m..n {
+            let g = (m..n).fold(T::zero(), |acc, i| acc + ort[i] * v[i][j]);
+            // Double division avoids possible underflow
+            let g = (g / ort[m]) / h[m][m - 1];
+            for i in m..n {
+                v[i][j] = v[i][j] + g * ort[i];
+            }
+        }
+    }
+    v
+}
+
+// Complex division (xr + i xi) / (yr + i yi)
+fn cdiv<T: Float>(xr: T, xi: T, yr: T, yi: T) -> (T, T) {
+    if yr.abs() > yi.abs() {
+        let r = yi / yr;
+        let d = yr + r * yi;
+        ((xr + r * xi) / d, (xi - r * xr) / d)
+    } else {
+        let r = yr / yi;
+        let d = yi + r * yr;
+        ((r * xr + xi) / d, (r * xi - xr) / d)
+    }
+}
+
+// Eigenvalues (re, im) of a general real matrix and, when requested, the
+// real Schur vectors back-substituted into eigenvectors. Complex pairs are
+// stored as in EISPACK's hqr2: columns j and j+1 hold the real and
+// imaginary parts of the eigenvector of re[j] + i im[j], with im[j] > 0.
+#[allow(clippy::type_complexity, clippy::needless_range_loop)]
+fn hqr2<T: Float>(src: &[T], n: usize, want_vectors: bool) -> Result<(Vec<T>, Vec<T>, Vec<Vec<T>>), LinalgError> {
+    let mut h: Vec<Vec<T>> = (0..n).map(|i| src[i * n..(i + 1) * n].to_vec()).collect();
+    let mut v = hessenberg(&mut h);
+    let mut d = vec![T::zero(); n];
+    let mut e = vec![T::zero(); n];
+    if n == 0 {
+        return Ok((d, e, v));
+    }
+
+    let c = |x: f64| T::from(x).unwrap();
+    let two = c(2.0);
+    let eps = T::epsilon();
+    let mut exshift = T::zero();
+    let (mut r, mut s, mut z) = (T::zero(), T::zero(), T::zero());
+    let (mut p, mut q, mut w, mut x, mut y);
+
+    let mut norm = T::zero();
+    for i in 0..n {
+        for j in i.saturating_sub(1)..n {
+            norm = norm + h[i][j].abs();
+        }
+    }
+
+    // Deflate one or two eigenvalues at a time from the bottom
+    let mut nn = n as isize - 1;
+    let mut iter = 0;
+    while nn >= 0 {
+        let en = nn as usize;
+        let mut l = en;
+        while l > 0 {
+            s = h[l - 1][l - 1].abs() + h[l][l].abs();
+            if s == T::zero() {
+                s = norm;
+            }
+            if h[l][l - 1].abs() < eps * s {
+                break;
+            }
+            l -= 1;
+        }
+
+        if l == en {
+            // One real root
+            h[en][en] = h[en][en] + exshift;
+            d[en] = h[en][en];
+            e[en] = T::zero();
+            nn -= 1;
+            iter = 0;
+        } else if l + 1 == en {
+            // Two roots
+            w = h[en][en - 1] * h[en - 1][en];
+            p = (h[en - 1][en - 1] - h[en][en]) / two;
+            q = p * p + w;
+            z = q.abs().sqrt();
+            h[en][en] = h[en][en] + exshift;
+            h[en - 1][en - 1] = h[en - 1][en - 1] + exshift;
+            x = h[en][en];
+
+            if q >= T::zero() {
+                // Real pair
+                z = if p >= T::zero() { p + z } else { p - z };
+                d[en - 1] = x + z;
+                d[en] = d[en - 1];
+                if z != T::zero() {
+                    d[en] = x - w / z;
+                }
+                e[en - 1] = T::zero();
+                e[en] = T::zero();
+                x = h[en][en - 1];
+                s = x.abs() + z.abs();
+                p = x / s;
+                q = z / s;
+                r = (p * p + q * q).sqrt();
+                p = p / r;
+                q = q / r;
+
+                for j in en - 1..n {
+                    z = h[en - 1][j];
+                    h[en - 1][j] = q * z + p * h[en][j];
+                    h[en][j] = q * h[en][j] - p * z;
+                }
+                for row in h.iter_mut().take(en + 1) {
+                    z = row[en - 1];
+                    row[en - 1] = q * z + p * row[en];
+                    row[en] = q * row[en] - p * z;
+                }
+                for row in v.iter_mut() {
+                    z = row[en - 1];
+                    row[en - 1] = q * z + p * row[en];
+                    row[en] = q * row[en] - p * z;
+                }
+            } else {
+                // Complex pair
+                d[en - 1] = x + p;
+                d[en] = x + p;
+                e[en - 1] = z;
+                e[en] = -z;
+            }
+            nn -= 2;
+            iter = 0;
+        } else {
+            // No convergence yet, form the shift
+            x = h[en][en];
+            y = h[en - 1][en - 1];
+            w = h[en][en - 1] * h[en - 1][en];
+
+            // Wilkinson's original ad hoc shift
+            if iter == 10 {
+                exshift = exshift + x;
+                for (i, row) in h.iter_mut().enumerate().take(en + 1) {
+                    row[i] = row[i] - x;
+                }
+                s = h[en][en - 1].abs() + h[en - 1][en - 2].abs();
+                x = c(0.75) * s;
+                y = x;
+                w = c(-0.4375) * s * s;
+            }
+            // MATLAB's ad hoc shift
+            if iter == 30 {
+                s = (y - x) / two;
+                s = s * s + w;
+                if s > T::zero() {
+                    s = s.sqrt();
+                    if y < x {
+                        s = -s;
+                    }
+                    s = x - w / ((y - x) / two + s);
+                    for (i, row) in h.iter_mut().enumerate().take(en + 1) {
+                        row[i] = row[i] - s;
+                    }
+                    exshift = exshift + s;
+                    x = c(0.964);
+                    y = x;
+                    w = x;
+                }
+            }
+
+            iter += 1;
+            if iter > MAX_QR_ITERATIONS {
+                return Err(LinalgError::NoConvergence);
+            }
+
+            // Look for two consecutive small sub-diagonal elements
+            let mut m = en - 2;
+            loop {
+                z = h[m][m];
+                r = x - z;
+                s = y - z;
+                p = (r * s - w) / h[m + 1][m] + h[m][m + 1];
+                q = h[m + 1][m + 1] - z - r - s;
+                r = h[m + 2][m + 1];
+                s = p.abs() + q.abs() + r.abs();
+                p = p / s;
+                q = q / s;
+                r = r / s;
+                if m == l {
+                    break;
+                }
+                if h[m][m - 1].abs() * (q.abs() + r.abs())
+                    < eps * (p.abs() * (h[m - 1][m - 1].abs() + z.abs() + h[m + 1][m + 1].abs()))
+                {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in m + 2..=en {
+                h[i][i - 2] = T::zero();
+                if i > m + 2 {
+                    h[i][i - 3] = T::zero();
+                }
+            }
+
+            // Double QR step on rows l..=en and columns m..=en
+            for k in m..en {
+                let notlast = k != en - 1;
+                if k != m {
+                    p = h[k][k - 1];
+                    q = h[k + 1][k - 1];
+                    r = if notlast { h[k + 2][k - 1] } else { T::zero() };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x == T::zero() {
+                        continue;
+                    }
+                    p = p / x;
+                    q = q / x;
+                    r = r / x;
+                }
+                s = (p * p + q * q + r * r).sqrt();
+                if p < T::zero() {
+                    s = -s;
+                }
+                if s == T::zero() {
+                    continue;
+                }
+                if k != m {
+                    h[k][k - 1] = -s * x;
+                } else if l != m {
+                    h[k][k - 1] = -h[k][k - 1];
+                }
+                p = p + s;
+                x = p / s;
+                y = q / s;
+                z = r / s;
+                q = q / p;
+                r = r / p;
+
+                for j in k..n {
+                    p = h[k][j] + q * h[k + 1][j];
+                    if notlast {
+                        p = p + r * h[k + 2][j];
+                        h[k + 2][j] = h[k + 2][j] - p * z;
+                    }
+                    h[k][j] = h[k][j] - p * x;
+                    h[k + 1][j] = h[k + 1][j] - p * y;
+                }
+                for row in h.iter_mut().take(en.min(k + 3) + 1) {
+                    p = x * row[k] + y * row[k + 1];
+                    if notlast {
+                        p = p + z * row[k + 2];
+                        row[k + 2] = row[k + 2] - p * r;
+                    }
+                    row[k] = row[k] - p;
+                    row[k + 1] = row[k + 1] - p * q;
+                }
+                for row in v.iter_mut() {
+                    p = x * row[k] + y * row[k + 1];
+                    if notlast {
+                        p = p + z * row[k + 2];
+                        row[k + 2] = row[k + 2] - p * r;
+                    }
+                    row[k] = row[k] - p;
+                    row[k + 1] = row[k + 1] - p * q;
+                }
+            }
+        }
+    }
+
+    if !want_vectors || norm == T::zero() {
+        return Ok((d, e, v));
+    }
+
+    // Back-substitute to find the vectors of the upper triangular form
+    for en in (0..n).rev() {
+        p = d[en];
+        q = e[en];
+        if q == T::zero() {
+            // Real vector
+            let mut l = en;
+            h[en][en] = T::one();
+            for i in (0..en).rev() {
+                w = h[i][i] - p;
+                r = (l..=en).fold(T::zero(), |acc, j| acc + h[i][j] * h[j][en]);
+                if e[i] < T::zero() {
+                    z = w;
+                    s = r;
+                    continue;
+                }
+                l = i;
+                if e[i] == T::zero() {
+                    h[i][en] = if w != T::zero() { -r / w } else { -r / (eps * norm) };
+                } else {
+                    // Solve the real 2x2 system
+                    x = h[i][i + 1];
+                    y = h[i + 1][i];
+                    q = (d[i] - p) * (d[i] - p) + e[i] * e[i];
+                    let t = (x * s - z * r) / q;
+                    h[i][en] = t;
+                    h[i + 1][en] = if x.abs() > z.abs() { (-r - w * t) / x } else { (-s - y * t) / z };
+                }
+                // Overflow control
+                let t = h[i][en].abs();
+                if (eps * t) * t > T::one() {
+                    for row in h.iter_mut().take(en + 1).skip(i) {
+                        row[en] = row[en] / t;
+                    }
+                }
+            }
+        } else if q < T::zero() {
+            // Complex vector, stored in columns en-1 (real) and en (imaginary)
+            let mut l = en - 1;
+            if h[en][en - 1].abs() > h[en - 1][en].abs() {
+                h[en - 1][en - 1] = q / h[en][en - 1];
+                h[en - 1][en] = -(h[en][en] - p) / h[en][en - 1];
+            } else {
+                let (cr, ci) = cdiv(T::zero(), -h[en - 1][en], h[en - 1][en - 1] - p, q);
+                h[en - 1][en - 1] = cr;
+                h[en - 1][en] = ci;
+            }
+            h[en][en - 1] = T::zero();
+            h[en][en] = T::one();
+            for i in (0..en - 1).rev() {
+                let ra = (l..=en).fold(T::zero(), |acc, j| acc + h[i][j] * h[j][en - 1]);
+                let sa = (l..=en).fold(T::zero(), |acc, j| acc + h[i][j] * h[j][en]);
+                w = h[i][i] - p;
+                if e[i] < T::zero() {
+                    z = w;
+                    r = ra;
+                    s = sa;
+                    continue;
+                }
+                l = i;
+                if e[i] == T::zero() {
+                    let (cr, ci) = cdiv(-ra, -sa, w, q);
+                    h[i][en - 1] = cr;
+                    h[i][en] = ci;
+                } else {
+                    // Solve the complex 2x2 system
+                    x = h[i][i + 1];
+                    y = h[i + 1][i];
+                    let mut vr = (d[i] - p) * (d[i] - p) + e[i] * e[i] - q * q;
+                    let vi = (d[i] - p) * two * q;
+                    if vr == T::zero() && vi == T::zero() {
+                        vr = eps * norm * (w.abs() + q.abs() + x.abs() + y.abs() + z.abs());
+                    }
+                    let (cr, ci) = cdiv(x * r - z * ra + q * sa, x * s - z * sa - q * ra, vr, vi);
+                    h[i][en - 1] = cr;
+                    h[i][en] = ci;
+                    if x.abs() > z.abs() + q.abs() {
+                        h[i + 1][en - 1] = (-ra - w * h[i][en - 1] + q * h[i][en]) / x;
+                        h[i + 1][en] = (-sa - w * h[i][en] - q * h[i][en - 1]) / x;
+                    } else {
+                        let (cr, ci) = cdiv(-r - y * h[i][en - 1], -s - y * h[i][en], z, q);
+                        h[i + 1][en - 1] = cr;
+                        h[i + 1][en] = ci;
+                    }
+                }
+                // Overflow control
+                let t = h[i][en - 1].abs().max(h[i][en].abs());
+                if (eps * t) * t > T::one() {
+                    for row in h.iter_mut().take(en + 1).skip(i) {
+                        row[en - 1] = row[en - 1] / t;
+                        row[en] = row[en] / t;
+                    }
+                }
+            }
+        }
+    }
+
+    // Back-transform to eigenvectors of the original matrix
+    for j in (0..n).rev() {
+        for row in v.iter_mut() {
+            let z = (0..=j).fold(T::zero(), |acc, k| acc + row[k] * h[k][j]);
+            row[j] = z;
+        }
+    }
+    Ok((d, e, v))
+}
+
+// Eigenvalues and eigenvectors of a general real row-major n x n matrix.
+// Eigenvectors are the unit-norm columns of the returned n x n matrix.
+#[allow(clippy::type_complexity)]
+pub(crate) fn general_eig<T: Float>(src: &[T], n: usize) -> Result<(Vec<Complex<T>>, Vec<Complex<T>>), LinalgError> {
+    let (d, e, v) = hqr2(src, n, true)?;
+    let values: Vec<Complex<T>> = d.iter().zip(&e).map(|(&re, &im)| Complex::new(re, im)).collect();
+
+    let mut vecs = vec![Complex::new(T::zero(), T::zero()); n * n];
+    let mut j = 0;
+    while j < n {
+        if e[j] > T::zero() && j + 1 < n {
+            // Conjugate pair sharing columns j (real) and j + 1 (imaginary)
+            for i in 0..n {
+                vecs[i * n + j] = Complex::new(v[i][j], v[i][j + 1]);
+                vecs[i * n + j + 1] = Complex::new(v[i][j], -v[i][j + 1]);
+            }
+            j += 2;
+        } else {
+            for i in 0..n {
+                vecs[i * n + j] = Complex::new(v[i][j], T::zero());
+            }
+            j += 1;
+        }
+    }
+    for col in 0..n {
+        let norm = (0..n).fold(T::zero(), |acc, i| acc + vecs[i * n + col].norm_sqr()).sqrt();
+        if norm > T::zero() {
+            for i in 0..n {
+                vecs[i * n + col] = vecs[i * n + col] / norm;
+            }
+        }
+    }
+    Ok((values, vecs))
+}
+
+// Eigenvalues and eigenvectors of (a stack of) symmetric matrices
+// (np.linalg.eigh). Only the lower triangle is read. Eigenvalues are
+// ascending with shape (..., n); eigenvectors are the columns of (..., n, n).
+pub fn nr_eigh<T: Float>(a: &Array<T>) -> Result<(Array<T>, Array<T>), LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut w_data = Vec::with_capacity(a.totalsize / n.max(1));
+    let mut v_data = Vec::with_capacity(a.totalsize);
+    for src in a.data.chunks(n * n) {
+        let (w, v) = jacobi_eigh(src, n)?;
+        w_data.extend(w);
+        v_data.extend(v);
+    }
+    Ok((
+        Array::from_vec(w_data, &stacked_shape(&batch, &[n])),
+        Array::from_vec(v_data, &stacked_shape(&batch, &[n, n])),
+    ))
+}
+
+// Ascending eigenvalues of (a stack of) symmetric matrices (np.linalg.eigvalsh)
+pub fn nr_eigvalsh<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    Ok(nr_eigh(a)?.0)
+}
+
+// Eigenvalues and right eigenvectors of (a stack of) general real matrices
+// (np.linalg.eig). Both are complex; complex eigenvalues come in adjacent
+// conjugate pairs. Eigenvectors are unit-norm columns, and the order of the
+// eigenvalues is not sorted.
+#[allow(clippy::type_complexity)]
+pub fn nr_eig<T: Float>(a: &Array<T>) -> Result<(Array<Complex<T>>, Array<Complex<T>>), LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut w_data = Vec::with_capacity(a.totalsize / n.max(1));
+    let mut v_data = Vec::with_capacity(a.totalsize);
+    for src in a.data.chunks(n * n) {
+        let (w, v) = general_eig(src, n)?;
+        w_data.extend(w);
+        v_data.extend(v);
+    }
+    Ok((
+        Array::from_vec(w_data, &stacked_shape(&batch, &[n])),
+        Array::from_vec(v_data, &stacked_shape(&batch, &[n, n])),
+    ))
+}
+
+// Eigenvalues of (a stack of) general real matrices (np.linalg.eigvals)
+pub fn nr_eigvals<T: Float>(a: &Array<T>) -> Result<Array<Complex<T>>, LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut w_data = Vec::with_capacity(a.totalsize / n.max(1));
+    for src in a.data.chunks(n * n) {
+        let (d, e, _) = hqr2(src, n, false)?;
+        w_data.extend(d.iter().zip(&e).map(|(&re, &im)| Complex::new(re, im)));
+    }
+    Ok(Array::from_vec(w_data, &stacked_shape(&batch, &[n])))
+}
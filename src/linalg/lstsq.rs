@@ -0,0 +1,121 @@
+use super::svd::jacobi_svd;
+use super::{matrix_dims, pair_systems, per_matrix, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_traits::Float;
+
+// Output of nr_lstsq
+#[derive(Debug)]
+pub struct LstsqResult<T> {
+    // Least-squares solution x, shaped like b with m replaced by n
+    pub solution: Array<T>,
+    // Squared residual norm of each column of b. Only present when every
+    // matrix has full column rank and more rows than columns.
+    pub residuals: Option<Array<T>>,
+    // Effective rank of each matrix
+    pub rank: Array<usize>,
+    // Singular values of each matrix, descending
+    pub singular_values: Array<T>,
+}
+
+// Default relative cutoff for small singular values, max(m, n) * eps
+fn default_rcond<T: Float>(m: usize, n: usize) -> T {
+    T::from(m.max(n)).unwrap() * T::epsilon()
+}
+
+// Pseudo-inverse of a row-major m x n matrix from its SVD, as n x m.
+// Also returns the singular values and the number above the cutoff.
+fn svd_pinv<T: Float>(a: &[T], m: usize, n: usize, rcond: T) -> (Vec<T>, Vec<T>, usize) {
+    let k = m.min(n);
+    let (u, s, vt) = jacobi_svd(a, m, n);
+    let cutoff = rcond * s.first().copied().unwrap_or_else(T::zero);
+    let rank = s.iter().filter(|&&x| x > cutoff).count();
+
+    // pinv = V diag(1/s) U^T, keeping only the first `rank` terms
+    let mut p = vec![T::zero(); n * m];
+    for r in 0..rank {
+        let inv = T::one() / s[r];
+        for i in 0..n {
+            let v = vt[r * n + i] * inv;
+            if v == T::zero() {
+                continue;
+            }
+            for j in 0..m {
+                p[i * m + j] = p[i * m + j] + v * u[j * k + r];
+            }
+        }
+    }
+    (p, s, rank)
+}
+
+// Least-squares solution of a @ x = b (np.linalg.lstsq), computed from the SVD.
+// a is (..., m, n) and b is (..., m, k) or (m,), broadcasting batch dims.
+// Singular values at or below rcond * s_max are treated as zero; the
+// default rcond is max(m, n) * eps.
+pub fn nr_lstsq<T: Float>(a: &Array<T>, b: &Array<T>, rcond: Option<T>) -> Result<LstsqResult<T>, LinalgError> {
+    let systems = pair_systems(a, b, "lstsq")?;
+    let (m, n, k) = (systems.m, systems.n, systems.k);
+    let rcond = rcond.unwrap_or_else(|| default_rcond(m, n));
+
+    let count = systems.offsets.len();
+    let mut x_data = Vec::with_capacity(count * n * k);
+    let mut res_data = Vec::with_capacity(count * k);
+    let mut ranks = Vec::with_capacity(count);
+    let mut s_data = Vec::with_capacity(count * m.min(n));
+    let mut full_rank = m > n;
+    for &(a_off, b_off) in &systems.offsets {
+        let am = &a.data[a_off..a_off + m * n];
+        let bm = &b.data[b_off..b_off + m * k];
+        let (p, s, rank) = svd_pinv(am, m, n, rcond);
+
+        let mut x = vec![T::zero(); n * k];
+        for i in 0..n {
+            for j in 0..m {
+                let pij = p[i * m + j];
+                for c in 0..k {
+                    x[i * k + c] = x[i * k + c] + pij * bm[j * k + c];
+                }
+            }
+        }
+
+        let mut res = vec![T::zero(); k];
+        for j in 0..m {
+            for (c, r) in res.iter_mut().enumerate() {
+                let fitted = (0..n).fold(T::zero(), |acc, i| acc + am[j * n + i] * x[i * k + c]);
+                let diff = bm[j * k + c] - fitted;
+                *r = *r + diff * diff;
+            }
+        }
+
+        full_rank &= rank == n;
+        x_data.extend(x);
+        res_data.extend(res);
+        ranks.push(rank);
+        s_data.extend(s);
+    }
+
+    let residuals = if full_rank {
+        let shape = if systems.b_vec { stacked_shape(&systems.batch, &[1]) } else { stacked_shape(&systems.batch, &[k]) };
+        Some(Array::from_vec(res_data, &shape))
+    } else {
+        None
+    };
+    Ok(LstsqResult {
+        solution: Array::from_vec(x_data, &systems.out_shape(n)),
+        residuals,
+        rank: per_matrix(ranks, &systems.batch),
+        singular_values: Array::from_vec(s_data, &stacked_shape(&systems.batch, &[m.min(n)])),
+    })
+}
+
+// Moore-Penrose pseudo-inverse of a (stack of) matrices (np.linalg.pinv).
+// Singular values at or below rcond * s_max are discarded; the default
+// rcond is max(m, n) * eps.
+pub fn nr_pinv<T: Float>(a: &Array<T>, rcond: Option<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let rcond = rcond.unwrap_or_else(|| default_rcond(m, n));
+    let mut out = Vec::with_capacity(a.totalsize);
+    for src in a.data.chunks(m * n) {
+        out.extend(svd_pinv(src, m, n, rcond).0);
+    }
+    Ok(Array::from_vec(out, &stacked_shape(&batch, &[n, m])))
+}
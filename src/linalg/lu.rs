@@ -0,0 +1,217 @@
+use super::{batch_count, matrix_dims, pair_systems, per_matrix, square_dims, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_traits::Float;
+
+// Row-major m x n LU factorization with partial pivoting, done in place.
+// Afterwards the strict lower triangle holds L (unit diagonal implied) and
+// the upper triangle holds U. Row i of P*A is row perm[i] of A.
+pub(crate) struct LuFactors<T> {
+    pub perm: Vec<usize>,
+    pub sign: T,
+    pub singular: bool,
+}
+
+pub(crate) fn lu_in_place<T: Float>(a: &mut [T], m: usize, n: usize) -> LuFactors<T> {
+    let mut perm: Vec<usize> = (0..m).collect();
+    let mut sign = T::one();
+    let mut singular = false;
+
+    for j in 0..m.min(n) {
+        let mut p = j;
+        let mut max = a[j * n + j].abs();
+        for i in j + 1..m {
+            let v = a[i * n + j].abs();
+            if v > max {
+                max = v;
+                p = i;
+            }
+        }
+        if p != j {
+            for c in 0..n {
+                a.swap(j * n + c, p * n + c);
+            }
+            perm.swap(j, p);
+            sign = -sign;
+        }
+
+        let pivot = a[j * n + j];
+        if pivot == T::zero() {
+            singular = true;
+            continue;
+        }
+        for i in j + 1..m {
+            let l = a[i * n + j] / pivot;
+            a[i * n + j] = l;
+            if l != T::zero() {
+                for c in j + 1..n {
+                    a[i * n + c] = a[i * n + c] - l * a[j * n + c];
+                }
+            }
+        }
+    }
+    LuFactors { perm, sign, singular }
+}
+
+// Solve A X = B for square A given its LU factors; B is n x nrhs, row-major
+pub(crate) fn lu_solve<T>(lu: &[T], perm: &[usize], n: usize, b: &[T], nrhs: usize) -> Vec<T>
+where
+    T: Float,
+{
+    let mut x = vec![T::zero(); n * nrhs];
+    for (i, &p) in perm.iter().enumerate() {
+        x[i * nrhs..(i + 1) * nrhs].copy_from_slice(&b[p * nrhs..(p + 1) * nrhs]);
+    }
+    // Forward substitution with the unit lower triangle
+    for i in 0..n {
+        for k in 0..i {
+            let l = lu[i * n + k];
+            if l != T::zero() {
+                for c in 0..nrhs {
+                    x[i * nrhs + c] = x[i * nrhs + c] - l * x[k * nrhs + c];
+                }
+            }
+        }
+    }
+    // Back substitution with the upper triangle
+    for i in (0..n).rev() {
+        for k in i + 1..n {
+            let u = lu[i * n + k];
+            if u != T::zero() {
+                for c in 0..nrhs {
+                    x[i * nrhs + c] = x[i * nrhs + c] - u * x[k * nrhs + c];
+                }
+            }
+        }
+        let d = lu[i * n + i];
+        for c in 0..nrhs {
+            x[i * nrhs + c] = x[i * nrhs + c] / d;
+        }
+    }
+    x
+}
+
+// LU decomposition with partial pivoting, A = P @ L @ U (scipy.linalg.lu).
+// For A of shape (..., m, n) with k = min(m, n), P is (..., m, m),
+// L is (..., m, k) with unit diagonal and U is (..., k, n).
+#[allow(clippy::type_complexity)]
+pub fn nr_lu<T: Float>(a: &Array<T>) -> Result<(Array<T>, Array<T>, Array<T>), LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let k = m.min(n);
+    let count = batch_count(&batch);
+    let mut p_data = vec![T::zero(); count * m * m];
+    let mut l_data = vec![T::zero(); count * m * k];
+    let mut u_data = vec![T::zero(); count * k * n];
+
+    for bi in 0..count {
+        let mut lu = a.data[bi * m * n..(bi + 1) * m * n].to_vec();
+        let factors = lu_in_place(&mut lu, m, n);
+
+        let p = &mut p_data[bi * m * m..(bi + 1) * m * m];
+        for (i, &src) in factors.perm.iter().enumerate() {
+            p[src * m + i] = T::one();
+        }
+        let l = &mut l_data[bi * m * k..(bi + 1) * m * k];
+        for i in 0..m {
+            for j in 0..k.min(i + 1) {
+                l[i * k + j] = if i == j { T::one() } else { lu[i * n + j] };
+            }
+        }
+        let u = &mut u_data[bi * k * n..(bi + 1) * k * n];
+        for i in 0..k {
+            for j in i..n {
+                u[i * n + j] = lu[i * n + j];
+            }
+        }
+    }
+
+    Ok((
+        Array::from_vec(p_data, &stacked_shape(&batch, &[m, m])),
+        Array::from_vec(l_data, &stacked_shape(&batch, &[m, k])),
+        Array::from_vec(u_data, &stacked_shape(&batch, &[k, n])),
+    ))
+}
+
+// Solve a @ x = b (np.linalg.solve).
+// a is (..., n, n); b is (..., n, k), or (n,) for a single right-hand side.
+// Batch dimensions broadcast like nr_matmul. Singular systems give Err.
+pub fn nr_solve<T: Float>(a: &Array<T>, b: &Array<T>) -> Result<Array<T>, LinalgError> {
+    square_dims(a)?;
+    let systems = pair_systems(a, b, "solve")?;
+    let (n, k) = (systems.n, systems.k);
+
+    let mut out = Vec::with_capacity(systems.offsets.len() * n * k);
+    for &(a_off, b_off) in &systems.offsets {
+        let mut lu = a.data[a_off..a_off + n * n].to_vec();
+        let factors = lu_in_place(&mut lu, n, n);
+        if factors.singular {
+            return Err(LinalgError::Singular);
+        }
+        out.extend(lu_solve(&lu, &factors.perm, n, &b.data[b_off..b_off + n * k], k));
+    }
+    Ok(Array::from_vec(out, &systems.out_shape(n)))
+}
+
+// Inverse of a (stack of) square matrices (np.linalg.inv)
+pub fn nr_inv<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut identity = vec![T::zero(); n * n];
+    for i in 0..n {
+        identity[i * n + i] = T::one();
+    }
+
+    let mut out = vec![T::zero(); a.totalsize];
+    for (dst, src) in out.chunks_mut(n * n).zip(a.data.chunks(n * n)) {
+        let mut lu = src.to_vec();
+        let factors = lu_in_place(&mut lu, n, n);
+        if factors.singular {
+            return Err(LinalgError::Singular);
+        }
+        dst.copy_from_slice(&lu_solve(&lu, &factors.perm, n, &identity, n));
+    }
+    Ok(Array::from_vec(out, &stacked_shape(&batch, &[n, n])))
+}
+
+// Determinant of each square matrix; shape is the batch shape, or [1]
+pub fn nr_det<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let dets = a
+        .data
+        .chunks(n * n)
+        .map(|src| {
+            let mut lu = src.to_vec();
+            let factors = lu_in_place(&mut lu, n, n);
+            (0..n).fold(factors.sign, |acc, i| acc * lu[i * n + i])
+        })
+        .collect();
+    Ok(per_matrix(dets, &batch))
+}
+
+// Sign and natural log of the absolute determinant (np.linalg.slogdet).
+// Avoids the overflow and underflow of nr_det for large matrices.
+// A singular matrix gives sign 0 and logabsdet -inf.
+pub fn nr_slogdet<T: Float>(a: &Array<T>) -> Result<(Array<T>, Array<T>), LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut signs = Vec::with_capacity(batch_count(&batch));
+    let mut logdets = Vec::with_capacity(batch_count(&batch));
+    for src in a.data.chunks(n * n) {
+        let mut lu = src.to_vec();
+        let factors = lu_in_place(&mut lu, n, n);
+        if factors.singular {
+            signs.push(T::zero());
+            logdets.push(T::neg_infinity());
+            continue;
+        }
+        let mut sign = factors.sign;
+        let mut logdet = T::zero();
+        for i in 0..n {
+            let d = lu[i * n + i];
+            if d < T::zero() {
+                sign = -sign;
+            }
+            logdet = logdet + d.abs().ln();
+        }
+        signs.push(sign);
+        logdets.push(logdet);
+    }
+    Ok((per_matrix(signs, &batch), per_matrix(logdets, &batch)))
+}
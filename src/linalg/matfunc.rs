@@ -0,0 +1,312 @@
+use super::lu::{lu_in_place, lu_solve};
+use super::{nr_inv, square_dims, stacked_shape, LinalgError};
+use crate::array::Array;
+use crate::gemm;
+use crate::ops::nr_matmul;
+use num_traits::Float;
+
+// Iteration cap for the Denman-Beavers square root
+const MAX_SQRT_ITERS: usize = 100;
+// Square roots taken by nr_logm before giving up on reaching ||A - I|| <= 1/4
+const MAX_LOG_ROOTS: usize = 64;
+// Gauss-Legendre points in the Pade approximant of log(I + X)
+const LOG_PADE_POINTS: usize = 8;
+
+// Raise a (stack of) square matrices to the integer power n
+// (np.linalg.matrix_power) by repeated squaring with nr_matmul.
+// n = 0 gives the identity and negative n powers the inverse.
+pub fn nr_matrix_power<T>(a: &Array<T>, n: i32) -> Result<Array<T>, LinalgError>
+where
+    T: Float + Send + Sync + 'static,
+{
+    let (batch, size) = square_dims(a)?;
+    let mut base = if n < 0 { nr_inv(a)? } else { Array::from_vec(a.data.clone(), &a.shape) };
+    let mut exp = n.unsigned_abs();
+    let mut result: Option<Array<T>> = None;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = Some(match result {
+                Some(r) => nr_matmul(&r, &base),
+                None => Array::from_vec(base.data.clone(), &base.shape),
+            });
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = nr_matmul(&base, &base);
+        }
+    }
+    Ok(result.unwrap_or_else(|| {
+        let mut data = Vec::with_capacity(a.totalsize);
+        for _ in 0..a.totalsize / (size * size).max(1) {
+            data.extend(identity::<T>(size));
+        }
+        Array::from_vec(data, &stacked_shape(&batch, &[size, size]))
+    }))
+}
+
+// Matrix exponential of a (stack of) square matrices (scipy.linalg.expm),
+// using the scaling-and-squaring algorithm of Higham (2005): the [m/m] Pade
+// approximant with m in {3, 5, 7, 9, 13} chosen from the 1-norm, after
+// halving A s times, and then squared s times.
+pub fn nr_expm(a: &Array<f64>) -> Result<Array<f64>, LinalgError> {
+    per_square_matrix(a, expm)
+}
+
+// Principal square root of a (stack of) square matrices (scipy.linalg.sqrtm)
+// by the determinant-scaled Denman-Beavers iteration. The matrices must be
+// nonsingular with no eigenvalues on the negative real axis, since the
+// result would otherwise be complex; such input gives
+// LinalgError::Singular or LinalgError::NoConvergence.
+pub fn nr_sqrtm(a: &Array<f64>) -> Result<Array<f64>, LinalgError> {
+    per_square_matrix(a, sqrtm)
+}
+
+// Principal logarithm of a (stack of) square matrices (scipy.linalg.logm)
+// by inverse scaling and squaring: square roots are taken until A is close
+// to I, then log(I + X) comes from a Pade approximant evaluated with
+// Gauss-Legendre quadrature. The same restrictions as nr_sqrtm apply.
+pub fn nr_logm(a: &Array<f64>) -> Result<Array<f64>, LinalgError> {
+    per_square_matrix(a, logm)
+}
+
+fn per_square_matrix(
+    a: &Array<f64>,
+    f: fn(&[f64], usize) -> Result<Vec<f64>, LinalgError>,
+) -> Result<Array<f64>, LinalgError> {
+    let (batch, n) = square_dims(a)?;
+    let mut out = Vec::with_capacity(a.totalsize);
+    if n > 0 {
+        for src in a.data.chunks(n * n) {
+            out.extend(f(src, n)?);
+        }
+    }
+    Ok(Array::from_vec(out, &stacked_shape(&batch, &[n, n])))
+}
+
+fn expm(a: &[f64], n: usize) -> Result<Vec<f64>, LinalgError> {
+    // Largest 1-norm for which each Pade degree is accurate to double precision
+    const THETA: [(usize, f64); 4] = [
+        (3, 1.495585217958292e-2),
+        (5, 2.53939833006323e-1),
+        (7, 9.504178996162932e-1),
+        (9, 2.097847961257068),
+    ];
+    const THETA_13: f64 = 5.371920351148152;
+
+    let norm = norm1(a, n);
+    if !norm.is_finite() {
+        return Err(LinalgError::InvalidArgument("expm: matrix has non-finite entries".to_string()));
+    }
+    let eye = identity(n);
+    let a2 = matmul(a, a, n);
+
+    for &(m, theta) in &THETA {
+        if norm <= theta {
+            let b = pade_coefficients(m);
+            // U = A * sum(b[2j+1] A^2j), V = sum(b[2j] A^2j)
+            let mut power = eye.clone();
+            let mut u_inner = scaled(&eye, b[1]);
+            let mut v = scaled(&eye, b[0]);
+            for j in 1..=m / 2 {
+                power = matmul(&power, &a2, n);
+                axpy(&mut u_inner, b[2 * j + 1], &power);
+                axpy(&mut v, b[2 * j], &power);
+            }
+            let u = matmul(a, &u_inner, n);
+            return pade_quotient(&u, &v, n);
+        }
+    }
+
+    // Degree 13 on A / 2^s, then square the result s times
+    let s = if norm > THETA_13 { (norm / THETA_13).log2().ceil() as i32 } else { 0 };
+    let scale = 0.5f64.powi(s);
+    let a1 = scaled(a, scale);
+    let a2 = scaled(&a2, scale * scale);
+    let a4 = matmul(&a2, &a2, n);
+    let a6 = matmul(&a4, &a2, n);
+    let b = pade_coefficients(13);
+
+    let mut u_hi = scaled(&a6, b[13]);
+    axpy(&mut u_hi, b[11], &a4);
+    axpy(&mut u_hi, b[9], &a2);
+    let mut u_inner = matmul(&a6, &u_hi, n);
+    axpy(&mut u_inner, b[7], &a6);
+    axpy(&mut u_inner, b[5], &a4);
+    axpy(&mut u_inner, b[3], &a2);
+    axpy(&mut u_inner, b[1], &eye);
+    let u = matmul(&a1, &u_inner, n);
+
+    let mut v_hi = scaled(&a6, b[12]);
+    axpy(&mut v_hi, b[10], &a4);
+    axpy(&mut v_hi, b[8], &a2);
+    let mut v = matmul(&a6, &v_hi, n);
+    axpy(&mut v, b[6], &a6);
+    axpy(&mut v, b[4], &a4);
+    axpy(&mut v, b[2], &a2);
+    axpy(&mut v, b[0], &eye);
+
+    let mut r = pade_quotient(&u, &v, n)?;
+    for _ in 0..s {
+        r = matmul(&r, &r, n);
+    }
+    Ok(r)
+}
+
+// Coefficients of the [m/m] Pade approximant to exp, lowest order first
+fn pade_coefficients(m: usize) -> Vec<f64> {
+    // b[j] = (2m - j)! m! / ((2m)! j! (m - j)!), scaled so that b[m] = 1
+    let mut b = vec![1.0; m + 1];
+    for j in (0..m).rev() {
+        b[j] = b[j + 1] * ((j + 1) * (2 * m - j)) as f64 / (m - j) as f64;
+    }
+    b
+}
+
+// (V - U)^-1 (V + U)
+fn pade_quotient(u: &[f64], v: &[f64], n: usize) -> Result<Vec<f64>, LinalgError> {
+    let mut q: Vec<f64> = v.iter().zip(u).map(|(v, u)| v - u).collect();
+    let p: Vec<f64> = v.iter().zip(u).map(|(v, u)| v + u).collect();
+    let factors = lu_in_place(&mut q, n, n);
+    if factors.singular {
+        return Err(LinalgError::Singular);
+    }
+    Ok(lu_solve(&q, &factors.perm, n, &p, n))
+}
+
+fn sqrtm(a: &[f64], n: usize) -> Result<Vec<f64>, LinalgError> {
+    let tol = f64::EPSILON * (n as f64).sqrt();
+    let mut y = a.to_vec();
+    let mut z = identity(n);
+    let mut scaling = true;
+    for _ in 0..MAX_SQRT_ITERS {
+        let (y_inv, y_logdet) = inverse_logdet(&y, n)?;
+        let (z_inv, z_logdet) = inverse_logdet(&z, n)?;
+        // mu = |det(Y) det(Z)|^(-1/2n) balances the iterates early on
+        let mu = if scaling { (-(y_logdet + z_logdet) / (2 * n) as f64).exp() } else { 1.0 };
+
+        let y_next: Vec<f64> = y.iter().zip(&z_inv).map(|(y, zi)| 0.5 * (mu * y + zi / mu)).collect();
+        let z_next: Vec<f64> = z.iter().zip(&y_inv).map(|(z, yi)| 0.5 * (mu * z + yi / mu)).collect();
+        let diff: Vec<f64> = y_next.iter().zip(&y).map(|(a, b)| a - b).collect();
+        let change = norm1(&diff, n) / norm1(&y_next, n);
+        y = y_next;
+        z = z_next;
+        if change.is_nan() {
+            break;
+        }
+        if change <= tol {
+            // Without a real square root the iterates can still settle, on
+            // a matrix whose square is far from A
+            let mut residual = matmul(&y, &y, n);
+            axpy(&mut residual, -1.0, a);
+            let y_norm = norm1(&y, n);
+            if norm1(&residual, n) > f64::EPSILON.sqrt() * y_norm * y_norm {
+                return Err(LinalgError::NoConvergence);
+            }
+            return Ok(y);
+        }
+        if change < 1e-2 {
+            scaling = false;
+        }
+    }
+    Err(LinalgError::NoConvergence)
+}
+
+fn logm(a: &[f64], n: usize) -> Result<Vec<f64>, LinalgError> {
+    let eye = identity(n);
+    let mut x = a.to_vec();
+    let mut roots = 0;
+    loop {
+        let dist: Vec<f64> = x.iter().zip(&eye).map(|(x, e)| x - e).collect();
+        if norm1(&dist, n) <= 0.25 {
+            x = dist;
+            break;
+        }
+        if roots == MAX_LOG_ROOTS {
+            return Err(LinalgError::NoConvergence);
+        }
+        x = sqrtm(&x, n)?;
+        roots += 1;
+    }
+
+    // log(I + X) = integral over [0, 1] of X (I + tX)^-1 dt
+    let mut log = vec![0.0; n * n];
+    for (t, w) in gauss_legendre(LOG_PADE_POINTS) {
+        let mut shifted = scaled(&x, t);
+        axpy(&mut shifted, 1.0, &eye);
+        let factors = lu_in_place(&mut shifted, n, n);
+        if factors.singular {
+            return Err(LinalgError::Singular);
+        }
+        axpy(&mut log, w, &lu_solve(&shifted, &factors.perm, n, &x, n));
+    }
+    let scale = 2f64.powi(roots as i32);
+    Ok(log.iter().map(|v| v * scale).collect())
+}
+
+// Nodes and weights of the m-point Gauss-Legendre rule on [0, 1]
+fn gauss_legendre(m: usize) -> Vec<(f64, f64)> {
+    (0..m)
+        .map(|i| {
+            // Newton's method on P_m from the Chebyshev-like initial guess
+            let mut x = (std::f64::consts::PI * (i as f64 + 0.75) / (m as f64 + 0.5)).cos();
+            let mut dp = 0.0;
+            for _ in 0..100 {
+                let (mut p0, mut p1) = (1.0, x);
+                for k in 2..=m {
+                    let p2 = ((2 * k - 1) as f64 * x * p1 - (k - 1) as f64 * p0) / k as f64;
+                    p0 = p1;
+                    p1 = p2;
+                }
+                dp = m as f64 * (x * p1 - p0) / (x * x - 1.0);
+                let dx = p1 / dp;
+                x -= dx;
+                if dx.abs() < 1e-15 {
+                    break;
+                }
+            }
+            let w = 2.0 / ((1.0 - x * x) * dp * dp);
+            ((1.0 - x) / 2.0, w / 2.0)
+        })
+        .collect()
+}
+
+// Inverse and log|det| from one LU factorization
+fn inverse_logdet(a: &[f64], n: usize) -> Result<(Vec<f64>, f64), LinalgError> {
+    let mut lu = a.to_vec();
+    let factors = lu_in_place(&mut lu, n, n);
+    if factors.singular {
+        return Err(LinalgError::Singular);
+    }
+    let logdet = (0..n).map(|i| lu[i * n + i].abs().ln()).sum();
+    Ok((lu_solve(&lu, &factors.perm, n, &identity(n), n), logdet))
+}
+
+fn identity<T: Float>(n: usize) -> Vec<T> {
+    let mut eye = vec![T::zero(); n * n];
+    for i in 0..n {
+        eye[i * n + i] = T::one();
+    }
+    eye
+}
+
+fn matmul(a: &[f64], b: &[f64], n: usize) -> Vec<f64> {
+    let mut c = vec![0.0; n * n];
+    gemm::matmul_2d(a, b, &mut c, n, n, n);
+    c
+}
+
+fn scaled(a: &[f64], s: f64) -> Vec<f64> {
+    a.iter().map(|x| x * s).collect()
+}
+
+// y += alpha * x
+fn axpy(y: &mut [f64], alpha: f64, x: &[f64]) {
+    for (y, x) in y.iter_mut().zip(x) {
+        *y += alpha * x;
+    }
+}
+
+// Maximum absolute column sum
+fn norm1(a: &[f64], n: usize) -> f64 {
+    (0..n).map(|j| (0..n).map(|i| a[i * n + j].abs()).sum::<f64>()).fold(0.0, f64::max)
+}
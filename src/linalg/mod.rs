@@ -0,0 +1,161 @@
+mod cholesky;
+mod eig;
+mod lstsq;
+mod lu;
+mod matfunc;
+mod norm;
+mod qr;
+mod svd;
+
+pub use cholesky::{nr_cho_solve, nr_cholesky};
+pub(crate) use cholesky::cholesky_lower;
+pub use eig::{nr_eig, nr_eigh, nr_eigvals, nr_eigvalsh};
+pub use lstsq::{nr_lstsq, nr_pinv, LstsqResult};
+pub use lu::{nr_det, nr_inv, nr_lu, nr_slogdet, nr_solve};
+pub(crate) use lu::{lu_in_place, lu_solve};
+pub use matfunc::{nr_expm, nr_logm, nr_matrix_power, nr_sqrtm};
+pub use norm::{nr_norm, NormOrd};
+pub use qr::{nr_qr, QrMode};
+pub use svd::{nr_cond, nr_matrix_rank, nr_svd, nr_svdvals, SvdResult};
+
+use crate::array::Array;
+use crate::ops::{batch_offset, batch_strides, broadcast_final_shape};
+use num_traits::Zero;
+use std::fmt;
+
+// Errors reported by the linear algebra routines
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinalgError {
+    // The trailing two dimensions are not square
+    NotSquare(Vec<i32>),
+    // Operand dimensions are unsuitable or incompatible
+    InvalidShape(String),
+    // The matrix is exactly singular
+    Singular,
+    // Cholesky factorization hit a non-positive pivot
+    NotPositiveDefinite,
+    // An iterative algorithm did not converge
+    NoConvergence,
+    // An option value is not supported for this input
+    InvalidArgument(String),
+}
+
+impl fmt::Display for LinalgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinalgError::NotSquare(shape) => {
+                write!(f, "Last 2 dimensions of the array must be square, got shape {:?}", shape)
+            }
+            LinalgError::InvalidShape(msg) => write!(f, "{}", msg),
+            LinalgError::Singular => write!(f, "Singular matrix"),
+            LinalgError::NotPositiveDefinite => write!(f, "Matrix is not positive definite"),
+            LinalgError::NoConvergence => write!(f, "Algorithm did not converge"),
+            LinalgError::InvalidArgument(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
+
+// Split a stack of matrices (..., m, n) into its batch shape, m and n
+pub(crate) fn matrix_dims<T>(a: &Array<T>) -> Result<(Vec<i32>, usize, usize), LinalgError> {
+    if a.ndim < 2 {
+        return Err(LinalgError::InvalidShape(format!(
+            "Expected an array of at least 2 dimensions, got shape {:?}",
+            a.shape
+        )));
+    }
+    let m = a.shape[a.ndim - 2] as usize;
+    let n = a.shape[a.ndim - 1] as usize;
+    Ok((a.shape[..a.ndim - 2].to_vec(), m, n))
+}
+
+// Like matrix_dims, but for a stack of square matrices
+pub(crate) fn square_dims<T>(a: &Array<T>) -> Result<(Vec<i32>, usize), LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    if m != n {
+        return Err(LinalgError::NotSquare(a.shape.clone()));
+    }
+    Ok((batch, n))
+}
+
+// Number of matrices in a batch shape
+pub(crate) fn batch_count(batch: &[i32]) -> usize {
+    batch.iter().map(|&d| d as usize).product()
+}
+
+// Build an array of one value per matrix; a single matrix gives shape [1]
+pub(crate) fn per_matrix<T: Copy + Zero>(data: Vec<T>, batch: &[i32]) -> Array<T> {
+    if batch.is_empty() {
+        Array::from_vec(data, &[1])
+    } else {
+        Array::from_vec(data, batch)
+    }
+}
+
+// Append trailing matrix dimensions to a batch shape
+pub(crate) fn stacked_shape(batch: &[i32], dims: &[usize]) -> Vec<i32> {
+    let mut shape = batch.to_vec();
+    shape.extend(dims.iter().map(|&d| d as i32));
+    shape
+}
+
+// Matrices of a (..., m, n) paired with right-hand sides b (..., m, k) or (m,),
+// with the batch dimensions broadcast like nr_matmul
+pub(crate) struct SystemBatch {
+    pub batch: Vec<i32>,
+    pub m: usize,
+    pub n: usize,
+    pub k: usize,
+    pub b_vec: bool,
+    // Element offsets of each (a, b) pair, in result batch order
+    pub offsets: Vec<(usize, usize)>,
+}
+
+impl SystemBatch {
+    // Result shape with `rows` rows per right-hand side
+    pub fn out_shape(&self, rows: usize) -> Vec<i32> {
+        if self.b_vec {
+            stacked_shape(&self.batch, &[rows])
+        } else {
+            stacked_shape(&self.batch, &[rows, self.k])
+        }
+    }
+}
+
+pub(crate) fn pair_systems<T: Copy + Zero>(a: &Array<T>, b: &Array<T>, name: &str) -> Result<SystemBatch, LinalgError> {
+    let (a_batch, m, n) = matrix_dims(a)?;
+    let b_vec = b.ndim == 1;
+    let (b_batch, bm, k) = if b_vec {
+        (Vec::new(), b.shape[0] as usize, 1)
+    } else {
+        matrix_dims(b)?
+    };
+    if bm != m {
+        return Err(LinalgError::InvalidShape(format!(
+            "{}: a of shape {:?} and b of shape {:?} are incompatible",
+            name, a.shape, b.shape
+        )));
+    }
+    let batch = broadcast_final_shape(&a_batch, &b_batch).ok_or_else(|| {
+        LinalgError::InvalidShape(format!(
+            "{}: batch dimensions {:?} and {:?} cannot be broadcast together",
+            name, a_batch, b_batch
+        ))
+    })?;
+
+    let a_strides = batch_strides(&a_batch, m * n);
+    let b_strides = batch_strides(&b_batch, m * k);
+    let idxs = Array::<T>::create_array_indices(&batch, batch.len());
+    let offsets = idxs
+        .indices
+        .iter()
+        .map(|nd_index| {
+            (
+                batch_offset(nd_index, &a_batch, &a_strides),
+                batch_offset(nd_index, &b_batch, &b_strides),
+            )
+        })
+        .collect();
+    Ok(SystemBatch { batch, m, n, k, b_vec, offsets })
+}
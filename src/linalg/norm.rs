@@ -0,0 +1,188 @@
+use super::svd::jacobi_svdvals;
+use super::LinalgError;
+use crate::array::Array;
+use crate::product::permute_axes;
+use num_traits::Float;
+
+// Order of the norm computed by nr_norm, mirroring the `ord` argument of
+// np.linalg.norm. P(p) is any real p for vectors; for matrices only
+// 1, -1, 2 and -2 are accepted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormOrd<T> {
+    // Frobenius norm, matrices only
+    Fro,
+    // Nuclear norm (sum of singular values), matrices only
+    Nuc,
+    // Max |x| for vectors, max row sum for matrices
+    Inf,
+    // Min |x| for vectors, min row sum for matrices
+    NegInf,
+    P(T),
+}
+
+// Euclidean norm with the running scale of LAPACK's nrm2, so that huge or
+// tiny entries neither overflow nor underflow when squared
+fn l2<T: Float>(xs: impl Iterator<Item = T>) -> T {
+    let mut scale = T::zero();
+    let mut ssq = T::one();
+    for x in xs {
+        let ax = x.abs();
+        if ax.is_nan() {
+            return ax;
+        }
+        if ax == T::zero() {
+            continue;
+        }
+        if scale < ax {
+            let r = scale / ax;
+            ssq = T::one() + ssq * r * r;
+            scale = ax;
+        } else {
+            let r = ax / scale;
+            ssq = ssq + r * r;
+        }
+    }
+    if scale.is_infinite() {
+        return scale;
+    }
+    scale * ssq.sqrt()
+}
+
+fn vector_norm<T: Float>(v: &[T], ord: NormOrd<T>) -> Result<T, LinalgError> {
+    let abs = v.iter().map(|x| x.abs());
+    Ok(match ord {
+        NormOrd::Inf => abs.fold(T::zero(), T::max),
+        NormOrd::NegInf => abs.fold(T::infinity(), T::min),
+        NormOrd::P(p) if p == T::zero() => T::from(v.iter().filter(|&&x| x != T::zero()).count()).unwrap(),
+        NormOrd::P(p) if p == T::one() => abs.fold(T::zero(), |acc, x| acc + x),
+        NormOrd::P(p) if p == T::one() + T::one() => l2(v.iter().copied()),
+        NormOrd::P(p) if p > T::zero() => {
+            // Scale by the largest entry to keep |x|^p finite
+            let max = v.iter().fold(T::zero(), |acc, x| acc.max(x.abs()));
+            if max == T::zero() || max.is_infinite() {
+                return Ok(max);
+            }
+            max * abs.fold(T::zero(), |acc, x| acc + (x / max).powf(p)).powf(p.recip())
+        }
+        NormOrd::P(p) => abs.fold(T::zero(), |acc, x| acc + x.powf(p)).powf(p.recip()),
+        NormOrd::Fro | NormOrd::Nuc => {
+            return Err(LinalgError::InvalidArgument("Invalid norm order for vectors".to_string()))
+        }
+    })
+}
+
+// Norm of a row-major m x n matrix
+fn matrix_norm<T: Float>(a: &[T], m: usize, n: usize, ord: NormOrd<T>) -> Result<T, LinalgError> {
+    let row_sums = || a.chunks(n.max(1)).map(|row| row.iter().fold(T::zero(), |acc, x| acc + x.abs()));
+    let col_sums = || (0..n).map(|j| (0..m).fold(T::zero(), |acc, i| acc + a[i * n + j].abs()));
+    let max = |xs: &mut dyn Iterator<Item = T>| xs.fold(T::zero(), T::max);
+    let min = |xs: &mut dyn Iterator<Item = T>| xs.fold(T::infinity(), T::min);
+    let one = T::one();
+    let two = one + one;
+    Ok(match ord {
+        NormOrd::Fro => l2(a.iter().copied()),
+        NormOrd::Nuc => jacobi_svdvals(a, m, n).iter().fold(T::zero(), |acc, &s| acc + s),
+        NormOrd::Inf => max(&mut row_sums()),
+        NormOrd::NegInf => min(&mut row_sums()),
+        NormOrd::P(p) if p == one => max(&mut col_sums()),
+        NormOrd::P(p) if p == -one => min(&mut col_sums()),
+        NormOrd::P(p) if p == two => jacobi_svdvals(a, m, n).first().copied().unwrap_or_else(T::zero),
+        NormOrd::P(p) if p == -two => jacobi_svdvals(a, m, n).last().copied().unwrap_or_else(T::zero),
+        NormOrd::P(_) => {
+            return Err(LinalgError::InvalidArgument("Invalid norm order for matrices".to_string()))
+        }
+    })
+}
+
+// Vector or matrix norm (np.linalg.norm).
+//
+// With `axis` = None, a 1-D array gets a vector norm and a 2-D array a
+// matrix norm; when `ord` is also None any array is flattened and its
+// 2-norm returned. One axis computes vector norms along it, two axes
+// compute matrix norms over the (row, column) pair. `ord` = None means the
+// 2-norm for vectors and the Frobenius norm for matrices. With `keepdims`
+// the reduced axes stay in the result with size 1; otherwise a full
+// reduction gives shape [1].
+pub fn nr_norm<T: Float>(
+    a: &Array<T>,
+    ord: Option<NormOrd<T>>,
+    axis: Option<&[usize]>,
+    keepdims: bool,
+) -> Result<Array<T>, LinalgError> {
+    let all: Vec<usize> = (0..a.ndim).collect();
+    let axes = match axis {
+        Some(axes) => axes.to_vec(),
+        None if ord.is_none() => {
+            let value = l2(a.data.iter().copied());
+            return Ok(reduced(vec![value], a, &all, keepdims));
+        }
+        None if a.ndim <= 2 => all.clone(),
+        None => {
+            return Err(LinalgError::InvalidShape(format!(
+                "Improper number of dimensions to norm, got shape {:?}",
+                a.shape
+            )))
+        }
+    };
+
+    if axes.iter().any(|&ax| ax >= a.ndim) {
+        return Err(LinalgError::InvalidShape(format!(
+            "Axis {:?} is out of bounds for array of shape {:?}",
+            axes, a.shape
+        )));
+    }
+    if axes.len() == 2 && axes[0] == axes[1] {
+        return Err(LinalgError::InvalidShape("Duplicate axes given".to_string()));
+    }
+
+    // Move the reduced axes to the end so each norm reads a contiguous block
+    let kept: Vec<usize> = all.iter().copied().filter(|ax| !axes.contains(ax)).collect();
+    let perm: Vec<usize> = kept.iter().chain(&axes).copied().collect();
+    let moved = permute_axes(a, &perm);
+    let dims: Vec<usize> = axes.iter().map(|&ax| a.shape[ax] as usize).collect();
+    let block: usize = dims.iter().product();
+
+    let values = match dims[..] {
+        [n] => {
+            let ord = ord.unwrap_or(NormOrd::P(T::one() + T::one()));
+            if n == 0 {
+                vec![T::zero(); batch_len(a, &kept)]
+            } else {
+                moved.data.chunks(n).map(|v| vector_norm(v, ord)).collect::<Result<_, _>>()?
+            }
+        }
+        [m, n] => {
+            let ord = ord.unwrap_or(NormOrd::Fro);
+            if block == 0 {
+                vec![T::zero(); batch_len(a, &kept)]
+            } else {
+                moved.data.chunks(block).map(|mat| matrix_norm(mat, m, n, ord)).collect::<Result<_, _>>()?
+            }
+        }
+        _ => {
+            return Err(LinalgError::InvalidShape(format!(
+                "Improper number of axes {:?} to norm, expected 1 or 2",
+                axes
+            )))
+        }
+    };
+    Ok(reduced(values, a, &axes, keepdims))
+}
+
+fn batch_len<T>(a: &Array<T>, kept: &[usize]) -> usize {
+    kept.iter().map(|&ax| a.shape[ax] as usize).product()
+}
+
+// Shape the per-block results, dropping or keeping the reduced axes
+fn reduced<T: Float>(values: Vec<T>, a: &Array<T>, axes: &[usize], keepdims: bool) -> Array<T> {
+    let shape: Vec<i32> = if keepdims {
+        (0..a.ndim).map(|ax| if axes.contains(&ax) { 1 } else { a.shape[ax] }).collect()
+    } else {
+        (0..a.ndim).filter(|ax| !axes.contains(ax)).map(|ax| a.shape[ax]).collect()
+    };
+    if shape.is_empty() {
+        Array::from_vec(values, &[1])
+    } else {
+        Array::from_vec(values, &shape)
+    }
+}
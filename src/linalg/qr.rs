@@ -0,0 +1,100 @@
+use super::{batch_count, matrix_dims, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_traits::Float;
+
+// Which factors nr_qr returns, mirroring np.linalg.qr's `mode`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QrMode {
+    // Q is (m, k) and R is (k, n), with k = min(m, n)
+    Reduced,
+    // Q is (m, m) and R is (m, n)
+    Complete,
+}
+
+// Householder QR of a row-major m x n matrix.
+// Returns Q (m x q_cols) and R (r_rows x n) with q_cols = r_rows.
+pub(crate) fn householder_qr<T: Float>(a: &[T], m: usize, n: usize, complete: bool) -> (Vec<T>, Vec<T>) {
+    let k = m.min(n);
+    let mut r = a.to_vec();
+    let mut reflectors: Vec<Vec<T>> = Vec::with_capacity(k);
+
+    for j in 0..k {
+        let norm = (j..m).fold(T::zero(), |acc, i| acc + r[i * n + j] * r[i * n + j]).sqrt();
+        if norm == T::zero() {
+            reflectors.push(Vec::new());
+            continue;
+        }
+        // Reflect x onto -sign(x0) * |x| * e0 to avoid cancellation
+        let x0 = r[j * n + j];
+        let alpha = if x0 > T::zero() { -norm } else { norm };
+        let mut v: Vec<T> = (j..m).map(|i| r[i * n + j]).collect();
+        v[0] = v[0] - alpha;
+        let vtv = v.iter().fold(T::zero(), |acc, &x| acc + x * x);
+        if vtv == T::zero() {
+            reflectors.push(Vec::new());
+            continue;
+        }
+        apply_reflector(&mut r, n, j, j, &v, vtv);
+        reflectors.push(v);
+    }
+
+    let q_cols = if complete { m } else { k };
+    let mut q = vec![T::zero(); m * q_cols];
+    for i in 0..q_cols {
+        q[i * q_cols + i] = T::one();
+    }
+    // Q = H_0 H_1 ... H_{k-1} applied to the leading columns of I
+    for (j, v) in reflectors.iter().enumerate().rev() {
+        if !v.is_empty() {
+            let vtv = v.iter().fold(T::zero(), |acc, &x| acc + x * x);
+            apply_reflector(&mut q, q_cols, j, 0, v, vtv);
+        }
+    }
+
+    let r_rows = q_cols;
+    let mut r_out = vec![T::zero(); r_rows * n];
+    for i in 0..r_rows.min(m) {
+        for c in i..n {
+            r_out[i * n + c] = r[i * n + c];
+        }
+    }
+    (q, r_out)
+}
+
+// Apply H = I - 2 v v^T / (v^T v) to rows row0.. and columns col0.. of a
+fn apply_reflector<T: Float>(a: &mut [T], lda: usize, row0: usize, col0: usize, v: &[T], vtv: T) {
+    let two = T::one() + T::one();
+    for c in col0..lda {
+        let mut w = T::zero();
+        for (i, &vi) in v.iter().enumerate() {
+            w = w + vi * a[(row0 + i) * lda + c];
+        }
+        let f = two * w / vtv;
+        if f != T::zero() {
+            for (i, &vi) in v.iter().enumerate() {
+                a[(row0 + i) * lda + c] = a[(row0 + i) * lda + c] - f * vi;
+            }
+        }
+    }
+}
+
+// QR decomposition by Householder reflections (np.linalg.qr).
+// Works on (..., m, n) stacks of matrices; see QrMode for the output shapes.
+pub fn nr_qr<T: Float>(a: &Array<T>, mode: QrMode) -> Result<(Array<T>, Array<T>), LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let complete = mode == QrMode::Complete;
+    let rows = if complete { m } else { m.min(n) };
+
+    let count = batch_count(&batch);
+    let mut q_data = Vec::with_capacity(count * m * rows);
+    let mut r_data = Vec::with_capacity(count * rows * n);
+    for src in a.data.chunks(m * n) {
+        let (q, r) = householder_qr(src, m, n, complete);
+        q_data.extend(q);
+        r_data.extend(r);
+    }
+    Ok((
+        Array::from_vec(q_data, &stacked_shape(&batch, &[m, rows])),
+        Array::from_vec(r_data, &stacked_shape(&batch, &[rows, n])),
+    ))
+}
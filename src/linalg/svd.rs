@@ -0,0 +1,262 @@
+use super::{matrix_dims, per_matrix, stacked_shape, LinalgError};
+use crate::array::Array;
+use num_traits::Float;
+
+// Sweeps of one-sided Jacobi before giving up on further rotations
+const MAX_SWEEPS: usize = 60;
+
+// Thin SVD of a row-major m x n matrix by one-sided (Hestenes) Jacobi rotations.
+// Returns U (m x k), s (k, descending) and Vt (k x n) with k = min(m, n),
+// all row-major. Columns of U belonging to zero singular values are
+// completed to an orthonormal set.
+pub(crate) fn jacobi_svd<T: Float>(a: &[T], m: usize, n: usize) -> (Vec<T>, Vec<T>, Vec<T>) {
+    if m < n {
+        // A^T = U' S V'^T gives A = V' S U'^T
+        let (u_t, s, vt_t) = jacobi_svd(&transpose(a, m, n), n, m);
+        return (transpose(&vt_t, m, m), s, transpose(&u_t, n, m));
+    }
+
+    // Work on columns: cols[j] is column j of A, v[j] column j of V
+    let mut cols: Vec<Vec<T>> = (0..n).map(|j| (0..m).map(|i| a[i * n + j]).collect()).collect();
+    let mut v: Vec<Vec<T>> = (0..n)
+        .map(|j| (0..n).map(|i| if i == j { T::one() } else { T::zero() }).collect())
+        .collect();
+    orthogonalize_columns(&mut cols, Some(&mut v));
+
+    let norms: Vec<T> = cols.iter().map(|c| dot(c, c).sqrt()).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&x, &y| norms[y].partial_cmp(&norms[x]).unwrap_or(std::cmp::Ordering::Equal));
+
+    let s: Vec<T> = order.iter().map(|&j| norms[j]).collect();
+    let mut u_cols: Vec<Vec<T>> = order
+        .iter()
+        .map(|&j| {
+            if norms[j] > T::zero() {
+                cols[j].iter().map(|&x| x / norms[j]).collect()
+            } else {
+                vec![T::zero(); m]
+            }
+        })
+        .collect();
+    complete_basis(&mut u_cols, m, n);
+
+    let mut u = vec![T::zero(); m * n];
+    for (r, col) in u_cols.iter().enumerate() {
+        for i in 0..m {
+            u[i * n + r] = col[i];
+        }
+    }
+    let mut vt = vec![T::zero(); n * n];
+    for (r, &j) in order.iter().enumerate() {
+        vt[r * n..(r + 1) * n].copy_from_slice(&v[j]);
+    }
+    (u, s, vt)
+}
+
+// Singular values only (descending), skipping the accumulation of U and V
+pub(crate) fn jacobi_svdvals<T: Float>(a: &[T], m: usize, n: usize) -> Vec<T> {
+    let mut cols: Vec<Vec<T>> = if m >= n {
+        (0..n).map(|j| (0..m).map(|i| a[i * n + j]).collect()).collect()
+    } else {
+        a.chunks(n).map(|row| row.to_vec()).collect()
+    };
+    orthogonalize_columns(&mut cols, None);
+    let mut s: Vec<T> = cols.iter().map(|c| dot(c, c).sqrt()).collect();
+    s.sort_by(|x, y| y.partial_cmp(x).unwrap_or(std::cmp::Ordering::Equal));
+    s
+}
+
+// Full SVD: U is m x m and Vt is n x n, extending the thin factors with
+// orthonormal completions
+pub(crate) fn jacobi_svd_full<T: Float>(a: &[T], m: usize, n: usize) -> (Vec<T>, Vec<T>, Vec<T>) {
+    let k = m.min(n);
+    let (u, s, vt) = jacobi_svd(a, m, n);
+    let mut u_cols: Vec<Vec<T>> = (0..k).map(|c| (0..m).map(|i| u[i * k + c]).collect()).collect();
+    complete_basis(&mut u_cols, m, m);
+    let mut v_rows: Vec<Vec<T>> = vt.chunks(n).map(|row| row.to_vec()).collect();
+    complete_basis(&mut v_rows, n, n);
+
+    let mut u_full = vec![T::zero(); m * m];
+    for (c, col) in u_cols.iter().enumerate() {
+        for i in 0..m {
+            u_full[i * m + c] = col[i];
+        }
+    }
+    (u_full, s, v_rows.concat())
+}
+
+// One-sided Jacobi sweeps until all column pairs are orthogonal to working
+// precision, applying the same rotations to the columns of v if given
+fn orthogonalize_columns<T: Float>(cols: &mut [Vec<T>], mut v: Option<&mut [Vec<T>]>) {
+    let n = cols.len();
+    let eps = T::epsilon();
+    let two = T::one() + T::one();
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let alpha = dot(&cols[p], &cols[p]);
+                let beta = dot(&cols[q], &cols[q]);
+                let gamma = dot(&cols[p], &cols[q]);
+                if gamma == T::zero() || gamma.abs() <= eps * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+
+                // Rotation that makes columns p and q orthogonal
+                let zeta = (beta - alpha) / (two * gamma);
+                let t = zeta.signum() / (zeta.abs() + (T::one() + zeta * zeta).sqrt());
+                let c = T::one() / (T::one() + t * t).sqrt();
+                let s = c * t;
+                rotate(cols, p, q, c, s);
+                if let Some(v) = v.as_deref_mut() {
+                    rotate(v, p, q, c, s);
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+}
+
+// Replace the zero vectors in `cols` and append new ones until there are
+// `count`, so that all of them form an orthonormal set in `dim` dimensions
+pub(crate) fn complete_basis<T: Float>(cols: &mut Vec<Vec<T>>, dim: usize, count: usize) {
+    cols.resize(count, vec![T::zero(); dim]);
+    let mut filled: Vec<bool> = cols.iter().map(|c| c.iter().any(|&x| x != T::zero())).collect();
+    for target in 0..count {
+        if filled[target] {
+            continue;
+        }
+        // The unit vector with the largest component outside the span wins
+        let mut best: Option<(T, Vec<T>)> = None;
+        for e in 0..dim {
+            let mut cand = vec![T::zero(); dim];
+            cand[e] = T::one();
+            for _ in 0..2 {
+                for (col, _) in cols.iter().zip(&filled).filter(|(_, &f)| f) {
+                    let proj = dot(&cand, col);
+                    for (x, &c) in cand.iter_mut().zip(col) {
+                        *x = *x - proj * c;
+                    }
+                }
+            }
+            let norm = dot(&cand, &cand).sqrt();
+            if best.as_ref().is_none_or(|(b, _)| norm > *b) {
+                best = Some((norm, cand));
+            }
+        }
+        let (norm, cand) = best.unwrap();
+        cols[target] = cand.iter().map(|&x| x / norm).collect();
+        filled[target] = true;
+    }
+}
+
+pub(crate) fn transpose<T: Copy>(a: &[T], m: usize, n: usize) -> Vec<T> {
+    let mut t = Vec::with_capacity(m * n);
+    for j in 0..n {
+        for i in 0..m {
+            t.push(a[i * n + j]);
+        }
+    }
+    t
+}
+
+fn dot<T: Float>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y).fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+}
+
+fn rotate<T: Float>(vecs: &mut [Vec<T>], p: usize, q: usize, c: T, s: T) {
+    let (head, tail) = vecs.split_at_mut(q);
+    for (xp, xq) in head[p].iter_mut().zip(tail[0].iter_mut()) {
+        let (a, b) = (*xp, *xq);
+        *xp = c * a - s * b;
+        *xq = s * a + c * b;
+    }
+}
+
+// Output of nr_svd. `u` and `vt` are None when compute_uv is false.
+#[derive(Debug)]
+pub struct SvdResult<T> {
+    // Left singular vectors as columns, (..., m, m) or (..., m, k)
+    pub u: Option<Array<T>>,
+    // Singular values in descending order, (..., k)
+    pub s: Array<T>,
+    // Right singular vectors as rows, (..., n, n) or (..., k, n)
+    pub vt: Option<Array<T>>,
+}
+
+// Singular value decomposition a = u @ diag(s) @ vt (np.linalg.svd), computed
+// by one-sided Jacobi rotations. Works on (..., m, n) stacks of matrices with
+// k = min(m, n). full_matrices selects square u and vt over the economy
+// factors; compute_uv = false returns the singular values only.
+pub fn nr_svd<T: Float>(a: &Array<T>, full_matrices: bool, compute_uv: bool) -> Result<SvdResult<T>, LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let k = m.min(n);
+    if !compute_uv {
+        return Ok(SvdResult { u: None, s: nr_svdvals(a)?, vt: None });
+    }
+
+    let (u_cols, vt_rows) = if full_matrices { (m, n) } else { (k, k) };
+    let mut u_data = Vec::with_capacity(a.totalsize / n.max(1) * u_cols);
+    let mut s_data = Vec::with_capacity(a.totalsize / (m * n).max(1) * k);
+    let mut vt_data = Vec::with_capacity(a.totalsize / m.max(1) * vt_rows);
+    for src in a.data.chunks(m * n) {
+        let (u, s, vt) = if full_matrices { jacobi_svd_full(src, m, n) } else { jacobi_svd(src, m, n) };
+        u_data.extend(u);
+        s_data.extend(s);
+        vt_data.extend(vt);
+    }
+    Ok(SvdResult {
+        u: Some(Array::from_vec(u_data, &stacked_shape(&batch, &[m, u_cols]))),
+        s: Array::from_vec(s_data, &stacked_shape(&batch, &[k])),
+        vt: Some(Array::from_vec(vt_data, &stacked_shape(&batch, &[vt_rows, n]))),
+    })
+}
+
+// Singular values of (a stack of) matrices in descending order, (..., k)
+pub fn nr_svdvals<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let mut s_data = Vec::new();
+    for src in a.data.chunks(m * n) {
+        s_data.extend(jacobi_svdvals(src, m, n));
+    }
+    Ok(Array::from_vec(s_data, &stacked_shape(&batch, &[m.min(n)])))
+}
+
+// 2-norm condition number s_max / s_min of each matrix (np.linalg.cond).
+// Singular matrices give infinity.
+pub fn nr_cond<T: Float>(a: &Array<T>) -> Result<Array<T>, LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let conds = a
+        .data
+        .chunks(m * n)
+        .map(|src| {
+            let s = jacobi_svdvals(src, m, n);
+            let (max, min) = (s[0], s[s.len() - 1]);
+            if min == T::zero() {
+                T::infinity()
+            } else {
+                max / min
+            }
+        })
+        .collect();
+    Ok(per_matrix(conds, &batch))
+}
+
+// Numerical rank of each matrix (np.linalg.matrix_rank): the number of
+// singular values above `tol`, which defaults to s_max * max(m, n) * eps
+pub fn nr_matrix_rank<T: Float>(a: &Array<T>, tol: Option<T>) -> Result<Array<usize>, LinalgError> {
+    let (batch, m, n) = matrix_dims(a)?;
+    let ranks = a
+        .data
+        .chunks(m * n)
+        .map(|src| {
+            let s = jacobi_svdvals(src, m, n);
+            let tol = tol.unwrap_or_else(|| s.first().copied().unwrap_or_else(T::zero) * T::from(m.max(n)).unwrap() * T::epsilon());
+            s.iter().filter(|&&x| x > tol).count()
+        })
+        .collect();
+    Ok(per_matrix(ranks, &batch))
+}
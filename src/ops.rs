@@ -1,6 +1,7 @@
 #![allow(unused)]
 use crate::array::{Array, ArrayIndices, LinearIndices};
-use crate::util::get_random_float;
+use crate::gemm;
+use crate::random::Generator;
 use rayon::prelude::*;
 use std::ops::{Add, Mul, Sub, Div};
 use std::process::Output;
@@ -29,15 +30,16 @@ where
     arr
 }
 
+// Uniform samples in [0, 1) from a freshly entropy-seeded Generator.
+// Use Generator::new(seed).random(shape) for reproducible draws.
 pub fn nr_random<T>(shape: &[i32], ndim: usize) -> Array<T>
 where 
-    T: Float,
+    T: Float + Send,
 {
-    let mut arr = Array::nr_create(shape, ndim);
-    for i in 0..arr.totalsize {
-        arr.data[i] = T::from(get_random_float(0.0, 1.0)).unwrap();
+    if shape.len() != ndim {
+        panic!("Cannot initialize Array with ndim {} or invalid shape", ndim);
     }
-    arr
+    Generator::from_entropy().random(shape)
 }
 
 pub fn nr_reshape_new<T:Copy + Zero>(arr: &Array<T>, shape: &[i32], ndim: usize) -> Array<T> {
@@ -50,26 +52,23 @@ pub fn nr_reshape_new<T:Copy + Zero>(arr: &Array<T>, shape: &[i32], ndim: usize)
     new_arr
 }
 
-fn broadcast_final_shape<T>(a: &Array<T>, b: &Array<T>) -> Option<Vec<i32>> {
-    if a.shape == b.shape {
-        return Some(a.shape.clone());
+pub(crate) fn broadcast_final_shape(a_shape: &[i32], b_shape: &[i32]) -> Option<Vec<i32>> {
+    if a_shape == b_shape {
+        return Some(a_shape.to_vec());
     }
 
-    let res_ndim = a.ndim.max(b.ndim);
-    let mut a_shape = vec![1; res_ndim];
-    let mut b_shape = vec![1; res_ndim];
+    let (a_ndim, b_ndim) = (a_shape.len(), b_shape.len());
+    let res_ndim = a_ndim.max(b_ndim);
+    let mut a_padded = vec![1; res_ndim];
+    let mut b_padded = vec![1; res_ndim];
 
-    for i in 0..a.ndim {
-        a_shape[res_ndim - a.ndim + i] = a.shape[i];
-    }
-    for i in 0..b.ndim {
-        b_shape[res_ndim - b.ndim + i] = b.shape[i];
-    }
+    a_padded[res_ndim - a_ndim..].copy_from_slice(a_shape);
+    b_padded[res_ndim - b_ndim..].copy_from_slice(b_shape);
 
     let mut res_shape = vec![0; res_ndim];
     for i in 0..res_ndim {
-        if a_shape[i] == 1 || b_shape[i] == 1 || a_shape[i] == b_shape[i] {
-            res_shape[i] = a_shape[i].max(b_shape[i]);
+        if a_padded[i] == 1 || b_padded[i] == 1 || a_padded[i] == b_padded[i] {
+            res_shape[i] = a_padded[i].max(b_padded[i]);
         } else {
             return None;
         }
@@ -77,7 +76,7 @@ fn broadcast_final_shape<T>(a: &Array<T>, b: &Array<T>) -> Option<Vec<i32>> {
     Some(res_shape)
 }
 
-fn broadcast_array<T: Copy + Zero>(arr: &Array<T>, shape: &[i32], ndim: usize) -> Array<T> {
+pub(crate) fn broadcast_array<T: Copy + Zero>(arr: &Array<T>, shape: &[i32], ndim: usize) -> Array<T> {
     let mut res = Array::nr_create(shape, ndim);
     let n_prepend = ndim - arr.ndim;
 
@@ -105,7 +104,7 @@ where
         return res;
     }
 
-    let res_shape = broadcast_final_shape(a, b).expect("Cannot add arrays of non-broadcastable shapes");
+    let res_shape = broadcast_final_shape(&a.shape, &b.shape).expect("Cannot add arrays of non-broadcastable shapes");
     let res_ndim = res_shape.len();
     let a_final = broadcast_array(a, &res_shape, res_ndim);
     let b_final = broadcast_array(b, &res_shape, res_ndim);
@@ -128,7 +127,7 @@ where
         return res;
     }
 
-    let res_shape = broadcast_final_shape(a, b).expect("Cannot multiply arrays of non-broadcastable shapes");
+    let res_shape = broadcast_final_shape(&a.shape, &b.shape).expect("Cannot multiply arrays of non-broadcastable shapes");
     let res_ndim = res_shape.len();
     let a_final = broadcast_array(a, &res_shape, res_ndim);
     let b_final = broadcast_array(b, &res_shape, res_ndim);
@@ -139,69 +138,98 @@ where
     res
 }
 
+// Matrix product with np.matmul semantics.
+// 1-D operands are promoted to a row (a) or column (b) vector and the added
+// dimension is removed from the result. Leading batch dimensions broadcast
+// from the right. Two 1-D operands give a result of shape [1].
 pub fn nr_matmul<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
 where 
-    T: Num + Copy + Sum,
+    T: Num + Copy + Send + Sync + 'static,
 {
-    if a.ndim < 2 || b.ndim < 2 {
-        panic!("Both arrays must have atleast 2 dimensions for matmul");
-    }
-    if a.shape[a.ndim - 1] != b.shape[b.ndim - 2] {
-        panic!("Last dimension of first array must match second-last dimension of second array");
-    }
+    let a_vec = a.ndim == 1;
+    let b_vec = b.ndim == 1;
+    let a_shape = if a_vec { vec![1, a.shape[0]] } else { a.shape.clone() };
+    let b_shape = if b_vec { vec![b.shape[0], 1] } else { b.shape.clone() };
+    let (a_nd, b_nd) = (a_shape.len(), b_shape.len());
+
+    let m = a_shape[a_nd - 2];
+    let n = a_shape[a_nd - 1];
+    let p = b_shape[b_nd - 1];
+    if n != b_shape[b_nd - 2] {
+        panic!(
+            "matmul: core dimension mismatch, {:?} @ {:?} ({} != {})",
+            a.shape, b.shape, n, b_shape[b_nd - 2]
+        );
+    }
+
+    let a_batch = &a_shape[..a_nd - 2];
+    let b_batch = &b_shape[..b_nd - 2];
+    let batch_shape = broadcast_final_shape(a_batch, b_batch).unwrap_or_else(|| {
+        panic!(
+            "matmul: batch dimensions {:?} and {:?} cannot be broadcast together",
+            a_batch, b_batch
+        )
+    });
+
+    let mut result_shape = batch_shape.clone();
+    result_shape.push(m);
+    result_shape.push(p);
+    let mut result = Array::nr_create(&result_shape, result_shape.len());
+    let (m, n, p) = (m as usize, n as usize, p as usize);
+
+    let a_batch_strides = batch_strides(a_batch, m * n);
+    let b_batch_strides = batch_strides(b_batch, n * p);
+    let idxs = Array::<T>::create_array_indices(&batch_shape, batch_shape.len());
+    for (bi, nd_index) in idxs.indices.iter().enumerate() {
+        // Batch offsets are fixed for the whole matrix, so resolve them once
+        let a_off = batch_offset(nd_index, a_batch, &a_batch_strides);
+        let b_off = batch_offset(nd_index, b_batch, &b_batch_strides);
+        let r_off = bi * m * p;
+        gemm::matmul_2d(
+            &a.data[a_off..a_off + m * n],
+            &b.data[b_off..b_off + n * p],
+            &mut result.data[r_off..r_off + m * p],
+            m,
+            n,
+            p,
+        );
+    }
+
+    // Drop the dimensions that were added for vector operands
+    let mut final_shape = batch_shape;
+    if !a_vec {
+        final_shape.push(m as i32);
+    }
+    if !b_vec {
+        final_shape.push(p as i32);
+    }
+    if final_shape.is_empty() {
+        final_shape.push(1);
+    }
+    if final_shape == result.shape {
+        return result;
+    }
+    nr_reshape_new(&result, &final_shape, final_shape.len())
+}
 
-    let result_ndim = a.ndim.max(b.ndim);
-    let mut result_shape = vec![0; result_ndim];
-    
-    for i in 0..result_ndim - 2 {
-        result_shape[i] = if i < a.ndim - 2 {
-            a.shape[i]
-        } else {
-            1
-        };
+// Element strides of the batch dimensions of a contiguous stack of matrices
+pub(crate) fn batch_strides(batch: &[i32], matrix_size: usize) -> Vec<usize> {
+    let mut strides = vec![matrix_size; batch.len()];
+    for i in (0..batch.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * batch[i + 1] as usize;
+    }
+    strides
+}
 
-        result_shape[i] = if i < b.ndim - 2 {
-            result_shape[i].max(b.shape[i])
-        } else {
-            result_shape[i]
-        };
-    }
-
-    result_shape[result_ndim - 2] = a.shape[a.ndim - 2];
-    result_shape[result_ndim - 1] = b.shape[b.ndim - 1];
-
-    let mut result = Array::nr_create(&result_shape, result_ndim);
-    let m = a.shape[a.ndim - 2] as usize;
-    let n = a.shape[a.ndim - 1] as usize;
-    let p = b.shape[b.ndim - 1] as usize;
-
-    let idxs = Array::<T>::create_array_indices(&result_shape[..result_ndim -2], result_ndim - 2);
-    for idx in 0..idxs.count {
-        let nd_index = &idxs.indices[idx];
-        for i in 0..m {
-            for j in 0..p {
-                let mut sum = T::zero();
-                for k in 0..n {
-                    let mut a_index1d = 0;
-                    let mut b_index1d = 0;
-                    for d in 0..a.ndim - 2 {
-                        a_index1d += (nd_index[d] * a.strides[d]) as usize;
-                    }
-                    for d in 0..b.ndim - 2 {
-                        b_index1d += (nd_index[d] * b.strides[d]) as usize;
-                    }
-                    a_index1d += (i * a.strides[a.ndim - 2] as usize + k * a.strides[a.ndim -1] as usize) / a.itemsize;
-                    b_index1d += (k * b.strides[b.ndim - 2] as usize + j * b.strides[b.ndim - 1] as usize) / b.itemsize;
-                    sum = sum + a.data[a_index1d] * b.data[b_index1d];
-                }
-                let mut r_index1d = 0;
-                for d in 0..result.ndim - 2 {
-                    r_index1d += (nd_index[d] * result.strides[d]) as usize;
-                }
-                r_index1d += (i * result.strides[result.ndim - 2] as usize + j * result.strides[result.ndim - 1] as usize) / result.itemsize;
-                result.data[r_index1d] = sum;
-            }
+// Offset of one operand's matrix for a broadcast batch index.
+// The operand's batch dims align with the right of the index; size-1 dims repeat.
+pub(crate) fn batch_offset(nd_index: &[i32], batch: &[i32], strides: &[usize]) -> usize {
+    let lead = nd_index.len() - batch.len();
+    let mut off = 0;
+    for (d, (&dim, &stride)) in batch.iter().zip(strides).enumerate() {
+        if dim > 1 {
+            off += nd_index[lead + d] as usize * stride;
         }
     }
-    result
-}
\ No newline at end of file
+    off
+}
@@ -0,0 +1,311 @@
+// L-BFGS-B (Byrd, Lu, Nocedal & Zhu, 1995): the limited-memory BFGS model
+// B = theta I - W M W' with W = [Y, theta S] is minimized along the
+// projected steepest descent path to the generalized Cauchy point, then
+// over the variables still free there, and a line search runs towards
+// that subspace minimizer.
+use super::minimize::{wolfe_search, Objective, OptimizeResult};
+use super::dot;
+use crate::linalg::{lu_in_place, lu_solve};
+use std::collections::VecDeque;
+
+// Number of correction pairs kept
+const MEMORY: usize = 10;
+
+// The compact representation: rows of W (2k entries per variable) and the
+// 2k x 2k middle matrix M
+struct Model {
+    theta: f64,
+    k2: usize,
+    w: Vec<f64>,
+    m: Vec<f64>,
+}
+
+impl Model {
+    fn row(&self, i: usize) -> &[f64] {
+        &self.w[i * self.k2..(i + 1) * self.k2]
+    }
+
+    fn m_times(&self, v: &[f64]) -> Vec<f64> {
+        if self.k2 == 0 {
+            return Vec::new();
+        }
+        self.m.chunks(self.k2).map(|row| dot(row, v)).collect()
+    }
+
+    // Build the model from the stored pairs; None when the middle matrix
+    // cannot be inverted
+    fn new(s: &VecDeque<Vec<f64>>, y: &VecDeque<Vec<f64>>, theta: f64, n: usize) -> Option<Model> {
+        let k = s.len();
+        let k2 = 2 * k;
+        let mut w = vec![0.0; n * k2];
+        for i in 0..n {
+            for j in 0..k {
+                w[i * k2 + j] = y[j][i];
+                w[i * k2 + k + j] = theta * s[j][i];
+            }
+        }
+        if k == 0 {
+            return Some(Model { theta, k2, w, m: Vec::new() });
+        }
+        // M^-1 = [[-D, L'], [L, theta S'S]] with D = diag(s_i'y_i) and L the
+        // strictly lower triangle of S'Y
+        let mut inv = vec![0.0; k2 * k2];
+        for i in 0..k {
+            for j in 0..k {
+                let sy = dot(&s[i], &y[j]);
+                if i == j {
+                    inv[i * k2 + j] = -sy;
+                } else if i > j {
+                    inv[(k + i) * k2 + j] = sy;
+                    inv[j * k2 + k + i] = sy;
+                }
+                inv[(k + i) * k2 + k + j] = theta * dot(&s[i], &s[j]);
+            }
+        }
+        let factors = lu_in_place(&mut inv, k2, k2);
+        if factors.singular {
+            return None;
+        }
+        let mut eye = vec![0.0; k2 * k2];
+        for i in 0..k2 {
+            eye[i * k2 + i] = 1.0;
+        }
+        let m = lu_solve(&inv, &factors.perm, k2, &eye, k2);
+        Some(Model { theta, k2, w, m })
+    }
+}
+
+// Generalized Cauchy point: the first local minimizer of the model along
+// the path x - t g bent by the bounds. Returns it together with
+// c = W' (xcp - x).
+fn cauchy_point(x: &[f64], g: &[f64], bounds: &[(f64, f64)], model: &Model) -> (Vec<f64>, Vec<f64>) {
+    let n = x.len();
+    let theta = model.theta;
+    // Breakpoint times where each variable reaches its bound
+    let t: Vec<f64> = (0..n)
+        .map(|i| {
+            let (lo, hi) = bounds[i];
+            if g[i] < 0.0 {
+                (x[i] - hi) / g[i]
+            } else if g[i] > 0.0 {
+                (x[i] - lo) / g[i]
+            } else {
+                f64::INFINITY
+            }
+        })
+        .collect();
+    let mut d: Vec<f64> = (0..n).map(|i| if t[i] == 0.0 { 0.0 } else { -g[i] }).collect();
+    let mut xcp = x.to_vec();
+    let mut p = vec![0.0; model.k2];
+    for (i, &di) in d.iter().enumerate() {
+        for (p, w) in p.iter_mut().zip(model.row(i)) {
+            *p += w * di;
+        }
+    }
+    let mut c = vec![0.0; model.k2];
+
+    // First and second derivatives of the model along the current segment
+    let mut fp = -dot(&d, &d);
+    if fp >= 0.0 {
+        return (xcp, c);
+    }
+    let mut fpp = -theta * fp - dot(&p, &model.m_times(&p));
+    let fpp0 = fpp;
+    let mut dt_min = -fp / fpp;
+    let mut t_old = 0.0;
+
+    let mut order: Vec<usize> = (0..n).filter(|&i| t[i] > 0.0 && t[i].is_finite()).collect();
+    order.sort_by(|&a, &b| t[a].total_cmp(&t[b]));
+    for &b in &order {
+        let dt = t[b] - t_old;
+        if dt_min < dt {
+            break;
+        }
+        // Fix variable b at its bound and update the derivatives for the
+        // next segment
+        xcp[b] = if d[b] > 0.0 { bounds[b].1 } else { bounds[b].0 };
+        let zb = xcp[b] - x[b];
+        for (c, p) in c.iter_mut().zip(&p) {
+            *c += dt * p;
+        }
+        let gb = g[b];
+        let wb = model.row(b);
+        let mwb = model.m_times(wb);
+        fp += dt * fpp + gb * gb + theta * gb * zb - gb * dot(&mwb, &c);
+        fpp -= theta * gb * gb + 2.0 * gb * dot(&mwb, &p) + gb * gb * dot(&mwb, wb);
+        fpp = fpp.max(f64::EPSILON * fpp0);
+        for (p, w) in p.iter_mut().zip(wb) {
+            *p += gb * w;
+        }
+        d[b] = 0.0;
+        dt_min = -fp / fpp;
+        t_old = t[b];
+    }
+    let t_end = t_old + dt_min.max(0.0);
+    for i in 0..n {
+        if d[i] != 0.0 {
+            xcp[i] = x[i] + t_end * d[i];
+        }
+    }
+    for (c, p) in c.iter_mut().zip(&p) {
+        *c += dt_min.max(0.0) * p;
+    }
+    (xcp, c)
+}
+
+// Minimize the model over the variables that are free at the Cauchy point
+// (direct primal method), then step back inside the bounds
+fn subspace_min(x: &[f64], g: &[f64], bounds: &[(f64, f64)], model: &Model, xcp: &[f64], c: &[f64]) -> Vec<f64> {
+    let theta = model.theta;
+    let k2 = model.k2;
+    let free: Vec<usize> = (0..x.len()).filter(|&i| xcp[i] > bounds[i].0 && xcp[i] < bounds[i].1).collect();
+    if free.is_empty() {
+        return xcp.to_vec();
+    }
+
+    // Reduced gradient of the model at the Cauchy point
+    let mc = model.m_times(c);
+    let r: Vec<f64> = free.iter().map(|&i| g[i] + theta * (xcp[i] - x[i]) - dot(model.row(i), &mc)).collect();
+    let mut du: Vec<f64> = r.iter().map(|r| -r / theta).collect();
+    if k2 > 0 {
+        // Sherman-Morrison-Woodbury: du = -r / theta - W_Z v / theta^2 with
+        // (I - M W_Z'W_Z / theta) v = M W_Z' r
+        let mut wr = vec![0.0; k2];
+        let mut wzw = vec![0.0; k2 * k2];
+        for (&i, &ri) in free.iter().zip(&r) {
+            let wi = model.row(i);
+            for a in 0..k2 {
+                wr[a] += wi[a] * ri;
+                for b in 0..k2 {
+                    wzw[a * k2 + b] += wi[a] * wi[b];
+                }
+            }
+        }
+        let rhs = model.m_times(&wr);
+        let mut nmat = vec![0.0; k2 * k2];
+        for a in 0..k2 {
+            for b in 0..k2 {
+                let mw: f64 = (0..k2).map(|l| model.m[a * k2 + l] * wzw[l * k2 + b]).sum();
+                nmat[a * k2 + b] = if a == b { 1.0 } else { 0.0 } - mw / theta;
+            }
+        }
+        let factors = lu_in_place(&mut nmat, k2, k2);
+        if factors.singular {
+            return xcp.to_vec();
+        }
+        let v = lu_solve(&nmat, &factors.perm, k2, &rhs, 1);
+        for (du, &i) in du.iter_mut().zip(&free) {
+            *du -= dot(model.row(i), &v) / (theta * theta);
+        }
+    }
+
+    // Longest feasible fraction of the step
+    let mut alpha: f64 = 1.0;
+    for (&du, &i) in du.iter().zip(&free) {
+        let (lo, hi) = bounds[i];
+        if du > 0.0 {
+            alpha = alpha.min((hi - xcp[i]) / du);
+        } else if du < 0.0 {
+            alpha = alpha.min((lo - xcp[i]) / du);
+        }
+    }
+    let mut xbar = xcp.to_vec();
+    for (&du, &i) in du.iter().zip(&free) {
+        xbar[i] += alpha * du;
+    }
+    xbar
+}
+
+// Largest step along d from x that stays inside the bounds
+fn max_step(x: &[f64], d: &[f64], bounds: &[(f64, f64)]) -> f64 {
+    let mut amax = f64::INFINITY;
+    for ((&x, &d), &(lo, hi)) in x.iter().zip(d).zip(bounds) {
+        if d > 0.0 {
+            amax = amax.min((hi - x) / d);
+        } else if d < 0.0 {
+            amax = amax.min((lo - x) / d);
+        }
+    }
+    amax
+}
+
+pub(super) fn lbfgsb(obj: &Objective, mut x: Vec<f64>, bounds: &[(f64, f64)], ftol: f64, gtol: f64, maxiter: usize, maxfun: usize) -> OptimizeResult {
+    let n = x.len();
+    let mut f = obj.value(&x);
+    let mut g = obj.grad(&x, f);
+    let mut s: VecDeque<Vec<f64>> = VecDeque::new();
+    let mut y: VecDeque<Vec<f64>> = VecDeque::new();
+    let mut theta = 1.0;
+
+    // Largest component of the gradient projected onto the feasible box
+    let pg_norm = |x: &[f64], g: &[f64]| {
+        (0..n).fold(0.0, |m: f64, i| {
+            let (lo, hi) = bounds[i];
+            let pg = if g[i] < 0.0 { (x[i] - hi).max(g[i]) } else { (x[i] - lo).min(g[i]) };
+            m.max(pg.abs())
+        })
+    };
+
+    let mut nit = 0;
+    let (success, message) = loop {
+        // NaN would pass unnoticed through the max in pg_norm
+        if !f.is_finite() || g.iter().any(|g| !g.is_finite()) {
+            break (false, "ABNORMAL: OBJECTIVE OR GRADIENT IS NOT FINITE");
+        }
+        if pg_norm(&x, &g) <= gtol {
+            break (true, "CONVERGENCE: NORM OF PROJECTED GRADIENT <= PGTOL");
+        }
+        if nit >= maxiter {
+            break (false, "STOP: TOTAL NO. OF ITERATIONS REACHED LIMIT");
+        }
+        if obj.nfev.get() >= maxfun {
+            break (false, "STOP: TOTAL NO. OF F,G EVALUATIONS EXCEEDS LIMIT");
+        }
+
+        let step = Model::new(&s, &y, theta, n).and_then(|model| {
+            let (xcp, c) = cauchy_point(&x, &g, bounds, &model);
+            let xbar = subspace_min(&x, &g, bounds, &model, &xcp, &c);
+            let d: Vec<f64> = xbar.iter().zip(&x).map(|(a, b)| a - b).collect();
+            let amax = max_step(&x, &d, bounds);
+            // Without curvature information, start with a unit-length step
+            let alpha1 = if s.is_empty() { (1.0 / dot(&d, &d).sqrt()).min(amax) } else { 1.0 };
+            wolfe_search(obj, &x, &d, f, &g, alpha1, amax)
+        });
+        let Some(step) = step else {
+            if s.is_empty() {
+                break (false, "ABNORMAL_TERMINATION_IN_LNSRCH");
+            }
+            // Discard the curvature pairs and restart from steepest descent
+            s.clear();
+            y.clear();
+            theta = 1.0;
+            continue;
+        };
+        nit += 1;
+
+        let sk: Vec<f64> = step.x.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let yk: Vec<f64> = step.g.iter().zip(&g).map(|(a, b)| a - b).collect();
+        let decrease = f - step.f;
+        let scale = f.abs().max(step.f.abs()).max(1.0);
+        x = step.x;
+        f = step.f;
+        g = step.g;
+        if decrease <= ftol * scale {
+            break (true, "CONVERGENCE: RELATIVE REDUCTION OF F <= FACTR*EPSMCH");
+        }
+
+        // Keep the pair only when it carries positive curvature
+        let sy = dot(&sk, &yk);
+        let yy = dot(&yk, &yk);
+        if sy > f64::EPSILON * yy {
+            if s.len() == MEMORY {
+                s.pop_front();
+                y.pop_front();
+            }
+            s.push_back(sk);
+            y.push_back(yk);
+            theta = yy / sy;
+        }
+    };
+    obj.result(x, f, Some(g), nit, success, message)
+}
@@ -0,0 +1,252 @@
+use super::{dot, fd_step, start_point, to_array, ArrayFn};
+use crate::array::Array;
+use crate::linalg::{lu_in_place, lu_solve, nr_svd, LinalgError};
+
+// Outcome of nr_least_squares
+#[derive(Debug)]
+pub struct LeastSquaresResult {
+    pub x: Array<f64>,
+    // Half the sum of squared residuals at x
+    pub cost: f64,
+    // Residuals, their [m, n] Jacobian and the gradient J'r of the cost
+    pub fun: Array<f64>,
+    pub jac: Array<f64>,
+    pub grad: Array<f64>,
+    // Residual evaluations (finite differences included) and Jacobian
+    // evaluations
+    pub nfev: usize,
+    pub njev: usize,
+    pub success: bool,
+    pub message: String,
+}
+
+// Minimize half the sum of squares of the residuals fun(x) by
+// Levenberg-Marquardt (scipy.optimize.least_squares with method='lm'). jac
+// returns the [m, n] Jacobian of the residuals and is estimated by forward
+// differences when None. The damping is scaled by the largest column norms
+// of the Jacobian seen so far. Stops when the relative decrease of the cost
+// is at most ftol, the scaled step at most xtol times the scaled x, or the
+// largest cosine between the residuals and a Jacobian column at most gtol
+// (all default 1e-8), or after max_nfev residual evaluations (default 100 n
+// with jac, 100 n (n + 1) without).
+pub fn nr_least_squares<F: Fn(&Array<f64>) -> Array<f64>>(
+    fun: F,
+    x0: &Array<f64>,
+    jac: Option<&ArrayFn>,
+    ftol: Option<f64>,
+    xtol: Option<f64>,
+    gtol: Option<f64>,
+    max_nfev: Option<usize>,
+) -> LeastSquaresResult {
+    let mut x = start_point(x0, "nr_least_squares");
+    let n = x.len();
+    let ftol = ftol.unwrap_or(1e-8);
+    let xtol = xtol.unwrap_or(1e-8);
+    let gtol = gtol.unwrap_or(1e-8);
+    if [ftol, xtol, gtol].iter().any(|t| t.is_nan() || *t < 0.0) {
+        panic!("nr_least_squares: tolerances must be non-negative");
+    }
+    let max_nfev = max_nfev.unwrap_or(if jac.is_some() { 100 * n } else { 100 * n * (n + 1) });
+
+    let mut nfev = 0;
+    let mut njev = 0;
+    let residuals = |x: &[f64], nfev: &mut usize| {
+        *nfev += 1;
+        fun(&to_array(x)).data
+    };
+    let mut r = residuals(&x, &mut nfev);
+    let m = r.len();
+    if m < n {
+        panic!("nr_least_squares: Levenberg-Marquardt needs at least as many residuals ({}) as variables ({})", m, n);
+    }
+
+    // Row-major [m, n] Jacobian at x, where the residuals are r
+    let mut jacobian = |x: &[f64], r: &[f64], nfev: &mut usize| -> Vec<f64> {
+        njev += 1;
+        if let Some(jac) = jac {
+            let j = jac(&to_array(x));
+            if j.totalsize != m * n {
+                panic!("nr_least_squares: Jacobian has shape {:?}, expected [{}, {}]", j.shape, m, n);
+            }
+            return j.data;
+        }
+        let mut j = vec![0.0; m * n];
+        let mut xh = x.to_vec();
+        for c in 0..n {
+            xh[c] = x[c] + fd_step(x[c], f64::NEG_INFINITY, f64::INFINITY);
+            let h = xh[c] - x[c];
+            let rh = residuals(&xh, nfev);
+            for i in 0..m {
+                j[i * n + c] = (rh[i] - r[i]) / h;
+            }
+            xh[c] = x[c];
+        }
+        j
+    };
+
+    let mut cost = 0.5 * dot(&r, &r);
+    let mut j = jacobian(&x, &r, &mut nfev);
+    let mut diag = vec![0.0f64; n];
+    let mut mu = 1e-3;
+    let mut nu = 2.0;
+    let (success, message) = loop {
+        // J'J, the gradient J'r and the column norms of J
+        let mut jtj = vec![0.0; n * n];
+        let mut grad = vec![0.0; n];
+        for (row, ri) in j.chunks(n).zip(&r) {
+            for a in 0..n {
+                grad[a] += row[a] * ri;
+                for b in 0..n {
+                    jtj[a * n + b] += row[a] * row[b];
+                }
+            }
+        }
+        for (d, a) in diag.iter_mut().enumerate() {
+            *a = (*a).max(jtj[d * n + d].sqrt());
+            if *a == 0.0 {
+                *a = 1.0;
+            }
+        }
+        let rnorm = dot(&r, &r).sqrt();
+        let cosine = (0..n).fold(0.0, |mx: f64, c| {
+            let cn = jtj[c * n + c].sqrt();
+            if cn == 0.0 || rnorm == 0.0 {
+                mx
+            } else {
+                mx.max(grad[c].abs() / (cn * rnorm))
+            }
+        });
+        if cosine <= gtol {
+            break (true, "`gtol` termination condition is satisfied.");
+        }
+        if nfev >= max_nfev {
+            break (false, "The maximum number of function evaluations is exceeded.");
+        }
+
+        // Damped step (J'J + mu D^2) h = -J'r
+        let mut a = jtj.clone();
+        for (d, s) in diag.iter().enumerate() {
+            a[d * n + d] += mu * s * s;
+        }
+        let factors = lu_in_place(&mut a, n, n);
+        if factors.singular {
+            mu *= nu;
+            nu *= 2.0;
+            continue;
+        }
+        let neg_grad: Vec<f64> = grad.iter().map(|g| -g).collect();
+        let h = lu_solve(&a, &factors.perm, n, &neg_grad, 1);
+        let x_new: Vec<f64> = x.iter().zip(&h).map(|(x, h)| x + h).collect();
+        let r_new = residuals(&x_new, &mut nfev);
+        let cost_new = 0.5 * dot(&r_new, &r_new);
+
+        // Gain ratio of the actual to the predicted decrease
+        let predicted: f64 = 0.5 * h.iter().zip(&grad).zip(&diag).map(|((h, g), s)| h * (mu * s * s * h - g)).sum::<f64>();
+        let actual = cost - cost_new;
+        let scaled_step = h.iter().zip(&diag).map(|(h, s)| (h * s).powi(2)).sum::<f64>().sqrt();
+        let scaled_x = x.iter().zip(&diag).map(|(x, s)| (x * s).powi(2)).sum::<f64>().sqrt();
+        let rho = actual / predicted;
+        if cost_new.is_finite() && actual > 0.0 && rho > 0.0 {
+            x = x_new;
+            r = r_new;
+            cost = cost_new;
+            if actual <= ftol * (cost + actual) {
+                j = jacobian(&x, &r, &mut nfev);
+                break (true, "`ftol` termination condition is satisfied.");
+            }
+            if scaled_step <= xtol * (scaled_x + xtol) {
+                j = jacobian(&x, &r, &mut nfev);
+                break (true, "`xtol` termination condition is satisfied.");
+            }
+            j = jacobian(&x, &r, &mut nfev);
+            mu *= (1.0 / 3.0f64).max(1.0 - (2.0 * rho - 1.0).powi(3));
+            nu = 2.0;
+        } else {
+            if scaled_step <= xtol * (scaled_x + xtol) {
+                break (true, "`xtol` termination condition is satisfied.");
+            }
+            mu *= nu;
+            nu *= 2.0;
+        }
+    };
+
+    let grad: Vec<f64> = (0..n).map(|c| j.chunks(n).zip(&r).map(|(row, ri)| row[c] * ri).sum()).collect();
+    LeastSquaresResult {
+        x: to_array(&x),
+        cost,
+        fun: to_array(&r),
+        jac: Array::from_vec(j, &[m as i32, n as i32]),
+        grad: to_array(&grad),
+        nfev,
+        njev,
+        success,
+        message: message.to_string(),
+    }
+}
+
+// Fit the parameters p of f(xdata, p) to ydata by least squares
+// (scipy.optimize.curve_fit with method='lm'), starting from p0. sigma
+// holds the standard deviations of ydata; with absolute_sigma false only
+// their relative sizes matter and the covariance is scaled by the reduced
+// chi-square. Returns the optimal parameters and their covariance, which is
+// infinite when it cannot be estimated, or NoConvergence when the fit does
+// not converge.
+pub fn nr_curve_fit<F: Fn(&Array<f64>, &Array<f64>) -> Array<f64>>(
+    f: F,
+    xdata: &Array<f64>,
+    ydata: &Array<f64>,
+    p0: &Array<f64>,
+    sigma: Option<&Array<f64>>,
+    absolute_sigma: bool,
+) -> Result<(Array<f64>, Array<f64>), LinalgError> {
+    let m = ydata.totalsize;
+    let n = start_point(p0, "nr_curve_fit").len();
+    if let Some(sigma) = sigma {
+        if sigma.totalsize != m {
+            panic!("nr_curve_fit: sigma has {} elements for {} data points", sigma.totalsize, m);
+        }
+    }
+    let residuals = |p: &Array<f64>| {
+        let model = f(xdata, p);
+        if model.totalsize != m {
+            panic!("nr_curve_fit: model returned {} values for {} data points", model.totalsize, m);
+        }
+        let r: Vec<f64> = match sigma {
+            Some(sigma) => model.data.iter().zip(&ydata.data).zip(&sigma.data).map(|((f, y), s)| (f - y) / s).collect(),
+            None => model.data.iter().zip(&ydata.data).map(|(f, y)| f - y).collect(),
+        };
+        to_array(&r)
+    };
+    let tol = 1.49012e-8;
+    let res = nr_least_squares(residuals, p0, None, Some(tol), Some(tol), Some(0.0), Some(200 * (n + 1)));
+    if !res.success {
+        return Err(LinalgError::NoConvergence);
+    }
+
+    // Pseudo-inverse of J'J from the SVD of J, dropping negligible
+    // singular values
+    let svd = nr_svd(&res.jac, false, true)?;
+    let s = &svd.s.data;
+    let vt = svd.vt.expect("nr_svd computes vt when asked").data;
+    let threshold = f64::EPSILON * m.max(n) as f64 * s[0];
+    let mut pcov = vec![0.0; n * n];
+    for (k, &sk) in s.iter().enumerate().filter(|&(_, &sk)| sk > threshold) {
+        let v = &vt[k * n..(k + 1) * n];
+        for a in 0..n {
+            for b in 0..n {
+                pcov[a * n + b] += v[a] * v[b] / (sk * sk);
+            }
+        }
+    }
+    if pcov.iter().any(|v| v.is_nan()) {
+        pcov.fill(f64::INFINITY);
+    } else if !absolute_sigma {
+        if m > n {
+            let s_sq = 2.0 * res.cost / (m - n) as f64;
+            pcov.iter_mut().for_each(|v| *v *= s_sq);
+        } else {
+            pcov.fill(f64::INFINITY);
+        }
+    }
+    Ok((res.x, Array::from_vec(pcov, &[n as i32, n as i32])))
+}
@@ -0,0 +1,409 @@
+use super::{dot, fd_step, lbfgsb, start_point, to_array, ArrayFn};
+use crate::array::Array;
+use std::cell::Cell;
+
+// Algorithm for nr_minimize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimizeMethod {
+    // Downhill simplex; uses function values only and honours bounds by
+    // clipping the simplex
+    NelderMead,
+    // Quasi-Newton with a dense inverse Hessian and a Wolfe line search
+    Bfgs,
+    // Limited-memory BFGS with simple bounds on each variable
+    LBfgsB,
+}
+
+// Outcome of nr_minimize
+#[derive(Debug)]
+pub struct OptimizeResult {
+    pub x: Array<f64>,
+    // Objective and gradient at x; there is no gradient for Nelder-Mead
+    pub fun: f64,
+    pub jac: Option<Array<f64>>,
+    // Iterations, objective and gradient evaluations (finite differences
+    // count towards nfev)
+    pub nit: usize,
+    pub nfev: usize,
+    pub njev: usize,
+    pub success: bool,
+    pub message: String,
+}
+
+// The objective with its gradient, bounds and evaluation counters
+pub(super) struct Objective<'f> {
+    fun: &'f dyn Fn(&Array<f64>) -> f64,
+    jac: Option<&'f ArrayFn<'f>>,
+    bounds: Option<&'f [(f64, f64)]>,
+    pub(super) nfev: Cell<usize>,
+    pub(super) njev: Cell<usize>,
+}
+
+impl Objective<'_> {
+    pub(super) fn value(&self, x: &[f64]) -> f64 {
+        self.nfev.set(self.nfev.get() + 1);
+        (self.fun)(&to_array(x))
+    }
+
+    // Gradient at x, where the objective is f0
+    pub(super) fn grad(&self, x: &[f64], f0: f64) -> Vec<f64> {
+        self.njev.set(self.njev.get() + 1);
+        if let Some(jac) = self.jac {
+            let g = jac(&to_array(x));
+            if g.totalsize != x.len() {
+                panic!("nr_minimize: gradient has {} elements for {} variables", g.totalsize, x.len());
+            }
+            return g.data;
+        }
+        let mut xh = x.to_vec();
+        (0..x.len())
+            .map(|i| {
+                let (lo, hi) = self.bounds.map_or((f64::NEG_INFINITY, f64::INFINITY), |b| b[i]);
+                let h = x[i] + fd_step(x[i], lo, hi) - x[i];
+                if h == 0.0 {
+                    // No room to move, as for a variable fixed by lo == hi
+                    return 0.0;
+                }
+                xh[i] = x[i] + h;
+                let d = (self.value(&xh) - f0) / h;
+                xh[i] = x[i];
+                d
+            })
+            .collect()
+    }
+
+    pub(super) fn clip(&self, x: &mut [f64]) {
+        if let Some(bounds) = self.bounds {
+            for (x, &(lo, hi)) in x.iter_mut().zip(bounds) {
+                *x = x.max(lo).min(hi);
+            }
+        }
+    }
+
+    pub(super) fn result(&self, x: Vec<f64>, fun: f64, jac: Option<Vec<f64>>, nit: usize, success: bool, message: &str) -> OptimizeResult {
+        OptimizeResult {
+            x: to_array(&x),
+            fun,
+            jac: jac.map(|g| to_array(&g)),
+            nit,
+            nfev: self.nfev.get(),
+            njev: self.njev.get(),
+            success,
+            message: message.to_string(),
+        }
+    }
+}
+
+// Minimize fun from x0 (scipy.optimize.minimize). jac is the gradient,
+// estimated by finite differences when None; bounds are (lower, upper)
+// pairs per variable, infinite when a side is unbounded, and are rejected
+// by BFGS. tol is the termination tolerance of the method (Nelder-Mead: on
+// the simplex size and function spread, default 1e-4; BFGS: on the largest
+// gradient component, default 1e-5; L-BFGS-B: on the projected gradient
+// and the relative decrease of fun). maxiter defaults to 200 n for
+// Nelder-Mead and BFGS and 15000 for L-BFGS-B.
+pub fn nr_minimize<F: Fn(&Array<f64>) -> f64>(
+    fun: F,
+    x0: &Array<f64>,
+    method: MinimizeMethod,
+    jac: Option<&ArrayFn>,
+    bounds: Option<&[(f64, f64)]>,
+    tol: Option<f64>,
+    maxiter: Option<usize>,
+) -> OptimizeResult {
+    let mut x = start_point(x0, "nr_minimize");
+    let n = x.len();
+    if let Some(bounds) = bounds {
+        if method == MinimizeMethod::Bfgs {
+            panic!("nr_minimize: BFGS does not support bounds");
+        }
+        if bounds.len() != n {
+            panic!("nr_minimize: {} bounds for {} variables", bounds.len(), n);
+        }
+        if bounds.iter().any(|&(lo, hi)| lo.is_nan() || hi.is_nan() || lo > hi) {
+            panic!("nr_minimize: every lower bound must not exceed its upper bound");
+        }
+    }
+    if let Some(tol) = tol {
+        if tol.is_nan() || tol < 0.0 {
+            panic!("nr_minimize: tol must be non-negative, got {}", tol);
+        }
+    }
+
+    let obj = Objective { fun: &fun, jac, bounds, nfev: Cell::new(0), njev: Cell::new(0) };
+    obj.clip(&mut x);
+    match method {
+        MinimizeMethod::NelderMead => {
+            // An explicit maxiter lifts the cap on function evaluations
+            let tol = tol.unwrap_or(1e-4);
+            let maxfev = if maxiter.is_some() { usize::MAX } else { 200 * n };
+            nelder_mead(&obj, x, tol, tol, maxiter.unwrap_or(200 * n), maxfev)
+        }
+        MinimizeMethod::Bfgs => bfgs(&obj, x, tol.unwrap_or(1e-5), maxiter.unwrap_or(200 * n)),
+        MinimizeMethod::LBfgsB => {
+            let unbounded = vec![(f64::NEG_INFINITY, f64::INFINITY); n];
+            let bounds = bounds.unwrap_or(&unbounded);
+            let ftol = tol.unwrap_or(2.220446049250313e-9);
+            let gtol = tol.unwrap_or(1e-5);
+            lbfgsb::lbfgsb(&obj, x, bounds, ftol, gtol, maxiter.unwrap_or(15000), 15000)
+        }
+    }
+}
+
+// Accepted point of a line search
+pub(super) struct Step {
+    pub(super) x: Vec<f64>,
+    pub(super) f: f64,
+    pub(super) g: Vec<f64>,
+}
+
+// Step along the descent direction d from x satisfying the strong Wolfe
+// conditions with c1 = 1e-4, c2 = 0.9 (Nocedal & Wright, algorithms 3.5 and
+// 3.6), trying alpha1 first. Steps never exceed amax; reaching it with a
+// sufficient decrease is accepted even without the curvature condition.
+pub(super) fn wolfe_search(obj: &Objective, x: &[f64], d: &[f64], f0: f64, g0: &[f64], alpha1: f64, amax: f64) -> Option<Step> {
+    const C1: f64 = 1e-4;
+    const C2: f64 = 0.9;
+    let dphi0 = dot(g0, d);
+    if dphi0.is_nan() || dphi0 >= 0.0 {
+        return None;
+    }
+    let eval = |alpha: f64| {
+        let mut xa: Vec<f64> = x.iter().zip(d).map(|(x, d)| x + alpha * d).collect();
+        obj.clip(&mut xa);
+        let f = obj.value(&xa);
+        let g = obj.grad(&xa, f);
+        let dphi = dot(&g, d);
+        (Step { x: xa, f, g }, dphi)
+    };
+    let armijo = |alpha: f64, f: f64| f <= f0 + C1 * alpha * dphi0;
+
+    // Bracket an acceptable step between lo and hi, then shrink the bracket
+    let zoom = |mut lo: f64, mut hi: f64, mut f_lo: f64, mut dphi_lo: f64, mut f_hi: f64| -> Option<Step> {
+        for _ in 0..30 {
+            let delta = hi - lo;
+            if delta.abs() <= 1e-16 * lo.abs().max(1.0) {
+                return None;
+            }
+            // Minimizer of the quadratic through f_lo, dphi_lo and f_hi,
+            // bisection when it falls too close to either end
+            let denom = 2.0 * (f_hi - f_lo - dphi_lo * delta);
+            let mut alpha = lo - dphi_lo * delta * delta / denom;
+            let (a, b) = (lo.min(hi), lo.max(hi));
+            let margin = 0.1 * delta.abs();
+            if !(alpha > a + margin && alpha < b - margin) {
+                alpha = lo + 0.5 * delta;
+            }
+            let (step, dphi) = eval(alpha);
+            if !armijo(alpha, step.f) || step.f >= f_lo {
+                hi = alpha;
+                f_hi = step.f;
+            } else {
+                if dphi.abs() <= -C2 * dphi0 {
+                    return Some(step);
+                }
+                if dphi * (hi - lo) >= 0.0 {
+                    hi = lo;
+                    f_hi = f_lo;
+                }
+                lo = alpha;
+                f_lo = step.f;
+                dphi_lo = dphi;
+            }
+        }
+        None
+    };
+
+    let (mut alpha_prev, mut f_prev, mut dphi_prev) = (0.0, f0, dphi0);
+    let mut alpha = alpha1.min(amax);
+    for i in 0..20 {
+        let (step, dphi) = eval(alpha);
+        if !step.f.is_finite() {
+            // Overshot into a region where the objective is undefined
+            alpha = alpha_prev + 0.1 * (alpha - alpha_prev);
+            continue;
+        }
+        if !armijo(alpha, step.f) || (i > 0 && step.f >= f_prev) {
+            return zoom(alpha_prev, alpha, f_prev, dphi_prev, step.f);
+        }
+        if dphi.abs() <= -C2 * dphi0 {
+            return Some(step);
+        }
+        if dphi >= 0.0 {
+            return zoom(alpha, alpha_prev, step.f, dphi, f_prev);
+        }
+        if alpha >= amax {
+            return Some(step);
+        }
+        alpha_prev = alpha;
+        f_prev = step.f;
+        dphi_prev = dphi;
+        alpha = (2.0 * alpha).min(amax);
+    }
+    None
+}
+
+// Nelder-Mead simplex with scipy's coefficients and initial simplex (5%
+// steps along each axis, 0.00025 for zero components)
+fn nelder_mead(obj: &Objective, x0: Vec<f64>, xatol: f64, fatol: f64, maxiter: usize, maxfev: usize) -> OptimizeResult {
+    let (rho, chi, psi, sigma) = (1.0, 2.0, 0.5, 0.5);
+    let n = x0.len();
+    let point = |mut x: Vec<f64>| {
+        obj.clip(&mut x);
+        x
+    };
+    let mut sim = vec![x0.clone()];
+    for k in 0..n {
+        let mut y = x0.clone();
+        y[k] = if y[k] != 0.0 { 1.05 * y[k] } else { 0.00025 };
+        sim.push(point(y));
+    }
+    let mut fsim: Vec<f64> = sim.iter().map(|x| obj.value(x)).collect();
+    let sort = |sim: &mut Vec<Vec<f64>>, fsim: &mut Vec<f64>| {
+        let mut order: Vec<usize> = (0..=n).collect();
+        order.sort_by(|&a, &b| fsim[a].total_cmp(&fsim[b]));
+        *sim = order.iter().map(|&i| sim[i].clone()).collect();
+        *fsim = order.iter().map(|&i| fsim[i]).collect();
+    };
+    sort(&mut sim, &mut fsim);
+
+    // Affine combination (1 + t) xbar - t worst
+    let along = |xbar: &[f64], worst: &[f64], t: f64| point(xbar.iter().zip(worst).map(|(b, w)| (1.0 + t) * b - t * w).collect());
+
+    let mut nit = 0;
+    while obj.nfev.get() < maxfev && nit < maxiter {
+        let xspread = sim[1..].iter().flat_map(|x| x.iter().zip(&sim[0]).map(|(a, b)| (a - b).abs())).fold(0.0, f64::max);
+        let fspread = fsim[1..].iter().map(|f| (f - fsim[0]).abs()).fold(0.0, f64::max);
+        if xspread <= xatol && fspread <= fatol {
+            break;
+        }
+
+        let mut xbar = vec![0.0; n];
+        for x in &sim[..n] {
+            for (b, x) in xbar.iter_mut().zip(x) {
+                *b += x / n as f64;
+            }
+        }
+        let worst = sim[n].clone();
+        let xr = along(&xbar, &worst, rho);
+        let fxr = obj.value(&xr);
+        let mut shrink = false;
+        if fxr < fsim[0] {
+            let xe = along(&xbar, &worst, rho * chi);
+            let fxe = obj.value(&xe);
+            if fxe < fxr {
+                sim[n] = xe;
+                fsim[n] = fxe;
+            } else {
+                sim[n] = xr;
+                fsim[n] = fxr;
+            }
+        } else if fxr < fsim[n - 1] {
+            sim[n] = xr;
+            fsim[n] = fxr;
+        } else if fxr < fsim[n] {
+            // Outside contraction
+            let xc = along(&xbar, &worst, psi * rho);
+            let fxc = obj.value(&xc);
+            if fxc <= fxr {
+                sim[n] = xc;
+                fsim[n] = fxc;
+            } else {
+                shrink = true;
+            }
+        } else {
+            // Inside contraction
+            let xcc = along(&xbar, &worst, -psi);
+            let fxcc = obj.value(&xcc);
+            if fxcc < fsim[n] {
+                sim[n] = xcc;
+                fsim[n] = fxcc;
+            } else {
+                shrink = true;
+            }
+        }
+        if shrink {
+            for j in 1..=n {
+                let x = point(sim[0].iter().zip(&sim[j]).map(|(b, x)| b + sigma * (x - b)).collect());
+                fsim[j] = obj.value(&x);
+                sim[j] = x;
+            }
+        }
+        nit += 1;
+        sort(&mut sim, &mut fsim);
+    }
+
+    let (success, message) = if obj.nfev.get() >= maxfev {
+        (false, "Maximum number of function evaluations has been exceeded.")
+    } else if nit >= maxiter {
+        (false, "Maximum number of iterations has been exceeded.")
+    } else {
+        (true, "Optimization terminated successfully.")
+    };
+    let f = fsim[0];
+    obj.result(sim.swap_remove(0), f, None, nit, success, message)
+}
+
+// BFGS with a dense inverse Hessian approximation, started from the
+// identity, until the largest gradient component is at most gtol
+fn bfgs(obj: &Objective, mut x: Vec<f64>, gtol: f64, maxiter: usize) -> OptimizeResult {
+    let n = x.len();
+    let mut f = obj.value(&x);
+    let mut g = obj.grad(&x, f);
+    let mut h = vec![0.0; n * n];
+    for i in 0..n {
+        h[i * n + i] = 1.0;
+    }
+    let gnorm = |g: &[f64]| g.iter().fold(0.0, |m: f64, v| m.max(v.abs()));
+    // Pretend previous value that makes the first trial step 1 / ||g||
+    let mut f_old = f + dot(&g, &g).sqrt() / 2.0;
+
+    let mut nit = 0;
+    let mut message = "Optimization terminated successfully.";
+    let mut success = true;
+    while gnorm(&g) > gtol {
+        if nit >= maxiter {
+            success = false;
+            message = "Maximum number of iterations has been exceeded.";
+            break;
+        }
+        let p: Vec<f64> = h.chunks(n).map(|row| -dot(row, &g)).collect();
+        let dphi0 = dot(&g, &p);
+        let mut alpha1 = 1.0;
+        if dphi0 != 0.0 {
+            alpha1 = (1.01 * 2.0 * (f - f_old) / dphi0).min(1.0);
+            if alpha1 < 0.0 {
+                alpha1 = 1.0;
+            }
+        }
+        let Some(step) = wolfe_search(obj, &x, &p, f, &g, alpha1, f64::INFINITY) else {
+            success = false;
+            message = "Desired error not necessarily achieved due to precision loss.";
+            break;
+        };
+        nit += 1;
+        let s: Vec<f64> = step.x.iter().zip(&x).map(|(a, b)| a - b).collect();
+        let y: Vec<f64> = step.g.iter().zip(&g).map(|(a, b)| a - b).collect();
+        f_old = f;
+        x = step.x;
+        f = step.f;
+        g = step.g;
+
+        // H <- (I - rho s y') H (I - rho y s') + rho s s'
+        let ys = dot(&y, &s);
+        let rho = if ys == 0.0 { 1000.0 } else { 1.0 / ys };
+        let hy: Vec<f64> = h.chunks(n).map(|row| dot(row, &y)).collect();
+        let yhy = dot(&y, &hy);
+        let ss_coef = rho * rho * yhy + rho;
+        for i in 0..n {
+            for j in 0..n {
+                h[i * n + j] += ss_coef * s[i] * s[j] - rho * (hy[i] * s[j] + s[i] * hy[j]);
+            }
+        }
+    }
+    if !f.is_finite() {
+        success = false;
+        message = "NaN result encountered.";
+    }
+    obj.result(x, f, Some(g), nit, success, message)
+}
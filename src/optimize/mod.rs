@@ -0,0 +1,57 @@
+// Root finding, minimization and nonlinear least squares, mirroring
+// scipy.optimize.
+//
+// Scalar root finders work on f64 functions. The multivariate routines take
+// the unknowns as a 1-D f64 array; objectives return an f64 (nr_minimize)
+// or an array of residuals (nr_least_squares), and user-supplied gradients
+// and Jacobians return arrays. Missing derivatives are estimated by forward
+// differences.
+mod lbfgsb;
+mod least_squares;
+mod minimize;
+mod root;
+
+pub use least_squares::{nr_curve_fit, nr_least_squares, LeastSquaresResult};
+pub use minimize::{nr_minimize, MinimizeMethod, OptimizeResult};
+pub use root::{nr_brentq, nr_newton, RootResult};
+
+use crate::array::Array;
+
+// Gradient or Jacobian supplied by the caller
+type ArrayFn<'a> = dyn Fn(&Array<f64>) -> Array<f64> + 'a;
+
+fn to_array(x: &[f64]) -> Array<f64> {
+    Array::from_vec(x.to_vec(), &[x.len() as i32])
+}
+
+// Unknowns from a 1-D starting point
+fn start_point(x0: &Array<f64>, name: &str) -> Vec<f64> {
+    if x0.ndim != 1 {
+        panic!("{}: x0 must be 1-D, got shape {:?}", name, x0.shape);
+    }
+    x0.data.clone()
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+// Forward-difference step for x inside [lower, upper] (scipy's
+// approx_derivative): turned around when it would leave the bounds, and
+// shrunk to the wider side when it fits neither way; zero for a variable
+// fixed by lower == upper
+fn fd_step(x: f64, lower: f64, upper: f64) -> f64 {
+    let h = f64::EPSILON.sqrt() * x.abs().max(1.0);
+    let h = if x >= 0.0 { h } else { -h };
+    if x + h >= lower && x + h <= upper {
+        return h;
+    }
+    let (below, above) = (x - lower, upper - x);
+    if h.abs() <= below.max(above) {
+        -h
+    } else if above >= below {
+        above
+    } else {
+        -below
+    }
+}
@@ -0,0 +1,176 @@
+// Outcome of the scalar root finders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootResult {
+    pub root: f64,
+    pub iterations: usize,
+    pub function_calls: usize,
+    // Whether the tolerance was met; false after maxiter iterations or a
+    // zero derivative in Newton's method
+    pub converged: bool,
+}
+
+// Zero of f in the bracket [a, b] by Brent's method (scipy.optimize.brentq):
+// inverse quadratic interpolation and secant steps, falling back to
+// bisection whenever they would not shrink the bracket fast enough. f(a) and
+// f(b) must differ in sign. Stops once the bracket is narrower than
+// xtol + rtol * |root| (defaults 2e-12 and 4 eps) or after maxiter
+// iterations (default 100).
+pub fn nr_brentq<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, xtol: Option<f64>, rtol: Option<f64>, maxiter: Option<usize>) -> RootResult {
+    let xtol = xtol.unwrap_or(2e-12);
+    let rtol = rtol.unwrap_or(4.0 * f64::EPSILON);
+    let maxiter = maxiter.unwrap_or(100);
+    if xtol.is_nan() || xtol <= 0.0 {
+        panic!("nr_brentq: xtol must be positive, got {}", xtol);
+    }
+    if rtol.is_nan() || rtol < 4.0 * f64::EPSILON {
+        panic!("nr_brentq: rtol must be at least 4 eps, got {}", rtol);
+    }
+
+    // xcur is the best estimate, xblk the other end of the bracket and xpre
+    // the previous estimate; scur and spre are the last two step sizes
+    let (mut xpre, mut xcur) = (a, b);
+    let (mut fpre, mut fcur) = (f(xpre), f(xcur));
+    let mut result = RootResult { root: xcur, iterations: 0, function_calls: 2, converged: true };
+    if fpre * fcur > 0.0 {
+        panic!("nr_brentq: f(a) and f(b) must have different signs");
+    }
+    if fpre == 0.0 {
+        result.root = xpre;
+        return result;
+    }
+    if fcur == 0.0 {
+        return result;
+    }
+
+    let (mut xblk, mut fblk) = (0.0, 0.0);
+    let (mut spre, mut scur) = (0.0, 0.0);
+    while result.iterations < maxiter {
+        result.iterations += 1;
+        if fpre != 0.0 && fcur != 0.0 && fpre.is_sign_negative() != fcur.is_sign_negative() {
+            xblk = xpre;
+            fblk = fpre;
+            spre = xcur - xpre;
+            scur = spre;
+        }
+        if fblk.abs() < fcur.abs() {
+            xpre = xcur;
+            xcur = xblk;
+            xblk = xpre;
+            fpre = fcur;
+            fcur = fblk;
+            fblk = fpre;
+        }
+
+        let delta = 0.5 * (xtol + rtol * xcur.abs());
+        let sbis = 0.5 * (xblk - xcur);
+        if fcur == 0.0 || sbis.abs() < delta {
+            result.root = xcur;
+            return result;
+        }
+
+        if spre.abs() > delta && fcur.abs() < fpre.abs() {
+            let stry = if xpre == xblk {
+                // Secant through the two bracket ends
+                -fcur * (xcur - xpre) / (fcur - fpre)
+            } else {
+                // Inverse quadratic interpolation through all three points
+                let dpre = (fpre - fcur) / (xpre - xcur);
+                let dblk = (fblk - fcur) / (xblk - xcur);
+                -fcur * (fblk * dblk - fpre * dpre) / (dblk * dpre * (fblk - fpre))
+            };
+            if 2.0 * stry.abs() < spre.abs().min(3.0 * sbis.abs() - delta) {
+                spre = scur;
+                scur = stry;
+            } else {
+                spre = sbis;
+                scur = sbis;
+            }
+        } else {
+            spre = sbis;
+            scur = sbis;
+        }
+
+        xpre = xcur;
+        fpre = fcur;
+        xcur += if scur.abs() > delta { scur } else if sbis > 0.0 { delta } else { -delta };
+        fcur = f(xcur);
+        result.function_calls += 1;
+    }
+    result.root = xcur;
+    result.converged = false;
+    result
+}
+
+// Zero of f near x0 (scipy.optimize.newton): Newton's method when the
+// derivative fprime is given, the secant method otherwise. Stops once a
+// step is smaller than tol (default 1.48e-8) or after maxiter iterations
+// (default 50).
+pub fn nr_newton<F: Fn(f64) -> f64>(f: F, x0: f64, fprime: Option<&dyn Fn(f64) -> f64>, tol: Option<f64>, maxiter: Option<usize>) -> RootResult {
+    let tol = tol.unwrap_or(1.48e-8);
+    let maxiter = maxiter.unwrap_or(50);
+    if tol.is_nan() || tol <= 0.0 {
+        panic!("nr_newton: tol must be positive, got {}", tol);
+    }
+    let mut result = RootResult { root: x0, iterations: 0, function_calls: 0, converged: false };
+
+    if let Some(fprime) = fprime {
+        let mut p0 = x0;
+        while result.iterations < maxiter {
+            result.iterations += 1;
+            let fval = f(p0);
+            result.function_calls += 1;
+            if fval == 0.0 {
+                result.root = p0;
+                result.converged = true;
+                return result;
+            }
+            let fder = fprime(p0);
+            result.function_calls += 1;
+            if fder == 0.0 {
+                result.root = p0;
+                return result;
+            }
+            let p = p0 - fval / fder;
+            if (p - p0).abs() <= tol {
+                result.root = p;
+                result.converged = true;
+                return result;
+            }
+            p0 = p;
+        }
+        result.root = p0;
+        return result;
+    }
+
+    // Secant method started from x0 and a point slightly beside it
+    let mut p0 = x0;
+    let mut p1 = x0 * (1.0 + 1e-4);
+    p1 += if p1 >= 0.0 { 1e-4 } else { -1e-4 };
+    let (mut q0, mut q1) = (f(p0), f(p1));
+    result.function_calls = 2;
+    if q1.abs() < q0.abs() {
+        std::mem::swap(&mut p0, &mut p1);
+        std::mem::swap(&mut q0, &mut q1);
+    }
+    while result.iterations < maxiter {
+        result.iterations += 1;
+        if q1 == q0 {
+            // Flat secant: no further progress is possible
+            result.root = 0.5 * (p0 + p1);
+            return result;
+        }
+        let p = if q1.abs() > q0.abs() { (-q0 / q1 * p1 + p0) / (1.0 - q0 / q1) } else { (-q1 / q0 * p0 + p1) / (1.0 - q1 / q0) };
+        if (p - p1).abs() <= tol {
+            result.root = p;
+            result.converged = true;
+            return result;
+        }
+        p0 = p1;
+        q0 = q1;
+        p1 = p;
+        q1 = f(p1);
+        result.function_calls += 1;
+    }
+    result.root = p1;
+    result
+}
@@ -0,0 +1,306 @@
+// Polynomials (numpy.polynomial and the legacy np.poly* functions).
+//
+// Polynomial and OrthoSeries store coefficients lowest degree first, like
+// numpy.polynomial. The nr_poly* functions follow the legacy NumPy layout
+// instead: 1-D arrays with the highest degree first.
+mod ortho;
+
+pub use ortho::{Basis, OrthoSeries};
+
+use crate::array::Array;
+use crate::linalg::{nr_eigvals, nr_lstsq, LinalgError};
+use num_complex::Complex;
+use num_traits::Float;
+use std::ops::{Add, Mul, Neg, Sub};
+
+// Power series sum coef[k] x^k. Trailing zero coefficients are trimmed, so
+// coef always has at least one entry and coef.len() - 1 is the degree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<T> {
+    pub coef: Vec<T>,
+}
+
+fn trim<T: Float>(mut coef: Vec<T>) -> Vec<T> {
+    while coef.len() > 1 && coef[coef.len() - 1] == T::zero() {
+        coef.pop();
+    }
+    if coef.is_empty() {
+        coef.push(T::zero());
+    }
+    coef
+}
+
+impl<T: Float> Polynomial<T> {
+    pub fn new(coef: Vec<T>) -> Polynomial<T> {
+        Polynomial { coef: trim(coef) }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.coef.len() - 1
+    }
+
+    // Value at x by Horner's rule
+    pub fn eval(&self, x: T) -> T {
+        self.coef.iter().rev().fold(T::zero(), |acc, &c| acc * x + c)
+    }
+
+    // Value at every element of x
+    pub fn eval_array(&self, x: &Array<T>) -> Array<T> {
+        Array::from_vec(x.data.iter().map(|&v| self.eval(v)).collect(), &x.shape)
+    }
+
+    // m-th derivative
+    pub fn deriv(&self, m: usize) -> Polynomial<T> {
+        let mut coef = self.coef.clone();
+        for _ in 0..m {
+            if coef.len() == 1 {
+                return Polynomial::new(vec![T::zero()]);
+            }
+            coef = coef.iter().enumerate().skip(1).map(|(k, &c)| c * T::from(k).unwrap()).collect();
+        }
+        Polynomial::new(coef)
+    }
+
+    // m-th antiderivative. The i-th integration adds the constant k[i]
+    // (zero when k is shorter), so that pass is worth k[i] at x = 0.
+    pub fn integ(&self, m: usize, k: &[T]) -> Polynomial<T> {
+        let mut coef = self.coef.clone();
+        for i in 0..m {
+            let mut next = Vec::with_capacity(coef.len() + 1);
+            next.push(k.get(i).copied().unwrap_or_else(T::zero));
+            next.extend(coef.iter().enumerate().map(|(j, &c)| c / T::from(j + 1).unwrap()));
+            coef = next;
+        }
+        Polynomial::new(coef)
+    }
+
+    // Quotient and remainder of polynomial long division by `divisor`
+    pub fn divrem(&self, divisor: &Polynomial<T>) -> (Polynomial<T>, Polynomial<T>) {
+        let d = divisor.degree();
+        let lead = divisor.coef[d];
+        if lead == T::zero() {
+            panic!("polynomial division by zero");
+        }
+        if self.degree() < d {
+            return (Polynomial::new(vec![T::zero()]), self.clone());
+        }
+        let mut rem = self.coef.clone();
+        let mut quot = vec![T::zero(); self.degree() - d + 1];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + d] / lead;
+            quot[i] = q;
+            for (j, &c) in divisor.coef.iter().enumerate() {
+                rem[i + j] = rem[i + j] - q * c;
+            }
+        }
+        // The low d coefficients are what is left; a constant divisor
+        // leaves nothing
+        if d == 0 {
+            rem = vec![T::zero()];
+        } else {
+            rem.truncate(d);
+        }
+        (Polynomial::new(quot), Polynomial::new(rem))
+    }
+
+    // Roots as eigenvalues of the companion matrix. Zero roots are split
+    // off exactly first; a constant has no roots.
+    pub fn roots(&self) -> Result<Vec<Complex<T>>, LinalgError> {
+        let zeros = self.coef.iter().take_while(|&&c| c == T::zero()).count().min(self.degree());
+        let coef = &self.coef[zeros..];
+        let n = coef.len() - 1;
+        let mut roots = vec![Complex::new(T::zero(), T::zero()); zeros];
+        if n == 0 {
+            return Ok(roots);
+        }
+        // First row holds -coef[n - 1 - j] / coef[n], ones on the subdiagonal
+        let mut companion = Array::nr_create(&[n as i32, n as i32], 2);
+        for j in 0..n {
+            companion.data[j] = -coef[n - 1 - j] / coef[n];
+        }
+        for i in 1..n {
+            companion.data[i * n + i - 1] = T::one();
+        }
+        roots.extend(nr_eigvals(&companion)?.data);
+        Ok(roots)
+    }
+
+    // Least-squares fit of degree deg to the points (x, y), each residual
+    // scaled by the matching weight when given
+    pub fn fit(x: &Array<T>, y: &Array<T>, deg: usize, weights: Option<&Array<T>>) -> Result<Polynomial<T>, LinalgError> {
+        if y.ndim != 1 {
+            return Err(LinalgError::InvalidShape(format!("polyfit: expected 1-D y, got shape {:?}", y.shape)));
+        }
+        let coef = fit_columns("polyfit", x, y, deg, weights, |v, d| power_vander(v, d))?;
+        Ok(Polynomial::new(coef.data))
+    }
+}
+
+// Rows [1, x, x^2, ..., x^deg] for every x
+fn power_vander<T: Float>(x: &[T], deg: usize) -> Vec<T> {
+    let mut v = Vec::with_capacity(x.len() * (deg + 1));
+    for &xi in x {
+        let mut p = T::one();
+        for _ in 0..=deg {
+            v.push(p);
+            p = p * xi;
+        }
+    }
+    v
+}
+
+// Weighted least-squares coefficients (lowest degree first) for the
+// pseudo-Vandermonde matrix built by `vander`. y is 1-D or holds one data
+// set per column; the result has deg + 1 rows to match. name prefixes the
+// error messages.
+pub(crate) fn fit_columns<T: Float>(
+    name: &str,
+    x: &Array<T>,
+    y: &Array<T>,
+    deg: usize,
+    weights: Option<&Array<T>>,
+    vander: impl Fn(&[T], usize) -> Vec<T>,
+) -> Result<Array<T>, LinalgError> {
+    if x.ndim != 1 || y.ndim > 2 || y.shape[0] != x.shape[0] {
+        return Err(LinalgError::InvalidShape(format!(
+            "{}: x must be 1-D and y must have the same leading length, got {:?} and {:?}",
+            name, x.shape, y.shape
+        )));
+    }
+    let n = x.data.len();
+    if n == 0 {
+        return Err(LinalgError::InvalidShape(format!("{}: x must not be empty", name)));
+    }
+    let cols = deg + 1;
+    let k = y.totalsize / n;
+    let mut lhs = vander(&x.data, deg);
+    let mut rhs = y.data.clone();
+    if let Some(w) = weights {
+        if w.ndim != 1 || w.data.len() != n {
+            return Err(LinalgError::InvalidShape(format!("{}: weights must have shape [{}], got {:?}", name, n, w.shape)));
+        }
+        for (i, &wi) in w.data.iter().enumerate() {
+            lhs[i * cols..(i + 1) * cols].iter_mut().for_each(|v| *v = *v * wi);
+            rhs[i * k..(i + 1) * k].iter_mut().for_each(|v| *v = *v * wi);
+        }
+    }
+
+    // Unit-norm columns keep high powers from swamping the conditioning
+    let scale: Vec<T> = (0..cols)
+        .map(|j| {
+            let s = (0..n).fold(T::zero(), |acc, i| acc + lhs[i * cols + j] * lhs[i * cols + j]).sqrt();
+            if s == T::zero() { T::one() } else { s }
+        })
+        .collect();
+    for row in lhs.chunks_mut(cols) {
+        for (v, &s) in row.iter_mut().zip(&scale) {
+            *v = *v / s;
+        }
+    }
+
+    let a = Array::from_vec(lhs, &[n as i32, cols as i32]);
+    let b = Array::from_vec(rhs, &y.shape);
+    let mut coef = nr_lstsq(&a, &b, None)?.solution;
+    for (i, v) in coef.data.iter_mut().enumerate() {
+        *v = *v / scale[i / k];
+    }
+    Ok(coef)
+}
+
+impl<T: Float> Add for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn add(self, other: &Polynomial<T>) -> Polynomial<T> {
+        let n = self.coef.len().max(other.coef.len());
+        let get = |c: &[T], i: usize| c.get(i).copied().unwrap_or_else(T::zero);
+        Polynomial::new((0..n).map(|i| get(&self.coef, i) + get(&other.coef, i)).collect())
+    }
+}
+
+impl<T: Float> Neg for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn neg(self) -> Polynomial<T> {
+        Polynomial::new(self.coef.iter().map(|&c| -c).collect())
+    }
+}
+
+impl<T: Float> Sub for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn sub(self, other: &Polynomial<T>) -> Polynomial<T> {
+        self + &(-other)
+    }
+}
+
+impl<T: Float> Mul for &Polynomial<T> {
+    type Output = Polynomial<T>;
+
+    fn mul(self, other: &Polynomial<T>) -> Polynomial<T> {
+        let mut coef = vec![T::zero(); self.coef.len() + other.coef.len() - 1];
+        for (i, &a) in self.coef.iter().enumerate() {
+            for (j, &b) in other.coef.iter().enumerate() {
+                coef[i + j] = coef[i + j] + a * b;
+            }
+        }
+        Polynomial::new(coef)
+    }
+}
+
+// Polynomial from a 1-D array of coefficients, highest degree first
+fn from_highest<T: Float>(p: &Array<T>, name: &str) -> Polynomial<T> {
+    if p.ndim != 1 {
+        panic!("{}: expected 1-D coefficients, got shape {:?}", name, p.shape);
+    }
+    Polynomial::new(p.data.iter().rev().copied().collect())
+}
+
+fn to_highest<T: Float>(p: &Polynomial<T>) -> Array<T> {
+    Array::from_vec(p.coef.iter().rev().copied().collect(), &[p.coef.len() as i32])
+}
+
+// Evaluate the polynomial p (highest degree first) at every element of x
+// (np.polyval)
+pub fn nr_polyval<T: Float>(p: &Array<T>, x: &Array<T>) -> Array<T> {
+    from_highest(p, "polyval").eval_array(x)
+}
+
+// Least-squares polynomial fit of degree deg, highest degree first
+// (np.polyfit). y may hold one data set per column, giving one column of
+// coefficients each. weights multiply the residuals, so 1 / sigma weights
+// points by their uncertainty.
+pub fn nr_polyfit<T: Float>(x: &Array<T>, y: &Array<T>, deg: usize, weights: Option<&Array<T>>) -> Result<Array<T>, LinalgError> {
+    let coef = fit_columns("polyfit", x, y, deg, weights, |v, d| power_vander(v, d))?;
+    // Reverse the row order to put the highest degree first
+    let k = coef.totalsize / (deg + 1);
+    let data = coef.data.chunks(k).rev().flatten().copied().collect();
+    Ok(Array::from_vec(data, &coef.shape))
+}
+
+// Roots of the polynomial p, highest degree first (np.roots). Returned as
+// a Vec since a constant polynomial has none and arrays cannot be empty.
+pub fn nr_roots<T: Float>(p: &Array<T>) -> Result<Vec<Complex<T>>, LinalgError> {
+    from_highest(p, "roots").roots()
+}
+
+// m-th derivative of p (np.polyder)
+pub fn nr_polyder<T: Float>(p: &Array<T>, m: usize) -> Array<T> {
+    to_highest(&from_highest(p, "polyder").deriv(m))
+}
+
+// m-th antiderivative of p with integration constants k, the first used by
+// the first integration (np.polyint)
+pub fn nr_polyint<T: Float>(p: &Array<T>, m: usize, k: Option<&[T]>) -> Array<T> {
+    to_highest(&from_highest(p, "polyint").integ(m, k.unwrap_or(&[])))
+}
+
+// Product of two polynomials (np.polymul)
+pub fn nr_polymul<T: Float>(a: &Array<T>, b: &Array<T>) -> Array<T> {
+    to_highest(&(&from_highest(a, "polymul") * &from_highest(b, "polymul")))
+}
+
+// Quotient and remainder of u / v (np.polydiv)
+pub fn nr_polydiv<T: Float>(u: &Array<T>, v: &Array<T>) -> (Array<T>, Array<T>) {
+    let (q, r) = from_highest(u, "polydiv").divrem(&from_highest(v, "polydiv"));
+    (to_highest(&q), to_highest(&r))
+}
@@ -0,0 +1,141 @@
+use super::{fit_columns, Polynomial};
+use crate::array::Array;
+use crate::linalg::LinalgError;
+use num_traits::Float;
+
+// Classical orthogonal polynomial families
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Basis {
+    // T_n, orthogonal on [-1, 1] with weight 1 / sqrt(1 - x^2)
+    Chebyshev,
+    // P_n, orthogonal on [-1, 1] with weight 1
+    Legendre,
+    // Physicists' H_n, orthogonal on the real line with weight exp(-x^2)
+    Hermite,
+}
+
+impl Basis {
+    // Recurrence P_{n+1} = a_n x P_n - c_n P_{n-1}, with P_0 = 1, P_{-1} = 0
+    fn recurrence<T: Float>(self, n: usize) -> (T, T) {
+        let f = |v: usize| T::from(v).unwrap();
+        match self {
+            Basis::Chebyshev => (if n == 0 { T::one() } else { f(2) }, T::one()),
+            Basis::Legendre => (f(2 * n + 1) / f(n + 1), f(n) / f(n + 1)),
+            Basis::Hermite => (f(2), f(2 * n)),
+        }
+    }
+
+    // Rows [P_0(x), ..., P_deg(x)] for every x
+    fn vander<T: Float>(self, x: &[T], deg: usize) -> Vec<T> {
+        let mut v = Vec::with_capacity(x.len() * (deg + 1));
+        for &xi in x {
+            let (mut prev, mut cur) = (T::zero(), T::one());
+            for n in 0..=deg {
+                v.push(cur);
+                let (a, c) = self.recurrence::<T>(n);
+                let next = a * xi * cur - c * prev;
+                prev = cur;
+                cur = next;
+            }
+        }
+        v
+    }
+
+    // numpy's name for a least-squares fit in this basis
+    fn fit_name(self) -> &'static str {
+        match self {
+            Basis::Chebyshev => "chebfit",
+            Basis::Legendre => "legfit",
+            Basis::Hermite => "hermfit",
+        }
+    }
+
+    // P_0, ..., P_deg in the power basis
+    fn power_forms<T: Float>(self, deg: usize) -> Vec<Polynomial<T>> {
+        let x = Polynomial::new(vec![T::zero(), T::one()]);
+        let mut forms = vec![Polynomial::new(vec![T::one()])];
+        let mut prev = Polynomial::new(vec![T::zero()]);
+        for n in 0..deg {
+            let (a, c) = self.recurrence::<T>(n);
+            let scaled = |p: &Polynomial<T>, s: T| Polynomial::new(p.coef.iter().map(|&v| v * s).collect());
+            let next = &scaled(&(&x * &forms[n]), a) - &scaled(&prev, c);
+            prev = forms[n].clone();
+            forms.push(next);
+        }
+        forms
+    }
+}
+
+// Series sum coef[k] P_k(x) in one of the orthogonal bases
+// (numpy.polynomial.Chebyshev, Legendre and Hermite)
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrthoSeries<T> {
+    pub basis: Basis,
+    pub coef: Vec<T>,
+}
+
+impl<T: Float> OrthoSeries<T> {
+    pub fn new(basis: Basis, coef: Vec<T>) -> OrthoSeries<T> {
+        if coef.is_empty() {
+            panic!("OrthoSeries: at least one coefficient is required");
+        }
+        OrthoSeries { basis, coef }
+    }
+
+    // Value at x by Clenshaw's recurrence
+    pub fn eval(&self, x: T) -> T {
+        let n = self.coef.len();
+        let (mut b1, mut b2) = (T::zero(), T::zero());
+        for k in (0..n).rev() {
+            let (a, _) = self.basis.recurrence::<T>(k);
+            let (_, c) = self.basis.recurrence::<T>(k + 1);
+            let b = self.coef[k] + a * x * b1 - c * b2;
+            b2 = b1;
+            b1 = b;
+        }
+        b1
+    }
+
+    // Value at every element of x
+    pub fn eval_array(&self, x: &Array<T>) -> Array<T> {
+        Array::from_vec(x.data.iter().map(|&v| self.eval(v)).collect(), &x.shape)
+    }
+
+    // Least-squares fit with basis polynomials up to degree deg
+    // (numpy.polynomial.chebyshev.chebfit and friends)
+    pub fn fit(basis: Basis, x: &Array<T>, y: &Array<T>, deg: usize, weights: Option<&Array<T>>) -> Result<OrthoSeries<T>, LinalgError> {
+        if y.ndim != 1 {
+            return Err(LinalgError::InvalidShape(format!("{}: expected 1-D y, got shape {:?}", basis.fit_name(), y.shape)));
+        }
+        let coef = fit_columns(basis.fit_name(), x, y, deg, weights, |v, d| basis.vander(v, d))?;
+        Ok(OrthoSeries::new(basis, coef.data))
+    }
+
+    // The same function in the power basis (cheb2poly and friends)
+    pub fn to_polynomial(&self) -> Polynomial<T> {
+        let forms = self.basis.power_forms::<T>(self.coef.len() - 1);
+        forms
+            .iter()
+            .zip(&self.coef)
+            .fold(Polynomial::new(vec![T::zero()]), |acc, (p, &c)| {
+                &acc + &Polynomial::new(p.coef.iter().map(|&v| v * c).collect())
+            })
+    }
+
+    // A power series rewritten in `basis` (poly2cheb and friends)
+    pub fn from_polynomial(basis: Basis, p: &Polynomial<T>) -> OrthoSeries<T> {
+        let deg = p.degree();
+        let forms = basis.power_forms::<T>(deg);
+        // P_k has degree k, so peel off the top power one basis term at a time
+        let mut rest = p.coef.clone();
+        let mut coef = vec![T::zero(); deg + 1];
+        for k in (0..=deg).rev() {
+            let c = rest[k] / forms[k].coef[k];
+            coef[k] = c;
+            for (r, &f) in rest.iter_mut().zip(&forms[k].coef) {
+                *r = *r - c * f;
+            }
+        }
+        OrthoSeries::new(basis, coef)
+    }
+}
@@ -0,0 +1,198 @@
+use crate::array::Array;
+use crate::complex::Conjugate;
+use crate::gemm;
+use crate::ops::{broadcast_array, broadcast_final_shape};
+use num_traits::{Num, Zero};
+
+// Copy of `arr` with its axes reordered, e.g. axes = [1, 0] is a transpose
+pub(crate) fn permute_axes<T: Copy + Zero>(arr: &Array<T>, axes: &[usize]) -> Array<T> {
+    if axes.len() != arr.ndim {
+        panic!("Permutation {:?} does not match array of {} dimensions", axes, arr.ndim);
+    }
+    let mut seen = vec![false; arr.ndim];
+    for &ax in axes {
+        if ax >= arr.ndim || seen[ax] {
+            panic!("Invalid axis permutation {:?}", axes);
+        }
+        seen[ax] = true;
+    }
+
+    let shape: Vec<i32> = axes.iter().map(|&ax| arr.shape[ax]).collect();
+    if axes.iter().enumerate().all(|(i, &ax)| i == ax) {
+        return Array::from_vec(arr.data.clone(), &shape);
+    }
+
+    let strides: Vec<usize> = axes.iter().map(|&ax| arr.strides[ax] as usize / arr.itemsize).collect();
+    let mut out = Vec::with_capacity(arr.totalsize);
+    let mut index = vec![0; arr.ndim];
+    let mut off = 0;
+    for _ in 0..arr.totalsize {
+        out.push(arr.data[off]);
+        // Odometer increment, walking the source with the permuted strides
+        for d in (0..arr.ndim).rev() {
+            index[d] += 1;
+            off += strides[d];
+            if index[d] < shape[d] {
+                break;
+            }
+            off -= strides[d] * shape[d] as usize;
+            index[d] = 0;
+        }
+    }
+    Array::from_vec(out, &shape)
+}
+
+// Sum products over axes_a of `a` and axes_b of `b` (np.tensordot).
+// The result has the free axes of `a` followed by the free axes of `b`,
+// or shape [1] when every axis is contracted.
+pub fn nr_tensordot<T>(a: &Array<T>, b: &Array<T>, axes_a: &[usize], axes_b: &[usize]) -> Array<T>
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    if axes_a.len() != axes_b.len() {
+        panic!("tensordot: axes_a and axes_b must have the same length");
+    }
+    for (&ax, &bx) in axes_a.iter().zip(axes_b) {
+        if ax >= a.ndim || bx >= b.ndim {
+            panic!("tensordot: axis out of bounds");
+        }
+        if a.shape[ax] != b.shape[bx] {
+            panic!(
+                "tensordot: shape mismatch for sum, {} != {} (axes {} and {})",
+                a.shape[ax], b.shape[bx], ax, bx
+            );
+        }
+    }
+
+    let free_a: Vec<usize> = (0..a.ndim).filter(|ax| !axes_a.contains(ax)).collect();
+    let free_b: Vec<usize> = (0..b.ndim).filter(|ax| !axes_b.contains(ax)).collect();
+
+    // Move the contracted axes to the end of a and the front of b,
+    // so the contraction is a single 2-D matrix product
+    let perm_a: Vec<usize> = free_a.iter().chain(axes_a).copied().collect();
+    let perm_b: Vec<usize> = axes_b.iter().chain(&free_b).copied().collect();
+    let a_t = permute_axes(a, &perm_a);
+    let b_t = permute_axes(b, &perm_b);
+
+    let m: usize = free_a.iter().map(|&ax| a.shape[ax] as usize).product();
+    let k: usize = axes_a.iter().map(|&ax| a.shape[ax] as usize).product();
+    let n: usize = free_b.iter().map(|&ax| b.shape[ax] as usize).product();
+
+    let mut out = vec![T::zero(); m * n];
+    gemm::matmul_2d(&a_t.data, &b_t.data, &mut out, m, k, n);
+
+    let mut shape: Vec<i32> = free_a.iter().map(|&ax| a.shape[ax]).collect();
+    shape.extend(free_b.iter().map(|&ax| b.shape[ax]));
+    if shape.is_empty() {
+        shape.push(1);
+    }
+    Array::from_vec(out, &shape)
+}
+
+// Dot product with np.dot semantics: sum over the last axis of `a` and the
+// second-to-last axis of `b` (or its only axis if `b` is 1-D).
+// Two 1-D operands give a result of shape [1].
+pub fn nr_dot<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    let b_axis = if b.ndim == 1 { 0 } else { b.ndim - 2 };
+    if a.shape[a.ndim - 1] != b.shape[b_axis] {
+        panic!(
+            "dot: shapes {:?} and {:?} not aligned ({} != {})",
+            a.shape, b.shape, a.shape[a.ndim - 1], b.shape[b_axis]
+        );
+    }
+    if a.ndim <= 2 && b.ndim <= 2 {
+        return crate::ops::nr_matmul(a, b);
+    }
+    nr_tensordot(a, b, &[a.ndim - 1], &[b_axis])
+}
+
+// Dot product of the flattened arrays, conjugating `a` when complex
+// (np.vdot)
+pub fn nr_vdot<T>(a: &Array<T>, b: &Array<T>) -> T
+where
+    T: Num + Conjugate,
+{
+    if a.totalsize != b.totalsize {
+        panic!("vdot: arrays must have the same number of elements");
+    }
+    a.data.iter().zip(&b.data).fold(T::zero(), |acc, (&x, &y)| acc + x.conjugate() * y)
+}
+
+// Sum products over the last axes of `a` and `b` (np.inner)
+pub fn nr_inner<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
+where
+    T: Num + Copy + Send + Sync + 'static,
+{
+    if a.shape[a.ndim - 1] != b.shape[b.ndim - 1] {
+        panic!(
+            "inner: last dimensions of {:?} and {:?} must match",
+            a.shape, b.shape
+        );
+    }
+    nr_tensordot(a, b, &[a.ndim - 1], &[b.ndim - 1])
+}
+
+// Outer product of the flattened arrays, shape [a.totalsize, b.totalsize]
+pub fn nr_outer<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
+where
+    T: Num + Copy,
+{
+    let mut res = Array::nr_create(&[a.totalsize as i32, b.totalsize as i32], 2);
+    for (row, &x) in res.data.chunks_mut(b.totalsize).zip(&a.data) {
+        for (r, &y) in row.iter_mut().zip(&b.data) {
+            *r = x * y;
+        }
+    }
+    res
+}
+
+// Kronecker product. The operand with fewer dimensions is padded with
+// leading 1s, and each result dimension is the product of the inputs'.
+pub fn nr_kron<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
+where
+    T: Num + Copy,
+{
+    let ndim = a.ndim.max(b.ndim);
+    let mut a_shape = vec![1; ndim];
+    let mut b_shape = vec![1; ndim];
+    a_shape[ndim - a.ndim..].copy_from_slice(&a.shape);
+    b_shape[ndim - b.ndim..].copy_from_slice(&b.shape);
+    let shape: Vec<i32> = a_shape.iter().zip(&b_shape).map(|(x, y)| x * y).collect();
+
+    let mut res = Array::nr_create(&shape, ndim);
+    let idxs = Array::<T>::create_array_indices(&shape, ndim);
+    for (i, nd_index) in idxs.indices.iter().enumerate() {
+        let (mut a_off, mut b_off) = (0, 0);
+        for d in 0..ndim {
+            a_off = a_off * a_shape[d] as usize + (nd_index[d] / b_shape[d]) as usize;
+            b_off = b_off * b_shape[d] as usize + (nd_index[d] % b_shape[d]) as usize;
+        }
+        res.data[i] = a.data[a_off] * b.data[b_off];
+    }
+    res
+}
+
+// Cross product of 3-vectors along the last axis, broadcasting the others
+pub fn nr_cross<T>(a: &Array<T>, b: &Array<T>) -> Array<T>
+where
+    T: Num + Copy,
+{
+    if a.shape[a.ndim - 1] != 3 || b.shape[b.ndim - 1] != 3 {
+        panic!("cross: last dimension of both arrays must be 3");
+    }
+    let shape = broadcast_final_shape(&a.shape, &b.shape)
+        .expect("cross: arrays could not be broadcast together");
+    let a_full = broadcast_array(a, &shape, shape.len());
+    let b_full = broadcast_array(b, &shape, shape.len());
+
+    let mut res = Array::nr_create(&shape, shape.len());
+    for ((r, x), y) in res.data.chunks_mut(3).zip(a_full.data.chunks(3)).zip(b_full.data.chunks(3)) {
+        r[0] = x[1] * y[2] - x[2] * y[1];
+        r[1] = x[2] * y[0] - x[0] * y[2];
+        r[2] = x[0] * y[1] - x[1] * y[0];
+    }
+    res
+}
@@ -0,0 +1,296 @@
+// Integration and differentiation of sampled data (np.trapezoid,
+// scipy.integrate.simpson and cumulative_trapezoid, np.gradient) and
+// adaptive quadrature of functions (scipy.integrate.quad).
+//
+// Sampled data is described by a Spacing along each axis it is integrated
+// or differentiated over.
+use crate::array::Array;
+use crate::fft::{map_lanes, resolve_axis};
+
+// Sample spacing along one axis
+#[derive(Debug, Clone, Copy)]
+pub enum Spacing<'a> {
+    // Constant distance between samples
+    Uniform(f64),
+    // Coordinates of the samples, one per index along the axis
+    Coords(&'a [f64]),
+}
+
+// Widths of the intervals between consecutive samples along `axis`, of
+// length n
+fn spacings(spacing: Spacing, n: usize, axis: usize, name: &str) -> Vec<f64> {
+    match spacing {
+        Spacing::Uniform(dx) => vec![dx; n.saturating_sub(1)],
+        Spacing::Coords(x) => {
+            if x.len() != n {
+                panic!("{}: {} coordinates for axis {} of length {}", name, x.len(), axis, n);
+            }
+            x.windows(2).map(|w| w[1] - w[0]).collect()
+        }
+    }
+}
+
+// Lane-wise reduction to one value, with the axis dropped from the shape
+fn reduce_lanes<F: Fn(&[f64]) -> f64 + Sync>(y: &Array<f64>, axis: usize, f: F) -> Array<f64> {
+    let out = map_lanes(y, axis, 1, |lane| vec![f(lane)]);
+    let shape: Vec<i32> = y.shape.iter().enumerate().filter(|&(ax, _)| ax != axis).map(|(_, &d)| d).collect();
+    if shape.is_empty() {
+        Array::from_vec(out.data, &[1])
+    } else {
+        Array::from_vec(out.data, &shape)
+    }
+}
+
+// Integral of y along `axis` (default last) by the trapezoidal rule
+// (np.trapezoid)
+pub fn nr_trapezoid(y: &Array<f64>, spacing: Spacing, axis: Option<usize>) -> Array<f64> {
+    let axis = resolve_axis(y, axis, "trapezoid");
+    let h = spacings(spacing, y.shape[axis] as usize, axis, "trapezoid");
+    reduce_lanes(y, axis, |lane| lane.windows(2).zip(&h).map(|(w, h)| 0.5 * h * (w[0] + w[1])).sum())
+}
+
+// Running trapezoidal integral of y along `axis`, one value per interval.
+// With `initial` the result starts with that value and has the length of
+// the axis (scipy.integrate.cumulative_trapezoid).
+pub fn nr_cumulative_trapezoid(y: &Array<f64>, spacing: Spacing, axis: Option<usize>, initial: Option<f64>) -> Array<f64> {
+    let axis = resolve_axis(y, axis, "cumulative_trapezoid");
+    let n = y.shape[axis] as usize;
+    let h = spacings(spacing, n, axis, "cumulative_trapezoid");
+    let n_out = if initial.is_some() { n } else { n - 1 };
+    if n_out == 0 {
+        panic!("cumulative_trapezoid: at least 2 samples are required without an initial value");
+    }
+    map_lanes(y, axis, n_out, |lane| {
+        let mut total = 0.0;
+        let steps = lane.windows(2).zip(&h).map(|(w, h)| {
+            total += 0.5 * h * (w[0] + w[1]);
+            total
+        });
+        initial.into_iter().chain(steps).collect()
+    })
+}
+
+// Composite Simpson's rule over the interval pairs (0, 1), (2, 3), ...
+// covering the first 2 * pairs intervals
+fn simpson_pairs(y: &[f64], h: &[f64], pairs: usize) -> f64 {
+    (0..pairs)
+        .map(|k| {
+            let (y0, y1, y2) = (y[2 * k], y[2 * k + 1], y[2 * k + 2]);
+            let (h0, h1) = (h[2 * k], h[2 * k + 1]);
+            // Integral of the parabola through the three points
+            let hsum = h0 + h1;
+            let ratio = h0 / h1;
+            hsum / 6.0 * (y0 * (2.0 - 1.0 / ratio) + y1 * hsum * hsum / (h0 * h1) + y2 * (2.0 - ratio))
+        })
+        .sum()
+}
+
+// Integral of y along `axis` by Simpson's rule (scipy.integrate.simpson).
+// With an even number of samples the last interval is integrated with Cartwright's correction,
+// which keeps the rule exact for cubics.
+pub fn nr_simpson(y: &Array<f64>, spacing: Spacing, axis: Option<usize>) -> Array<f64> {
+    let axis = resolve_axis(y, axis, "simpson");
+    let n = y.shape[axis] as usize;
+    let h = spacings(spacing, n, axis, "simpson");
+    reduce_lanes(y, axis, |lane| {
+        if n < 2 {
+            0.0
+        } else if n == 2 {
+            0.5 * h[0] * (lane[0] + lane[1])
+        } else if n % 2 == 1 {
+            simpson_pairs(lane, &h, (n - 1) / 2)
+        } else {
+            let (h0, h1) = (h[n - 3], h[n - 2]);
+            let alpha = (2.0 * h1 * h1 + 3.0 * h0 * h1) / (6.0 * (h0 + h1));
+            let beta = (h1 * h1 + 3.0 * h0 * h1) / (6.0 * h0);
+            let eta = h1 * h1 * h1 / (6.0 * h0 * (h0 + h1));
+            simpson_pairs(lane, &h, (n - 2) / 2) + alpha * lane[n - 1] + beta * lane[n - 2] - eta * lane[n - 3]
+        }
+    })
+}
+
+// Derivative of f along every axis by second-order central differences in
+// the interior and one-sided differences of order edge_order (1 or 2) at
+// the boundaries, with possibly uneven sample spacing (np.gradient).
+// `spacing` is empty for unit spacing, a single Uniform entry shared by all
+// axes, or one entry per axis.
+pub fn nr_gradient(f: &Array<f64>, spacing: &[Spacing], edge_order: usize) -> Vec<Array<f64>> {
+    if edge_order != 1 && edge_order != 2 {
+        panic!("gradient: edge_order must be 1 or 2, got {}", edge_order);
+    }
+    let spacing: Vec<Spacing> = match spacing.len() {
+        0 => vec![Spacing::Uniform(1.0); f.ndim],
+        1 if f.ndim > 1 => match spacing[0] {
+            Spacing::Uniform(_) => vec![spacing[0]; f.ndim],
+            Spacing::Coords(_) => panic!("gradient: a single coordinate array cannot be shared by {} axes", f.ndim),
+        },
+        len if len == f.ndim => spacing.to_vec(),
+        len => panic!("gradient: {} spacings given for a {}-D array", len, f.ndim),
+    };
+
+    (0..f.ndim)
+        .map(|axis| {
+            let n = f.shape[axis] as usize;
+            if n < edge_order + 1 {
+                panic!("gradient: axis {} has {} samples, at least {} are required", axis, n, edge_order + 1);
+            }
+            let h = spacings(spacing[axis], n, axis, "gradient");
+            map_lanes(f, axis, n, |lane| lane_gradient(lane, &h, edge_order))
+        })
+        .collect()
+}
+
+fn lane_gradient(f: &[f64], h: &[f64], edge_order: usize) -> Vec<f64> {
+    let n = f.len();
+    let mut out = vec![0.0; n];
+    // Derivative of the parabola through three neighbouring points
+    for i in 1..n - 1 {
+        let (h1, h2) = (h[i - 1], h[i]);
+        let a = -h2 / (h1 * (h1 + h2));
+        let b = (h2 - h1) / (h1 * h2);
+        let c = h1 / (h2 * (h1 + h2));
+        out[i] = a * f[i - 1] + b * f[i] + c * f[i + 1];
+    }
+    if edge_order == 1 {
+        out[0] = (f[1] - f[0]) / h[0];
+        out[n - 1] = (f[n - 1] - f[n - 2]) / h[n - 2];
+    } else {
+        let (h1, h2) = (h[0], h[1]);
+        let a = -(2.0 * h1 + h2) / (h1 * (h1 + h2));
+        let b = (h1 + h2) / (h1 * h2);
+        let c = -h1 / (h2 * (h1 + h2));
+        out[0] = a * f[0] + b * f[1] + c * f[2];
+        let (h1, h2) = (h[n - 3], h[n - 2]);
+        let a = h2 / (h1 * (h1 + h2));
+        let b = -(h1 + h2) / (h1 * h2);
+        let c = (2.0 * h2 + h1) / (h2 * (h1 + h2));
+        out[n - 1] = a * f[n - 3] + b * f[n - 2] + c * f[n - 1];
+    }
+    out
+}
+
+// Outcome of nr_quad
+#[derive(Debug, Clone, Copy)]
+pub struct QuadResult {
+    pub value: f64,
+    // Estimate of the absolute error of value
+    pub abserr: f64,
+    // Number of function evaluations
+    pub neval: usize,
+    // Whether the requested tolerance was met within the subdivision limit
+    pub converged: bool,
+}
+
+// 15-point Kronrod abscissae (non-negative half) with their weights, and
+// the weights of the embedded 7-point Gauss rule at the odd-indexed nodes
+const XGK: [f64; 8] = [
+    0.991_455_371_120_812_6,
+    0.949_107_912_342_758_5,
+    0.864_864_423_359_769_1,
+    0.741_531_185_599_394_4,
+    0.586_087_235_467_691_1,
+    0.405_845_151_377_397_2,
+    0.207_784_955_007_898_5,
+    0.0,
+];
+const WGK: [f64; 8] = [
+    0.022_935_322_010_529_22,
+    0.063_092_092_629_978_55,
+    0.104_790_010_322_250_2,
+    0.140_653_259_715_525_9,
+    0.169_004_726_639_267_9,
+    0.190_350_578_064_785_4,
+    0.204_432_940_075_298_9,
+    0.209_482_141_084_727_8,
+];
+const WG: [f64; 4] = [0.129_484_966_168_869_7, 0.279_705_391_489_276_7, 0.381_830_050_505_118_9, 0.417_959_183_673_469_4];
+
+// Gauss-Kronrod G7-K15 estimate over [a, b] with QUADPACK's error estimate
+fn kronrod15<F: Fn(f64) -> f64>(f: &F, a: f64, b: f64) -> (f64, f64) {
+    let center = 0.5 * (a + b);
+    let half = 0.5 * (b - a);
+    let fc = f(center);
+    let mut resk = WGK[7] * fc;
+    let mut resg = WG[3] * fc;
+    let mut resabs = resk.abs();
+    let mut pairs = [(0.0, 0.0); 7];
+    for (j, pair) in pairs.iter_mut().enumerate() {
+        let dx = half * XGK[j];
+        let (f1, f2) = (f(center - dx), f(center + dx));
+        resk += WGK[j] * (f1 + f2);
+        resabs += WGK[j] * (f1.abs() + f2.abs());
+        if j % 2 == 1 {
+            resg += WG[j / 2] * (f1 + f2);
+        }
+        *pair = (f1, f2);
+    }
+    let mean = 0.5 * resk;
+    let resasc = WGK[7] * (fc - mean).abs() + pairs.iter().zip(&WGK).map(|(&(f1, f2), w)| w * ((f1 - mean).abs() + (f2 - mean).abs())).sum::<f64>();
+
+    let (resk, resabs, resasc) = (resk * half, resabs * half.abs(), resasc * half.abs());
+    let mut err = (resk - resg * half).abs();
+    if resasc != 0.0 && err != 0.0 {
+        err = resasc * (200.0 * err / resasc).powf(1.5).min(1.0);
+    }
+    if resabs > f64::MIN_POSITIVE / (50.0 * f64::EPSILON) {
+        err = err.max(50.0 * f64::EPSILON * resabs);
+    }
+    (resk, err)
+}
+
+// Definite integral of f from a to b by adaptive Gauss-Kronrod quadrature,
+// repeatedly bisecting the subinterval with the largest error estimate
+// until the total error is below max(epsabs, epsrel * |value|) (defaults
+// 1.49e-8 each) or `limit` subintervals (default 50) are in use. Either
+// limit may be infinite (scipy.integrate.quad).
+pub fn nr_quad<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, epsabs: Option<f64>, epsrel: Option<f64>, limit: Option<usize>) -> QuadResult {
+    if a.is_nan() || b.is_nan() {
+        panic!("quad: integration limits must not be NaN");
+    }
+    if a == b {
+        return QuadResult { value: 0.0, abserr: 0.0, neval: 0, converged: true };
+    }
+    if a > b {
+        let r = nr_quad(f, b, a, epsabs, epsrel, limit);
+        return QuadResult { value: -r.value, ..r };
+    }
+    let epsabs = epsabs.unwrap_or(1.49e-8);
+    let epsrel = epsrel.unwrap_or(1.49e-8);
+    let limit = limit.unwrap_or(50).max(1);
+
+    // Infinite ranges are mapped onto (0, 1] by x = a + (1 - t) / t
+    let g = |t: f64| -> f64 {
+        let s = (1.0 - t) / t;
+        match (a.is_finite(), b.is_finite()) {
+            (true, true) => f(t),
+            (true, false) => f(a + s) / (t * t),
+            (false, true) => f(b - s) / (t * t),
+            (false, false) => (f(s) + f(-s)) / (t * t),
+        }
+    };
+    let (lo, hi) = if a.is_finite() && b.is_finite() { (a, b) } else { (0.0, 1.0) };
+
+    let (value, err) = kronrod15(&g, lo, hi);
+    let mut intervals = vec![(lo, hi, value, err)];
+    let mut neval = 15;
+    loop {
+        let value: f64 = intervals.iter().map(|iv| iv.2).sum();
+        let abserr: f64 = intervals.iter().map(|iv| iv.3).sum();
+        let converged = abserr <= epsabs.max(epsrel * value.abs());
+        if converged || intervals.len() >= limit {
+            return QuadResult { value, abserr, neval, converged };
+        }
+        let worst = (0..intervals.len()).max_by(|&i, &j| intervals[i].3.total_cmp(&intervals[j].3)).unwrap();
+        let (l, r, _, _) = intervals.swap_remove(worst);
+        let mid = 0.5 * (l + r);
+        if mid <= l || mid >= r {
+            // The interval cannot be split any further in floating point
+            return QuadResult { value, abserr, neval, converged: false };
+        }
+        let (v1, e1) = kronrod15(&g, l, mid);
+        let (v2, e2) = kronrod15(&g, mid, r);
+        intervals.push((l, mid, v1, e1));
+        intervals.push((mid, r, v2, e2));
+        neval += 30;
+    }
+}
@@ -0,0 +1,365 @@
+use super::Generator;
+use crate::array::Array;
+use crate::linalg::{cholesky_lower, LinalgError};
+use num_traits::{Float, PrimInt};
+
+// Below this mean, binomial and Poisson draws use inversion
+const INVERSION_MEAN: f64 = 10.0;
+
+impl Generator {
+    // Standard normal draw by the Marsaglia polar method
+    pub fn next_normal(&mut self) -> f64 {
+        loop {
+            let x = 2.0 * self.next_f64() - 1.0;
+            let y = 2.0 * self.next_f64() - 1.0;
+            let r2 = x * x + y * y;
+            if r2 > 0.0 && r2 < 1.0 {
+                return x * (-2.0 * r2.ln() / r2).sqrt();
+            }
+        }
+    }
+
+    // Gaussian samples (numpy Generator.normal)
+    pub fn normal<T: Float + Send>(&mut self, mean: T, std: T, shape: &[i32]) -> Array<T> {
+        if std < T::zero() {
+            panic!("normal: std must be non-negative");
+        }
+        let (mean, std) = (mean.to_f64().unwrap(), std.to_f64().unwrap());
+        self.fill(shape, |g| T::from(mean + std * g.next_normal()).unwrap())
+    }
+
+    // Uniform samples in [low, high) (numpy Generator.uniform)
+    pub fn uniform<T: Float + Send>(&mut self, low: T, high: T, shape: &[i32]) -> Array<T> {
+        if low > high {
+            panic!("uniform: low must not exceed high");
+        }
+        let (low, high) = (low.to_f64().unwrap(), high.to_f64().unwrap());
+        self.fill(shape, |g| {
+            let v = T::from(low + (high - low) * g.next_f64()).unwrap();
+            // Rounding can land exactly on high
+            if v >= T::from(high).unwrap() && high > low {
+                T::from(low).unwrap()
+            } else {
+                v
+            }
+        })
+    }
+
+    // Uniform integers in [low, high) without modulo bias
+    // (numpy Generator.integers)
+    pub fn integers<T: PrimInt + Send>(&mut self, low: T, high: T, shape: &[i32]) -> Array<T> {
+        let (lo, hi) = (low.to_i128().unwrap(), high.to_i128().unwrap());
+        if lo >= hi {
+            panic!("integers: low must be less than high");
+        }
+        let range = (hi - lo) as u128;
+        if range > u64::MAX as u128 {
+            panic!("integers: range {} is too large", range);
+        }
+        let range = range as u64;
+        self.fill(shape, |g| T::from(lo + g.bounded(range) as i128).unwrap())
+    }
+
+    // Uniform integer in [0, range) by Lemire's multiply-and-reject method
+    pub(crate) fn bounded(&mut self, range: u64) -> u64 {
+        let threshold = range.wrapping_neg() % range;
+        loop {
+            let m = self.next_u64() as u128 * range as u128;
+            if (m as u64) >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+
+    // Exponential samples with unit rate (numpy Generator.standard_exponential)
+    pub fn standard_exponential<T: Float + Send>(&mut self, shape: &[i32]) -> Array<T> {
+        self.fill(shape, |g| T::from(-(1.0 - g.next_f64()).ln()).unwrap())
+    }
+
+    // Gamma samples with shape k and the given scale (numpy Generator.gamma)
+    pub fn gamma<T: Float + Send>(&mut self, k: T, scale: T, shape: &[i32]) -> Array<T> {
+        if k <= T::zero() || k.is_nan() || scale < T::zero() {
+            panic!("gamma: shape must be positive and scale non-negative");
+        }
+        let (k, scale) = (k.to_f64().unwrap(), scale.to_f64().unwrap());
+        self.fill(shape, |g| T::from(scale * g.next_gamma(k)).unwrap())
+    }
+
+    // Marsaglia and Tsang's squeeze method, boosted for k < 1
+    fn next_gamma(&mut self, k: f64) -> f64 {
+        if k < 1.0 {
+            let u = 1.0 - self.next_f64();
+            return self.next_gamma(k + 1.0) * u.powf(1.0 / k);
+        }
+        let d = k - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let x = self.next_normal();
+            let v = 1.0 + c * x;
+            if v <= 0.0 {
+                continue;
+            }
+            let v = v * v * v;
+            let u = 1.0 - self.next_f64();
+            if u < 1.0 - 0.0331 * x.powi(4) || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+                return d * v;
+            }
+        }
+    }
+
+    // Beta samples (numpy Generator.beta)
+    pub fn beta<T: Float + Send>(&mut self, a: T, b: T, shape: &[i32]) -> Array<T> {
+        if a <= T::zero() || b <= T::zero() || a.is_nan() || b.is_nan() {
+            panic!("beta: a and b must be positive");
+        }
+        let (a, b) = (a.to_f64().unwrap(), b.to_f64().unwrap());
+        self.fill(shape, |g| T::from(g.next_beta(a, b)).unwrap())
+    }
+
+    fn next_beta(&mut self, a: f64, b: f64) -> f64 {
+        if a > 1.0 || b > 1.0 {
+            let x = self.next_gamma(a);
+            let y = self.next_gamma(b);
+            return x / (x + y);
+        }
+        // Johnk's algorithm, in logs when both powers underflow
+        loop {
+            let u = self.next_f64();
+            let v = self.next_f64();
+            let x = u.powf(1.0 / a);
+            let y = v.powf(1.0 / b);
+            if x + y <= 1.0 && u + v > 0.0 {
+                if x + y > 0.0 {
+                    return x / (x + y);
+                }
+                let (log_x, log_y) = (u.ln() / a, v.ln() / b);
+                let log_m = log_x.max(log_y);
+                let (log_x, log_y) = (log_x - log_m, log_y - log_m);
+                return (log_x - (log_x.exp() + log_y.exp()).ln()).exp();
+            }
+        }
+    }
+
+    // Number of successes in n Bernoulli(p) trials (numpy Generator.binomial)
+    pub fn binomial(&mut self, n: u64, p: f64, shape: &[i32]) -> Array<i64> {
+        if !(0.0..=1.0).contains(&p) {
+            panic!("binomial: p must be in [0, 1]");
+        }
+        self.fill(shape, |g| g.next_binomial(n, p) as i64)
+    }
+
+    fn next_binomial(&mut self, n: u64, p: f64) -> u64 {
+        if p > 0.5 {
+            return n - self.next_binomial(n, 1.0 - p);
+        }
+        if n == 0 || p == 0.0 {
+            return 0;
+        }
+        if n as f64 * p < INVERSION_MEAN {
+            return self.binomial_inversion(n, p);
+        }
+        // Devroye's recursion: the i-th order statistic of n uniforms is
+        // Beta(i, n + 1 - i), which splits the trials around it
+        let i = n / 2 + 1;
+        let y = self.next_beta(i as f64, (n + 1 - i) as f64);
+        if y >= p {
+            self.next_binomial(i - 1, p / y)
+        } else {
+            i + self.next_binomial(n - i, (p - y) / (1.0 - y))
+        }
+    }
+
+    // Sequential search through the pmf, for n p below INVERSION_MEAN
+    fn binomial_inversion(&mut self, n: u64, p: f64) -> u64 {
+        let q = 1.0 - p;
+        let r = p / q;
+        let g = r * (n + 1) as f64;
+        let q_n = q.powf(n as f64);
+        loop {
+            let mut u = self.next_f64();
+            let mut px = q_n;
+            let mut x = 0;
+            while u > px {
+                u -= px;
+                x += 1;
+                if x > n {
+                    break;
+                }
+                px *= g / x as f64 - r;
+            }
+            if x <= n {
+                return x;
+            }
+        }
+    }
+
+    // Poisson samples with mean lam (numpy Generator.poisson)
+    pub fn poisson(&mut self, lam: f64, shape: &[i32]) -> Array<i64> {
+        if lam < 0.0 || lam.is_nan() {
+            panic!("poisson: lam must be non-negative");
+        }
+        self.fill(shape, |g| g.next_poisson(lam) as i64)
+    }
+
+    fn next_poisson(&mut self, lam: f64) -> u64 {
+        if lam == 0.0 {
+            return 0;
+        }
+        if lam < INVERSION_MEAN {
+            // Multiply uniforms until the product drops below e^-lam
+            let limit = (-lam).exp();
+            let mut k = 0;
+            let mut prod = self.next_f64();
+            while prod > limit {
+                k += 1;
+                prod *= self.next_f64();
+            }
+            return k;
+        }
+        // Hormann's transformed rejection with squeeze (PTRS)
+        let slam = lam.sqrt();
+        let log_lam = lam.ln();
+        let b = 0.931 + 2.53 * slam;
+        let a = -0.059 + 0.02483 * b;
+        let inv_alpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.0);
+        loop {
+            let u = self.next_f64() - 0.5;
+            let v = self.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lam + 0.43).floor();
+            if us >= 0.07 && v <= vr {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            if v.ln() + inv_alpha.ln() - (a / (us * us) + b).ln() <= -lam + k * log_lam - ln_factorial(k) {
+                return k as u64;
+            }
+        }
+    }
+
+    // Counts of n trials over len(pvals) outcomes (numpy Generator.multinomial).
+    // The result has shape `shape + [len(pvals)]`; the last probability is
+    // taken as whatever the others leave over.
+    pub fn multinomial(&mut self, n: u64, pvals: &[f64], shape: &[i32]) -> Array<i64> {
+        if pvals.is_empty() || pvals.iter().any(|&p| !(0.0..=1.0).contains(&p)) {
+            panic!("multinomial: pvals must be probabilities");
+        }
+        if pvals[..pvals.len() - 1].iter().sum::<f64>() > 1.0 + 1e-12 {
+            panic!("multinomial: sum(pvals[:-1]) must not exceed 1");
+        }
+        let k = pvals.len();
+        let out_shape = sample_shape(shape, k);
+        let mut out = Array::nr_create(&out_shape, out_shape.len());
+        for sample in out.data.chunks_mut(k) {
+            let mut remaining = n;
+            let mut mass = 1.0;
+            for (count, &p) in sample.iter_mut().zip(&pvals[..k - 1]) {
+                let drawn = if remaining == 0 || mass <= 0.0 {
+                    0
+                } else {
+                    self.next_binomial(remaining, (p / mass).min(1.0))
+                };
+                *count = drawn as i64;
+                remaining -= drawn;
+                mass -= p;
+            }
+            sample[k - 1] = remaining as i64;
+        }
+        out
+    }
+
+    // Samples of N(mean, cov) via the Cholesky factor of cov
+    // (numpy Generator.multivariate_normal). The result has shape
+    // `shape + [len(mean)]`. cov must be positive definite.
+    pub fn multivariate_normal(
+        &mut self,
+        mean: &Array<f64>,
+        cov: &Array<f64>,
+        shape: &[i32],
+    ) -> Result<Array<f64>, LinalgError> {
+        let k = mean.totalsize;
+        if mean.ndim != 1 || cov.shape != [k as i32, k as i32] {
+            return Err(LinalgError::InvalidShape(format!(
+                "multivariate_normal: mean of shape {:?} and cov of shape {:?} are incompatible",
+                mean.shape, cov.shape
+            )));
+        }
+        let l = cholesky_lower(&cov.data, k)?;
+        let out_shape = sample_shape(shape, k);
+        let mut out = Array::nr_create(&out_shape, out_shape.len());
+        let mut z = vec![0.0; k];
+        for sample in out.data.chunks_mut(k) {
+            for zi in z.iter_mut() {
+                *zi = self.next_normal();
+            }
+            for (i, x) in sample.iter_mut().enumerate() {
+                *x = mean.data[i] + (0..=i).map(|j| l[i * k + j] * z[j]).sum::<f64>();
+            }
+        }
+        Ok(out)
+    }
+
+    // Dirichlet samples with concentrations alpha (numpy Generator.dirichlet),
+    // of shape `shape + [len(alpha)]`
+    pub fn dirichlet(&mut self, alpha: &[f64], shape: &[i32]) -> Array<f64> {
+        if alpha.is_empty() || alpha.iter().any(|&a| a <= 0.0 || a.is_nan()) {
+            panic!("dirichlet: alpha values must be positive");
+        }
+        let k = alpha.len();
+        let out_shape = sample_shape(shape, k);
+        let mut out = Array::nr_create(&out_shape, out_shape.len());
+        for sample in out.data.chunks_mut(k) {
+            for (x, &a) in sample.iter_mut().zip(alpha) {
+                *x = self.next_gamma(a);
+            }
+            let total: f64 = sample.iter().sum();
+            if total > 0.0 {
+                sample.iter_mut().for_each(|x| *x /= total);
+            } else {
+                // Every gamma underflowed; fall back to the beta stick-breaking form
+                let mut rest = 1.0;
+                for i in 0..k - 1 {
+                    let tail: f64 = alpha[i + 1..].iter().sum();
+                    sample[i] = rest * self.next_beta(alpha[i], tail);
+                    rest -= sample[i];
+                }
+                sample[k - 1] = rest;
+            }
+        }
+        out
+    }
+}
+
+// Draw shape with one trailing axis of length k for vector-valued samples
+fn sample_shape(shape: &[i32], k: usize) -> Vec<i32> {
+    let mut out = shape.to_vec();
+    out.push(k as i32);
+    out
+}
+
+// ln(k!) via Stirling's series, exact table for small k
+fn ln_factorial(k: f64) -> f64 {
+    const SMALL: [f64; 10] = [
+        0.0,
+        0.0,
+        std::f64::consts::LN_2,
+        1.791_759_469_228_055,
+        3.178_053_830_347_146,
+        4.787_491_742_782_046,
+        6.579_251_212_010_101,
+        8.525_161_361_065_415,
+        10.604_602_902_745_25,
+        12.801_827_480_081_469,
+    ];
+    if k < 10.0 {
+        return SMALL[k as usize];
+    }
+    let n = k + 1.0;
+    let inv = 1.0 / n;
+    let inv2 = inv * inv;
+    (n - 0.5) * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI).ln()
+        + inv * (1.0 / 12.0 - inv2 * (1.0 / 360.0 - inv2 / 1260.0))
+}
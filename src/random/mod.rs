@@ -0,0 +1,170 @@
+// Seedable random number generation (numpy.random.Generator).
+//
+// A Generator wraps a PCG64 stream. Bulk draws are split into fixed-size
+// chunks that are filled in parallel, each from its own PCG64 stream keyed
+// by the chunk index, so results depend only on the seed and never on the
+// number of rayon threads. Child streams get both their state and their
+// increment from numpy's SeedSequence hash of a 128-bit seed drawn from the
+// parent and the child's index, since PCG streams that share a state and
+// differ only in the increment are strongly correlated.
+mod distributions;
+mod sampling;
+
+use crate::array::Array;
+use num_traits::{Float, Zero};
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg64;
+use rayon::prelude::*;
+
+// Elements per independently seeded chunk in bulk draws
+const CHUNK: usize = 1 << 14;
+
+#[derive(Debug, Clone)]
+pub struct Generator {
+    rng: Pcg64,
+}
+
+impl Generator {
+    // Deterministic generator: equal seeds give equal streams
+    pub fn new(seed: u64) -> Generator {
+        Generator { rng: Pcg64::seed_from_u64(seed) }
+    }
+
+    // Generator seeded from operating system entropy
+    pub fn from_entropy() -> Generator {
+        Generator { rng: Pcg64::from_os_rng() }
+    }
+
+    // `n` child generators with independent streams, for handing out to
+    // parallel workers. Children are determined by this generator's state,
+    // which advances.
+    pub fn spawn(&mut self, n: usize) -> Vec<Generator> {
+        let seed = self.next_u128();
+        (0..n).map(|i| Generator { rng: child_pcg(seed, i) }).collect()
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    // Uniform f64 in [0, 1) with 53 random bits
+    pub fn next_f64(&mut self) -> f64 {
+        (self.rng.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    fn next_u128(&mut self) -> u128 {
+        ((self.rng.next_u64() as u128) << 64) | self.rng.next_u64() as u128
+    }
+
+    // Array of `shape` filled by `f`, which draws one element from the
+    // generator it is given. Chunks are filled in parallel from streams
+    // split off this generator.
+    pub fn fill<T, F>(&mut self, shape: &[i32], f: F) -> Array<T>
+    where
+        T: Copy + Zero + Send,
+        F: Fn(&mut Generator) -> T + Sync,
+    {
+        let mut arr = Array::nr_create(shape, shape.len());
+        let seed = self.next_u128();
+        arr.data.par_chunks_mut(CHUNK).enumerate().for_each(|(i, chunk)| {
+            let mut g = Generator { rng: child_pcg(seed, i) };
+            for v in chunk.iter_mut() {
+                *v = f(&mut g);
+            }
+        });
+        arr
+    }
+
+    // Uniform samples in [0, 1) (numpy Generator.random)
+    pub fn random<T: Float + Send>(&mut self, shape: &[i32]) -> Array<T> {
+        self.fill(shape, |g| unit::<T>(g.next_f64()))
+    }
+}
+
+// Constants of numpy's SeedSequence hash
+const POOL_SIZE: usize = 4;
+const INIT_A: u32 = 0x43b0_d7e5;
+const MULT_A: u32 = 0x931e_8875;
+const INIT_B: u32 = 0x8b51_f9dd;
+const MULT_B: u32 = 0x58f3_8ded;
+const MIX_MULT_L: u32 = 0xca01_f9dd;
+const MIX_MULT_R: u32 = 0x4973_f715;
+
+fn hashmix(value: u32, hash_const: &mut u32) -> u32 {
+    let v = value ^ *hash_const;
+    *hash_const = hash_const.wrapping_mul(MULT_A);
+    let v = v.wrapping_mul(*hash_const);
+    v ^ (v >> 16)
+}
+
+fn mix(x: u32, y: u32) -> u32 {
+    let r = MIX_MULT_L.wrapping_mul(x).wrapping_sub(MIX_MULT_R.wrapping_mul(y));
+    r ^ (r >> 16)
+}
+
+// 32-bit words of n, least significant first, as numpy coerces integers
+fn u32_words(mut n: u128) -> Vec<u32> {
+    let mut words = vec![(n as u32)];
+    n >>= 32;
+    while n > 0 {
+        words.push(n as u32);
+        n >>= 32;
+    }
+    words
+}
+
+// PCG64 stream of child `index` of `seed`: the state and increment numpy
+// gives PCG64(SeedSequence(seed, spawn_key=(index,)))
+fn child_pcg(seed: u128, index: usize) -> Pcg64 {
+    let mut entropy = u32_words(seed);
+    entropy.resize(entropy.len().max(POOL_SIZE), 0);
+    entropy.extend(u32_words(index as u128));
+
+    // Hash the entropy into the pool
+    let mut hash_const = INIT_A;
+    let mut pool = [0u32; POOL_SIZE];
+    for (i, p) in pool.iter_mut().enumerate() {
+        *p = hashmix(entropy.get(i).copied().unwrap_or(0), &mut hash_const);
+    }
+    for src in 0..POOL_SIZE {
+        for dst in 0..POOL_SIZE {
+            if src != dst {
+                let h = hashmix(pool[src], &mut hash_const);
+                pool[dst] = mix(pool[dst], h);
+            }
+        }
+    }
+    for &word in &entropy[POOL_SIZE..] {
+        for p in pool.iter_mut() {
+            *p = mix(*p, hashmix(word, &mut hash_const));
+        }
+    }
+
+    // Expand the pool to four 64-bit words: state, then increment
+    let mut hash_const = INIT_B;
+    let words: Vec<u64> = (0..8)
+        .map(|i| {
+            let v = pool[i % POOL_SIZE] ^ hash_const;
+            hash_const = hash_const.wrapping_mul(MULT_B);
+            let v = v.wrapping_mul(hash_const);
+            (v ^ (v >> 16)) as u64
+        })
+        .collect::<Vec<_>>()
+        .chunks(2)
+        .map(|w| w[0] | (w[1] << 32))
+        .collect();
+    let state = ((words[0] as u128) << 64) | words[1] as u128;
+    let stream = ((words[2] as u128) << 64) | words[3] as u128;
+    Pcg64::new(state, stream)
+}
+
+// Convert a draw in [0, 1) to T, keeping it below 1 when narrowing to f32
+// would round up
+pub(crate) fn unit<T: Float>(u: f64) -> T {
+    let v = T::from(u).unwrap();
+    if v >= T::one() {
+        T::one() - T::epsilon() / (T::one() + T::one())
+    } else {
+        v
+    }
+}
@@ -0,0 +1,120 @@
+use super::Generator;
+use crate::array::Array;
+use num_traits::Zero;
+
+impl Generator {
+    // Uniform index in [0, n)
+    fn index(&mut self, n: usize) -> usize {
+        self.bounded(n as u64) as usize
+    }
+
+    // Random sample of the entries of `arr` along axis 0 (numpy
+    // Generator.choice). The result has shape `size + arr.shape[1..]`.
+    // `p` gives a probability per entry (uniform when None); without
+    // replacement, entries are drawn in turn proportionally to the weights
+    // that are left.
+    pub fn choice<T: Copy + Zero>(&mut self, arr: &Array<T>, size: &[i32], replace: bool, p: Option<&[f64]>) -> Array<T> {
+        let n = arr.shape[0] as usize;
+        let k: usize = size.iter().map(|&d| d as usize).product();
+        if let Some(p) = p {
+            if p.len() != n {
+                panic!("choice: p has {} entries for {} candidates", p.len(), n);
+            }
+            if p.iter().any(|&w| w < 0.0 || w.is_nan()) || (p.iter().sum::<f64>() - 1.0).abs() > 1e-8 {
+                panic!("choice: probabilities must be non-negative and sum to 1");
+            }
+        }
+
+        let picks: Vec<usize> = match (replace, p) {
+            (true, None) => (0..k).map(|_| self.index(n)).collect(),
+            (true, Some(p)) => {
+                let mut cdf: Vec<f64> = p
+                    .iter()
+                    .scan(0.0, |acc, &w| {
+                        *acc += w;
+                        Some(*acc)
+                    })
+                    .collect();
+                let total = cdf[n - 1];
+                cdf.iter_mut().for_each(|c| *c /= total);
+                (0..k)
+                    .map(|_| {
+                        let u = self.next_f64();
+                        // First entry whose cumulative weight exceeds u,
+                        // skipping zero-weight entries
+                        cdf.partition_point(|&c| c <= u).min(n - 1)
+                    })
+                    .collect()
+            }
+            (false, None) => {
+                if k > n {
+                    panic!("choice: cannot take {} samples from {} without replacement", k, n);
+                }
+                // Partial Fisher-Yates shuffle
+                let mut idx: Vec<usize> = (0..n).collect();
+                for i in 0..k {
+                    let j = i + self.index(n - i);
+                    idx.swap(i, j);
+                }
+                idx.truncate(k);
+                idx
+            }
+            (false, Some(p)) => {
+                let support = p.iter().filter(|&&w| w > 0.0).count();
+                if k > support {
+                    panic!("choice: fewer non-zero entries in p than samples requested");
+                }
+                // Efraimidis-Spirakis: the k largest keys u^(1/w) follow the
+                // same law as k successive weighted draws
+                let mut keys: Vec<(f64, usize)> = p
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &w)| w > 0.0)
+                    .map(|(i, &w)| ((1.0 - self.next_f64()).ln() / w, i))
+                    .collect();
+                keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+                keys.into_iter().take(k).map(|(_, i)| i).collect()
+            }
+        };
+
+        let row = arr.totalsize / n.max(1);
+        let mut data = Vec::with_capacity(k * row);
+        for i in picks {
+            data.extend_from_slice(&arr.data[i * row..(i + 1) * row]);
+        }
+        let mut shape = size.to_vec();
+        shape.extend_from_slice(&arr.shape[1..]);
+        if shape.is_empty() {
+            shape.push(1);
+        }
+        Array::from_vec(data, &shape)
+    }
+
+    // Shuffle the sub-arrays along axis 0 in place (numpy Generator.shuffle)
+    pub fn shuffle<T: Copy + Zero>(&mut self, arr: &mut Array<T>) {
+        let n = arr.shape[0] as usize;
+        let row = arr.totalsize / n.max(1);
+        for i in (1..n).rev() {
+            let j = self.index(i + 1);
+            if i != j {
+                // j < i, so row j lies entirely in the head
+                let (head, tail) = arr.data.split_at_mut(i * row);
+                head[j * row..(j + 1) * row].swap_with_slice(&mut tail[..row]);
+            }
+        }
+    }
+
+    // Random permutation of 0..n (numpy Generator.permutation(n))
+    pub fn permutation(&mut self, n: usize) -> Array<i64> {
+        let mut arr = Array::from_vec((0..n as i64).collect(), &[n as i32]);
+        self.shuffle(&mut arr);
+        arr
+    }
+
+    // Shuffled copy of `arr` along axis 0 (numpy Generator.permutation(arr))
+    pub fn permutation_of<T: Copy + Zero>(&mut self, arr: &Array<T>) -> Array<T> {
+        let mut out = Array::from_vec(arr.data.clone(), &arr.shape);
+        self.shuffle(&mut out);
+        out
+    }
+}
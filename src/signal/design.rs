@@ -0,0 +1,334 @@
+use super::windows::{nr_get_window, Window};
+use crate::array::Array;
+use num_complex::Complex;
+use std::f64::consts::PI;
+
+type C64 = Complex<f64>;
+
+// Frequency band a filter passes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BandType {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Bandstop,
+}
+
+// Digital filter as zeros, poles and gain:
+// H(z) = gain * prod(z - zeros) / prod(z - poles)
+#[derive(Debug, Clone)]
+pub struct Zpk {
+    pub zeros: Vec<C64>,
+    pub poles: Vec<C64>,
+    pub gain: f64,
+}
+
+// Coefficients, highest power first, of the monic polynomial with these roots
+fn poly(roots: &[C64]) -> Vec<C64> {
+    let mut c = vec![C64::new(1.0, 0.0)];
+    for &r in roots {
+        c.push(C64::new(0.0, 0.0));
+        for i in (1..c.len()).rev() {
+            let prev = c[i - 1];
+            c[i] -= r * prev;
+        }
+    }
+    c
+}
+
+// Distance of a root from the unit circle
+fn unit_distance(r: &C64) -> f64 {
+    (1.0 - r.norm()).abs()
+}
+
+// Split roots into section-sized groups: conjugate pairs, then the real
+// roots two at a time, closest to the unit circle first
+fn root_groups(roots: &[C64]) -> Vec<Vec<C64>> {
+    let tol = |r: &C64| 1e-10 * r.norm().max(1.0);
+    let mut groups: Vec<Vec<C64>> = roots
+        .iter()
+        .filter(|r| r.im > tol(r))
+        .map(|&r| vec![r, r.conj()])
+        .collect();
+    let mut real: Vec<C64> = roots
+        .iter()
+        .filter(|r| r.im.abs() <= tol(r))
+        .map(|r| C64::new(r.re, 0.0))
+        .collect();
+    real.sort_by(|x, y| unit_distance(x).partial_cmp(&unit_distance(y)).unwrap());
+    groups.extend(real.chunks(2).map(|c| c.to_vec()));
+    groups
+}
+
+impl Zpk {
+    // Transfer function coefficients (b, a) with a[0] == 1
+    // (scipy.signal.zpk2tf)
+    pub fn to_ba(&self) -> (Vec<f64>, Vec<f64>) {
+        let b = poly(&self.zeros).iter().map(|c| c.re * self.gain).collect();
+        let a = poly(&self.poles).iter().map(|c| c.re).collect();
+        (b, a)
+    }
+
+    // Second-order sections as an [n_sections, 6] array of rows
+    // [b0, b1, b2, 1, a1, a2], for use with nr_sosfilt
+    // (scipy.signal.zpk2sos). Each pole pair takes the nearest remaining
+    // zeros, and sections with poles closest to the unit circle come last.
+    pub fn to_sos(&self) -> Array<f64> {
+        let n_sections = self.zeros.len().max(self.poles.len()).div_ceil(2).max(1);
+        let mut pole_groups = root_groups(&self.poles);
+        let mut zero_groups = root_groups(&self.zeros);
+        pole_groups.resize(n_sections, Vec::new());
+        zero_groups.resize(n_sections, Vec::new());
+
+        let closeness = |g: &Vec<C64>| g.first().map_or(f64::INFINITY, unit_distance);
+        pole_groups.sort_by(|x, y| closeness(x).partial_cmp(&closeness(y)).unwrap());
+
+        let mut sections = Vec::with_capacity(n_sections);
+        for poles in pole_groups {
+            let target = poles.first().copied().unwrap_or_default();
+            let (best, _) = zero_groups
+                .iter()
+                .enumerate()
+                .map(|(i, g)| (i, g.first().map_or(f64::INFINITY, |z| (z - target).norm())))
+                .fold((0, f64::INFINITY), |acc, (i, d)| if d < acc.1 { (i, d) } else { acc });
+            let zeros = zero_groups.swap_remove(best);
+            sections.push((zeros, poles));
+        }
+        sections.reverse();
+
+        let mut data = Vec::with_capacity(6 * n_sections);
+        for (i, (zeros, poles)) in sections.iter().enumerate() {
+            let gain = if i == 0 { self.gain } else { 1.0 };
+            let mut b: Vec<f64> = poly(zeros).iter().map(|c| c.re * gain).collect();
+            let mut a: Vec<f64> = poly(poles).iter().map(|c| c.re).collect();
+            b.resize(3, 0.0);
+            a.resize(3, 0.0);
+            data.extend(b);
+            data.extend(a);
+        }
+        Array::from_vec(data, &[n_sections as i32, 6])
+    }
+}
+
+// Analog prototype as (zeros, poles, gain) with a cutoff of 1 rad/s
+struct Prototype(Vec<C64>, Vec<C64>, f64);
+
+fn product(roots: &[C64]) -> C64 {
+    roots.iter().fold(C64::new(1.0, 0.0), |acc, &r| acc * r)
+}
+
+fn butter_prototype(n: usize) -> Prototype {
+    let poles = (0..n)
+        .map(|k| {
+            let m = (2 * k) as f64 - n as f64 + 1.0;
+            -C64::from_polar(1.0, PI * m / (2.0 * n as f64))
+        })
+        .collect();
+    Prototype(Vec::new(), poles, 1.0)
+}
+
+fn cheby1_prototype(n: usize, rp: f64) -> Prototype {
+    let eps = (10f64.powf(0.1 * rp) - 1.0).sqrt();
+    let mu = (1.0 / eps).asinh() / n as f64;
+    let poles: Vec<C64> = (0..n)
+        .map(|k| {
+            let theta = PI * ((2 * k) as f64 - n as f64 + 1.0) / (2.0 * n as f64);
+            -C64::new(mu, theta).sinh()
+        })
+        .collect();
+    let mut gain = product(&poles.iter().map(|p| -p).collect::<Vec<_>>()).re;
+    if n.is_multiple_of(2) {
+        gain /= (1.0 + eps * eps).sqrt();
+    }
+    Prototype(Vec::new(), poles, gain)
+}
+
+fn cheby2_prototype(n: usize, rs: f64) -> Prototype {
+    let de = 1.0 / (10f64.powf(0.1 * rs) - 1.0).sqrt();
+    let mu = (1.0 / de).asinh() / n as f64;
+    // Zeros on the imaginary axis, skipping the one at infinity for odd n
+    let zeros: Vec<C64> = (0..n)
+        .map(|k| (2 * k) as f64 - n as f64 + 1.0)
+        .filter(|&m| m != 0.0)
+        .map(|m| -(C64::new(0.0, 1.0) / (m * PI / (2.0 * n as f64)).sin()).conj())
+        .collect();
+    let poles: Vec<C64> = (0..n)
+        .map(|k| {
+            let p = -C64::from_polar(1.0, PI * ((2 * k) as f64 - n as f64 + 1.0) / (2.0 * n as f64));
+            C64::new(mu.sinh() * p.re, mu.cosh() * p.im).inv()
+        })
+        .collect();
+    let neg = |r: &[C64]| product(&r.iter().map(|v| -v).collect::<Vec<_>>());
+    let gain = (neg(&poles) / neg(&zeros)).re;
+    Prototype(zeros, poles, gain)
+}
+
+// Map the analog prototype onto the band edges (prewarped, in rad/s) and
+// discretize it with the bilinear transform at fs = 2
+fn transform(proto: Prototype, warped: &[f64], btype: BandType) -> Zpk {
+    let Prototype(z, p, k) = proto;
+    let degree = p.len() - z.len();
+    let neg = |r: &[C64]| product(&r.iter().map(|v| -v).collect::<Vec<_>>());
+    let zero = C64::new(0.0, 0.0);
+
+    let (z, p, k) = match btype {
+        BandType::Lowpass => {
+            let wo = warped[0];
+            let z: Vec<C64> = z.iter().map(|v| v * wo).collect();
+            let p: Vec<C64> = p.iter().map(|v| v * wo).collect();
+            (z, p, k * wo.powi(degree as i32))
+        }
+        BandType::Highpass => {
+            let wo = warped[0];
+            let k = k * (neg(&z) / neg(&p)).re;
+            let mut zh: Vec<C64> = z.iter().map(|v| wo / v).collect();
+            zh.extend(std::iter::repeat_n(zero, degree));
+            (zh, p.iter().map(|v| wo / v).collect(), k)
+        }
+        BandType::Bandpass | BandType::Bandstop => {
+            let wo = (warped[0] * warped[1]).sqrt();
+            let bw = warped[1] - warped[0];
+            // Each root r of the lowpass (or, for bandstop, highpass) shape
+            // splits into r +- sqrt(r^2 - wo^2)
+            let split = |roots: Vec<C64>| -> Vec<C64> {
+                let d: Vec<C64> = roots.iter().map(|r| (r * r - wo * wo).sqrt()).collect();
+                let mut out: Vec<C64> = roots.iter().zip(&d).map(|(r, d)| r + d).collect();
+                out.extend(roots.iter().zip(&d).map(|(r, d)| r - d));
+                out
+            };
+            if btype == BandType::Bandpass {
+                let mut zb = split(z.iter().map(|v| v * bw / 2.0).collect());
+                zb.extend(std::iter::repeat_n(zero, degree));
+                (zb, split(p.iter().map(|v| v * bw / 2.0).collect()), k * bw.powi(degree as i32))
+            } else {
+                let k = k * (neg(&z) / neg(&p)).re;
+                let mut zb = split(z.iter().map(|v| (bw / 2.0) / v).collect());
+                zb.extend(std::iter::repeat_n(C64::new(0.0, wo), degree));
+                zb.extend(std::iter::repeat_n(C64::new(0.0, -wo), degree));
+                (zb, split(p.iter().map(|v| (bw / 2.0) / v).collect()), k)
+            }
+        }
+    };
+
+    // Bilinear transform s = 2 fs (z - 1) / (z + 1); zeros at infinity land
+    // on z = -1
+    let fs2 = C64::new(4.0, 0.0);
+    let degree = p.len() - z.len();
+    let gain = k * (product(&z.iter().map(|v| fs2 - v).collect::<Vec<_>>()) / product(&p.iter().map(|v| fs2 - v).collect::<Vec<_>>())).re;
+    let mut zeros: Vec<C64> = z.iter().map(|v| (fs2 + v) / (fs2 - v)).collect();
+    zeros.extend(std::iter::repeat_n(C64::new(-1.0, 0.0), degree));
+    let poles = p.iter().map(|v| (fs2 + v) / (fs2 - v)).collect();
+    Zpk { zeros, poles, gain }
+}
+
+// Critical frequencies as fractions of the Nyquist frequency, checked
+// against the band type
+fn normalized_edges(wn: &[f64], btype: BandType, fs: Option<f64>, name: &str) -> Vec<f64> {
+    let expected = match btype {
+        BandType::Lowpass | BandType::Highpass => 1,
+        BandType::Bandpass | BandType::Bandstop => 2,
+    };
+    if wn.len() != expected {
+        panic!("{}: {:?} needs {} critical frequencies, got {}", name, btype, expected, wn.len());
+    }
+    let nyquist = fs.map_or(1.0, |fs| fs / 2.0);
+    let w: Vec<f64> = wn.iter().map(|&v| v / nyquist).collect();
+    if w.iter().any(|&v| v <= 0.0 || v >= 1.0 || v.is_nan()) {
+        panic!("{}: critical frequencies must lie strictly between 0 and the Nyquist frequency", name);
+    }
+    if expected == 2 && w[0] >= w[1] {
+        panic!("{}: band edges must be increasing", name);
+    }
+    w
+}
+
+fn iirfilter(order: usize, wn: &[f64], btype: BandType, fs: Option<f64>, name: &str, proto: impl Fn(usize) -> Prototype) -> Zpk {
+    if order == 0 {
+        panic!("{}: filter order must be positive", name);
+    }
+    let w = normalized_edges(wn, btype, fs, name);
+    // Prewarp so the digital edges land exactly on the requested ones
+    let warped: Vec<f64> = w.iter().map(|&v| 4.0 * (PI * v / 2.0).tan()).collect();
+    transform(proto(order), &warped, btype)
+}
+
+// Butterworth filter of the given order with critical frequencies wn, as
+// fractions of the Nyquist frequency or in the units of fs when given
+// (scipy.signal.butter)
+pub fn nr_butter(order: usize, wn: &[f64], btype: BandType, fs: Option<f64>) -> Zpk {
+    iirfilter(order, wn, btype, fs, "butter", butter_prototype)
+}
+
+// Chebyshev type I filter with rp dB of passband ripple
+// (scipy.signal.cheby1)
+pub fn nr_cheby1(order: usize, rp: f64, wn: &[f64], btype: BandType, fs: Option<f64>) -> Zpk {
+    if rp <= 0.0 || rp.is_nan() {
+        panic!("cheby1: passband ripple must be positive");
+    }
+    iirfilter(order, wn, btype, fs, "cheby1", |n| cheby1_prototype(n, rp))
+}
+
+// Chebyshev type II filter with stopband attenuation of at least rs dB
+// (scipy.signal.cheby2)
+pub fn nr_cheby2(order: usize, rs: f64, wn: &[f64], btype: BandType, fs: Option<f64>) -> Zpk {
+    if rs <= 0.0 || rs.is_nan() {
+        panic!("cheby2: stopband attenuation must be positive");
+    }
+    iirfilter(order, wn, btype, fs, "cheby2", |n| cheby2_prototype(n, rs))
+}
+
+// Linear-phase FIR filter of numtaps taps by the window method, with band
+// edges at `cutoff` (fractions of Nyquist, or in the units of fs). The
+// first band is a passband when pass_zero is set. The taps are scaled to
+// unit gain at the center of the first passband (scipy.signal.firwin).
+pub fn nr_firwin(numtaps: usize, cutoff: &[f64], window: Window, pass_zero: bool, fs: Option<f64>) -> Array<f64> {
+    if numtaps == 0 || cutoff.is_empty() {
+        panic!("firwin: numtaps and cutoff must be non-empty");
+    }
+    let nyquist = fs.map_or(1.0, |fs| fs / 2.0);
+    let cutoff: Vec<f64> = cutoff.iter().map(|&v| v / nyquist).collect();
+    if cutoff.iter().any(|&v| v <= 0.0 || v >= 1.0 || v.is_nan()) {
+        panic!("firwin: cutoff frequencies must lie strictly between 0 and the Nyquist frequency");
+    }
+    if cutoff.windows(2).any(|w| w[0] >= w[1]) {
+        panic!("firwin: cutoff frequencies must be strictly increasing");
+    }
+    let pass_nyquist = cutoff.len().is_multiple_of(2) == pass_zero;
+    if pass_nyquist && numtaps.is_multiple_of(2) {
+        panic!("firwin: a filter that passes the Nyquist frequency needs an odd number of taps");
+    }
+
+    let mut edges = Vec::with_capacity(cutoff.len() + 2);
+    if pass_zero {
+        edges.push(0.0);
+    }
+    edges.extend(&cutoff);
+    if pass_nyquist {
+        edges.push(1.0);
+    }
+
+    let sinc = |x: f64| if x == 0.0 { 1.0 } else { (PI * x).sin() / (PI * x) };
+    let alpha = 0.5 * (numtaps - 1) as f64;
+    let win = nr_get_window(window, numtaps, true);
+    let mut h: Vec<f64> = (0..numtaps)
+        .map(|i| {
+            let m = i as f64 - alpha;
+            let ideal: f64 = edges.chunks(2).map(|band| band[1] * sinc(band[1] * m) - band[0] * sinc(band[0] * m)).sum();
+            ideal * win.data[i]
+        })
+        .collect();
+
+    let (left, right) = (edges[0], edges[1]);
+    let f = if left == 0.0 {
+        0.0
+    } else if right == 1.0 {
+        1.0
+    } else {
+        0.5 * (left + right)
+    };
+    let s: f64 = h.iter().enumerate().map(|(i, &v)| v * (PI * (i as f64 - alpha) * f).cos()).sum();
+    for v in h.iter_mut() {
+        *v /= s;
+    }
+    Array::from_vec(h, &[numtaps as i32])
+}
@@ -0,0 +1,177 @@
+use crate::array::Array;
+use crate::fft::{map_lanes, resolve_axis};
+
+// b and a padded to a common length and scaled so that a[0] == 1
+fn normalize(b: &[f64], a: &[f64], name: &str) -> (Vec<f64>, Vec<f64>) {
+    if b.is_empty() || a.is_empty() {
+        panic!("{}: filter coefficients must not be empty", name);
+    }
+    if a[0] == 0.0 {
+        panic!("{}: a[0] must be nonzero", name);
+    }
+    let k = b.len().max(a.len());
+    let mut nb: Vec<f64> = b.iter().map(|&v| v / a[0]).collect();
+    let mut na: Vec<f64> = a.iter().map(|&v| v / a[0]).collect();
+    nb.resize(k, 0.0);
+    na.resize(k, 0.0);
+    (nb, na)
+}
+
+// Direct form II transposed over one lane, carrying the k - 1 delay
+// states in z from call to call
+fn df2t(b: &[f64], a: &[f64], x: &[f64], z: &mut [f64]) -> Vec<f64> {
+    let k = b.len();
+    x.iter()
+        .map(|&xi| {
+            if k == 1 {
+                return b[0] * xi;
+            }
+            let y = b[0] * xi + z[0];
+            for i in 0..k - 2 {
+                z[i] = b[i + 1] * xi + z[i + 1] - a[i + 1] * y;
+            }
+            z[k - 2] = b[k - 1] * xi - a[k - 1] * y;
+            y
+        })
+        .collect()
+}
+
+// Filter x along `axis` (default last) with the rational transfer function
+// b(z) / a(z), starting from rest (scipy.signal.lfilter)
+pub fn nr_lfilter(b: &[f64], a: &[f64], x: &Array<f64>, axis: Option<usize>) -> Array<f64> {
+    let (b, a) = normalize(b, a, "lfilter");
+    let axis = resolve_axis(x, axis, "lfilter");
+    map_lanes(x, axis, x.shape[axis] as usize, |lane| {
+        let mut z = vec![0.0; b.len() - 1];
+        df2t(&b, &a, lane, &mut z)
+    })
+}
+
+// Delay states of lfilter in the steady state of a unit step input, to be
+// scaled by the first sample to start a filter without a transient
+// (scipy.signal.lfilter_zi)
+pub fn nr_lfilter_zi(b: &[f64], a: &[f64]) -> Vec<f64> {
+    let (b, a) = normalize(b, a, "lfilter_zi");
+    let k = b.len();
+    if k == 1 {
+        return Vec::new();
+    }
+    let sum_a: f64 = a.iter().sum();
+    if sum_a == 0.0 {
+        panic!("lfilter_zi: the filter has a pole at z = 1 and no steady state");
+    }
+    // With the output settled at the DC gain g, z[i] = sum_{j > i} (b[j] - a[j] g)
+    let g = b.iter().sum::<f64>() / sum_a;
+    let mut zi = vec![0.0; k - 1];
+    let mut acc = 0.0;
+    for i in (0..k - 1).rev() {
+        acc += b[i + 1] - a[i + 1] * g;
+        zi[i] = acc;
+    }
+    zi
+}
+
+// Lane extended by `pad` points at each end with its odd reflection about
+// the end values
+fn odd_extend(x: &[f64], pad: usize) -> Vec<f64> {
+    let n = x.len();
+    let mut ext = Vec::with_capacity(n + 2 * pad);
+    ext.extend((1..=pad).rev().map(|i| 2.0 * x[0] - x[i]));
+    ext.extend_from_slice(x);
+    ext.extend((1..=pad).map(|i| 2.0 * x[n - 1] - x[n - 1 - i]));
+    ext
+}
+
+// Run `pass` forward and then backward over an odd extension of the lane,
+// which cancels the phase response. pass(v) filters v with initial states
+// scaled by v[0].
+fn forward_backward(x: &[f64], pad: usize, name: &str, pass: impl Fn(&[f64]) -> Vec<f64>) -> Vec<f64> {
+    if x.len() <= pad {
+        panic!("{}: input length {} must be greater than the padding length {}", name, x.len(), pad);
+    }
+    let ext = odd_extend(x, pad);
+    let mut y = pass(&ext);
+    y.reverse();
+    let mut y = pass(&y);
+    y.reverse();
+    y[pad..pad + x.len()].to_vec()
+}
+
+// Zero-phase filtering by applying b(z) / a(z) forward and backward along
+// `axis`, with odd padding and steady-state initial conditions to suppress
+// edge transients (scipy.signal.filtfilt)
+pub fn nr_filtfilt(b: &[f64], a: &[f64], x: &Array<f64>, axis: Option<usize>) -> Array<f64> {
+    let zi = nr_lfilter_zi(b, a);
+    let (b, a) = normalize(b, a, "filtfilt");
+    let axis = resolve_axis(x, axis, "filtfilt");
+    let pad = 3 * b.len();
+    map_lanes(x, axis, x.shape[axis] as usize, |lane| {
+        forward_backward(lane, pad, "filtfilt", |v| {
+            let mut z: Vec<f64> = zi.iter().map(|&s| s * v[0]).collect();
+            df2t(&b, &a, v, &mut z)
+        })
+    })
+}
+
+// Rows of an [n_sections, 6] second-order-sections array as normalized
+// (b, a) pairs
+fn sections(sos: &Array<f64>, name: &str) -> Vec<([f64; 3], [f64; 3])> {
+    if sos.ndim != 2 || sos.shape[1] != 6 {
+        panic!("{}: sos must have shape [n_sections, 6], got {:?}", name, sos.shape);
+    }
+    sos.data
+        .chunks(6)
+        .map(|s| {
+            if s[3] == 0.0 {
+                panic!("{}: a[0] of every section must be nonzero", name);
+            }
+            ([s[0] / s[3], s[1] / s[3], s[2] / s[3]], [1.0, s[4] / s[3], s[5] / s[3]])
+        })
+        .collect()
+}
+
+fn sos_lane(secs: &[([f64; 3], [f64; 3])], x: &[f64], zi: &mut [Vec<f64>]) -> Vec<f64> {
+    let mut y = x.to_vec();
+    for ((b, a), z) in secs.iter().zip(zi.iter_mut()) {
+        y = df2t(b, a, &y, z);
+    }
+    y
+}
+
+// Filter x along `axis` with a cascade of second-order sections, as made
+// by Zpk::to_sos (scipy.signal.sosfilt)
+pub fn nr_sosfilt(sos: &Array<f64>, x: &Array<f64>, axis: Option<usize>) -> Array<f64> {
+    let secs = sections(sos, "sosfilt");
+    let axis = resolve_axis(x, axis, "sosfilt");
+    map_lanes(x, axis, x.shape[axis] as usize, |lane| {
+        let mut zi = vec![vec![0.0; 2]; secs.len()];
+        sos_lane(&secs, lane, &mut zi)
+    })
+}
+
+// Zero-phase version of nr_sosfilt (scipy.signal.sosfiltfilt)
+pub fn nr_sosfiltfilt(sos: &Array<f64>, x: &Array<f64>, axis: Option<usize>) -> Array<f64> {
+    let secs = sections(sos, "sosfiltfilt");
+    let axis = resolve_axis(x, axis, "sosfiltfilt");
+
+    // Steady state of the cascade: each section sees the step scaled by the
+    // DC gain of the sections before it
+    let mut scale = 1.0;
+    let mut zi = Vec::with_capacity(secs.len());
+    for (b, a) in &secs {
+        zi.push(nr_lfilter_zi(b, a).iter().map(|&s| s * scale).collect::<Vec<f64>>());
+        scale *= b.iter().sum::<f64>() / a.iter().sum::<f64>();
+    }
+
+    // Trailing zero coefficients shorten the effective filter
+    let zero_b = secs.iter().filter(|(b, _)| b[2] == 0.0).count();
+    let zero_a = secs.iter().filter(|(_, a)| a[2] == 0.0).count();
+    let pad = 3 * (2 * secs.len() + 1 - zero_b.min(zero_a));
+
+    map_lanes(x, axis, x.shape[axis] as usize, |lane| {
+        forward_backward(lane, pad, "sosfiltfilt", |v| {
+            let mut z: Vec<Vec<f64>> = zi.iter().map(|s| s.iter().map(|&c| c * v[0]).collect()).collect();
+            sos_lane(&secs, v, &mut z)
+        })
+    })
+}
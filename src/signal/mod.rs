@@ -0,0 +1,18 @@
+// Digital signal processing over f64 arrays, mirroring scipy.signal.
+//
+// Filters are applied along one axis of an Array, every lane
+// independently. IIR designs come back as a Zpk value that converts to
+// (b, a) coefficients for nr_lfilter / nr_filtfilt or, better conditioned
+// for high orders, to second-order sections for nr_sosfilt. The spectral
+// estimates and Fourier resampling are built on the fft module.
+mod design;
+mod filter;
+mod resample;
+mod spectral;
+mod windows;
+
+pub use design::{nr_butter, nr_cheby1, nr_cheby2, nr_firwin, BandType, Zpk};
+pub use filter::{nr_filtfilt, nr_lfilter, nr_lfilter_zi, nr_sosfilt, nr_sosfiltfilt};
+pub use resample::{nr_decimate, nr_resample};
+pub use spectral::{nr_periodogram, nr_welch, Scaling};
+pub use windows::{nr_bartlett, nr_blackman, nr_get_window, nr_hamming, nr_hann, nr_kaiser, Window};
@@ -0,0 +1,50 @@
+use super::design::{nr_cheby1, BandType};
+use super::filter::nr_sosfiltfilt;
+use crate::array::Array;
+use crate::fft::{map_lanes, nr_irfft, nr_rfft, resolve_axis, FftNorm};
+use num_complex::Complex;
+
+// Resample x to `num` points along `axis` by truncating or zero-padding its
+// spectrum, which treats the signal as periodic (scipy.signal.resample)
+pub fn nr_resample(x: &Array<f64>, num: usize, axis: Option<usize>) -> Array<f64> {
+    if num == 0 {
+        panic!("resample: number of output samples must be positive");
+    }
+    let axis = resolve_axis(x, axis, "resample");
+    let nx = x.shape[axis] as usize;
+    map_lanes(x, axis, num, |lane| {
+        let spec = nr_rfft(&Array::from_vec(lane.to_vec(), &[nx as i32]), None, None, FftNorm::Backward);
+        let n = num.min(nx);
+        let mut y = vec![Complex::new(0.0, 0.0); num / 2 + 1];
+        y[..n / 2 + 1].copy_from_slice(&spec.data[..n / 2 + 1]);
+        // An even-length spectrum has an unpaired Nyquist bin that is split
+        // or merged across the positive and negative frequencies
+        if n.is_multiple_of(2) {
+            if num < nx {
+                y[n / 2] *= 2.0;
+            } else if nx < num {
+                y[n / 2] *= 0.5;
+            }
+        }
+        let spec = Array::from_vec(y, &[(num / 2 + 1) as i32]);
+        let scale = num as f64 / nx as f64;
+        nr_irfft(&spec, Some(num), None, FftNorm::Backward).data.iter().map(|v| v * scale).collect()
+    })
+}
+
+// Downsample x by an integer factor q along `axis` after a zero-phase order
+// 8 Chebyshev type I anti-aliasing filter (scipy.signal.decimate)
+pub fn nr_decimate(x: &Array<f64>, q: usize, axis: Option<usize>) -> Array<f64> {
+    if q == 0 {
+        panic!("decimate: downsampling factor must be positive");
+    }
+    let axis = resolve_axis(x, axis, "decimate");
+    let filtered = if q == 1 {
+        Array::from_vec(x.data.clone(), &x.shape)
+    } else {
+        let sos = nr_cheby1(8, 0.05, &[0.8 / q as f64], BandType::Lowpass, None).to_sos();
+        nr_sosfiltfilt(&sos, x, Some(axis))
+    };
+    let n = (x.shape[axis] as usize).div_ceil(q);
+    map_lanes(&filtered, axis, n, |lane| lane.iter().step_by(q).copied().collect())
+}
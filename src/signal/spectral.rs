@@ -0,0 +1,97 @@
+use super::windows::{nr_get_window, Window};
+use crate::array::Array;
+use crate::fft::{map_lanes, nr_rfft, nr_rfftfreq, resolve_axis, FftNorm};
+
+// Units of a power spectrum estimate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    // Power spectral density, V**2/Hz
+    Density,
+    // Power spectrum, V**2
+    Spectrum,
+}
+
+// One-sided spectrum of a lane averaged over windowed, mean-removed
+// segments of win.len() points taken every `step` points, each
+// zero-padded to nfft
+fn averaged_spectrum(lane: &[f64], win: &[f64], step: usize, nfft: usize, fs: f64, scaling: Scaling) -> Vec<f64> {
+    let nperseg = win.len();
+    let starts: Vec<usize> = (0..=lane.len() - nperseg).step_by(step).collect();
+    let segments: Vec<f64> = starts
+        .iter()
+        .flat_map(|&s| {
+            let seg = &lane[s..s + nperseg];
+            let mean = seg.iter().sum::<f64>() / nperseg as f64;
+            seg.iter().zip(win).map(move |(v, w)| (v - mean) * w)
+        })
+        .collect();
+    let segments = Array::from_vec(segments, &[starts.len() as i32, nperseg as i32]);
+    let spec = nr_rfft(&segments, Some(nfft), Some(1), FftNorm::Backward);
+
+    let nfreq = nfft / 2 + 1;
+    let scale = match scaling {
+        Scaling::Density => 1.0 / (fs * win.iter().map(|w| w * w).sum::<f64>()),
+        Scaling::Spectrum => 1.0 / win.iter().sum::<f64>().powi(2),
+    };
+    let mut power = vec![0.0; nfreq];
+    for row in spec.data.chunks(nfreq) {
+        for (p, v) in power.iter_mut().zip(row) {
+            *p += v.norm_sqr();
+        }
+    }
+    // Fold the negative frequencies in; DC and an even-length Nyquist bin
+    // have no mirror image
+    let last_paired = if nfft.is_multiple_of(2) { nfreq - 1 } else { nfreq };
+    for (k, p) in power.iter_mut().enumerate() {
+        *p *= scale / starts.len() as f64;
+        if k > 0 && k < last_paired {
+            *p *= 2.0;
+        }
+    }
+    power
+}
+
+// Power spectrum estimate of the whole signal along `axis`, sampled at fs,
+// after removing the mean and applying `window` (scipy.signal.periodogram).
+// Returns the frequencies and the spectrum along that axis.
+pub fn nr_periodogram(x: &Array<f64>, fs: f64, window: Window, nfft: Option<usize>, scaling: Scaling, axis: Option<usize>) -> (Array<f64>, Array<f64>) {
+    let axis = resolve_axis(x, axis, "periodogram");
+    let n = x.shape[axis] as usize;
+    let nfft = nfft.unwrap_or(n);
+    if nfft == 0 {
+        panic!("periodogram: nfft must be positive");
+    }
+    // A shorter nfft truncates the signal rather than aliasing it
+    let nperseg = n.min(nfft);
+    let win = nr_get_window(window, nperseg, false).data;
+    let psd = map_lanes(x, axis, nfft / 2 + 1, |lane| averaged_spectrum(&lane[..nperseg], &win, nperseg, nfft, fs, scaling));
+    (nr_rfftfreq(nfft, 1.0 / fs), psd)
+}
+
+// Welch's power spectrum estimate along `axis`: the average periodogram of
+// overlapping segments of nperseg points (default 256, at most the signal
+// length) overlapping by noverlap (default nperseg / 2), each detrended by
+// its mean and tapered by `window` (scipy.signal.welch)
+pub fn nr_welch(
+    x: &Array<f64>,
+    fs: f64,
+    window: Window,
+    nperseg: Option<usize>,
+    noverlap: Option<usize>,
+    scaling: Scaling,
+    axis: Option<usize>,
+) -> (Array<f64>, Array<f64>) {
+    let axis = resolve_axis(x, axis, "welch");
+    let n = x.shape[axis] as usize;
+    let nperseg = nperseg.unwrap_or(256).min(n);
+    if nperseg == 0 {
+        panic!("welch: nperseg must be positive");
+    }
+    let noverlap = noverlap.unwrap_or(nperseg / 2);
+    if noverlap >= nperseg {
+        panic!("welch: noverlap must be less than nperseg");
+    }
+    let win = nr_get_window(window, nperseg, false).data;
+    let psd = map_lanes(x, axis, nperseg / 2 + 1, |lane| averaged_spectrum(lane, &win, nperseg - noverlap, nperseg, fs, scaling));
+    (nr_rfftfreq(nperseg, 1.0 / fs), psd)
+}
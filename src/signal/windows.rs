@@ -0,0 +1,74 @@
+use crate::array::Array;
+use crate::special::bessel_i0;
+use std::f64::consts::PI;
+
+// Window shapes understood by nr_get_window and the functions that taper
+// their input (firwin, welch, periodogram)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Boxcar,
+    Hann,
+    Hamming,
+    Blackman,
+    Bartlett,
+    // Kaiser window with shape parameter beta
+    Kaiser(f64),
+}
+
+// Window of length m. A symmetric window (sym = true) is meant for filter
+// design; the periodic one (sym = false) drops the last point of an m + 1
+// window and is the one to use for spectral analysis.
+pub fn nr_get_window(window: Window, m: usize, sym: bool) -> Array<f64> {
+    if m == 0 {
+        panic!("get_window: window length must be positive");
+    }
+    if m == 1 {
+        return Array::from_vec(vec![1.0], &[1]);
+    }
+    let len = if sym { m } else { m + 1 };
+    let span = (len - 1) as f64;
+    let data = (0..m)
+        .map(|n| {
+            let n = n as f64;
+            match window {
+                Window::Boxcar => 1.0,
+                Window::Hann => 0.5 - 0.5 * (2.0 * PI * n / span).cos(),
+                Window::Hamming => 0.54 - 0.46 * (2.0 * PI * n / span).cos(),
+                Window::Blackman => 0.42 - 0.5 * (2.0 * PI * n / span).cos() + 0.08 * (4.0 * PI * n / span).cos(),
+                Window::Bartlett => 1.0 - (2.0 * n / span - 1.0).abs(),
+                Window::Kaiser(beta) => {
+                    let r = 2.0 * n / span - 1.0;
+                    bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(beta)
+                }
+            }
+        })
+        .collect();
+    Array::from_vec(data, &[m as i32])
+}
+
+// Hann (raised cosine) window (scipy.signal.windows.hann)
+pub fn nr_hann(m: usize, sym: bool) -> Array<f64> {
+    nr_get_window(Window::Hann, m, sym)
+}
+
+// Hamming window (scipy.signal.windows.hamming)
+pub fn nr_hamming(m: usize, sym: bool) -> Array<f64> {
+    nr_get_window(Window::Hamming, m, sym)
+}
+
+// Blackman window (scipy.signal.windows.blackman)
+pub fn nr_blackman(m: usize, sym: bool) -> Array<f64> {
+    nr_get_window(Window::Blackman, m, sym)
+}
+
+// Triangular window with zero end points (scipy.signal.windows.bartlett)
+pub fn nr_bartlett(m: usize, sym: bool) -> Array<f64> {
+    nr_get_window(Window::Bartlett, m, sym)
+}
+
+// Kaiser window; beta trades main-lobe width for side-lobe level
+// (scipy.signal.windows.kaiser)
+pub fn nr_kaiser(m: usize, beta: f64, sym: bool) -> Array<f64> {
+    nr_get_window(Window::Kaiser(beta), m, sym)
+}
+
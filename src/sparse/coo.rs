@@ -0,0 +1,88 @@
+use super::{compress, CscMatrix, CsrMatrix};
+use crate::array::Array;
+use num_traits::Num;
+
+// Coordinate-format sparse matrix: entry k is data[k] at (row[k], col[k]).
+// Duplicate coordinates are allowed and summed when converting.
+#[derive(Debug, Clone)]
+pub struct CooMatrix<T> {
+    pub shape: (usize, usize),
+    pub row: Vec<usize>,
+    pub col: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+impl<T: Num + Copy> CooMatrix<T> {
+    pub fn new(shape: (usize, usize), row: Vec<usize>, col: Vec<usize>, data: Vec<T>) -> CooMatrix<T> {
+        if row.len() != data.len() || col.len() != data.len() {
+            panic!("CooMatrix: row, col and data must have the same length");
+        }
+        if row.iter().any(|&r| r >= shape.0) || col.iter().any(|&c| c >= shape.1) {
+            panic!("CooMatrix: coordinates out of bounds for shape {:?}", shape);
+        }
+        CooMatrix { shape, row, col, data }
+    }
+
+    // Empty matrix to be filled with push
+    pub fn zeros(shape: (usize, usize)) -> CooMatrix<T> {
+        CooMatrix { shape, row: Vec::new(), col: Vec::new(), data: Vec::new() }
+    }
+
+    // Append an entry, adding to any existing value at (i, j)
+    pub fn push(&mut self, i: usize, j: usize, value: T) {
+        if i >= self.shape.0 || j >= self.shape.1 {
+            panic!("CooMatrix: ({}, {}) is out of bounds for shape {:?}", i, j, self.shape);
+        }
+        self.row.push(i);
+        self.col.push(j);
+        self.data.push(value);
+    }
+
+    // Stored entries, counting duplicates and explicit zeros
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    // Nonzero entries of a 2-D array
+    pub fn from_dense(a: &Array<T>) -> CooMatrix<T> {
+        if a.ndim != 2 {
+            panic!("CooMatrix: expected a 2-D array, got shape {:?}", a.shape);
+        }
+        let (m, n) = (a.shape[0] as usize, a.shape[1] as usize);
+        let mut coo = CooMatrix::zeros((m, n));
+        for (k, &v) in a.data.iter().enumerate() {
+            if v != T::zero() {
+                coo.push(k / n, k % n, v);
+            }
+        }
+        coo
+    }
+
+    pub fn to_dense(&self) -> Array<T> {
+        let (m, n) = self.shape;
+        let mut out = vec![T::zero(); m * n];
+        for ((&i, &j), &v) in self.row.iter().zip(&self.col).zip(&self.data) {
+            out[i * n + j] = out[i * n + j] + v;
+        }
+        Array::from_vec(out, &[m as i32, n as i32])
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        let (indptr, indices, data) = compress(self.shape.0, &self.row, &self.col, &self.data);
+        CsrMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        let (indptr, indices, data) = compress(self.shape.1, &self.col, &self.row, &self.data);
+        CscMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    pub fn transpose(&self) -> CooMatrix<T> {
+        CooMatrix {
+            shape: (self.shape.1, self.shape.0),
+            row: self.col.clone(),
+            col: self.row.clone(),
+            data: self.data.clone(),
+        }
+    }
+}
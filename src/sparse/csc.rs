@@ -0,0 +1,155 @@
+use super::{dense_dims, validate_compressed, CooMatrix, CsrMatrix};
+use crate::array::Array;
+use num_traits::Num;
+
+// Compressed sparse column matrix: the row indices and values of column j
+// are indices[indptr[j]..indptr[j + 1]] and data[indptr[j]..indptr[j + 1]].
+// Structurally it is the CSR form of the transpose, which is how the
+// products and element-wise ops are implemented.
+#[derive(Debug, Clone)]
+pub struct CscMatrix<T> {
+    pub shape: (usize, usize),
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+impl<T: Num + Copy> CscMatrix<T> {
+    pub fn new(shape: (usize, usize), indptr: Vec<usize>, indices: Vec<usize>, data: Vec<T>) -> CscMatrix<T> {
+        validate_compressed("CscMatrix", shape.1, shape.0, &indptr, &indices, data.len());
+        CscMatrix { shape, indptr, indices, data }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    // Row indices and values stored in column j
+    pub fn col(&self, j: usize) -> (&[usize], &[T]) {
+        let (start, end) = (self.indptr[j], self.indptr[j + 1]);
+        (&self.indices[start..end], &self.data[start..end])
+    }
+
+    // Value at (i, j), zero when not stored
+    pub fn get(&self, i: usize, j: usize) -> T {
+        let (rows, vals) = self.col(j);
+        rows.iter().zip(vals).filter(|(&r, _)| r == i).fold(T::zero(), |acc, (_, &v)| acc + v)
+    }
+
+    pub fn from_dense(a: &Array<T>) -> CscMatrix<T> {
+        CooMatrix::from_dense(a).to_csc()
+    }
+
+    pub fn to_dense(&self) -> Array<T> {
+        let (m, n) = self.shape;
+        let mut out = vec![T::zero(); m * n];
+        for j in 0..n {
+            let (rows, vals) = self.col(j);
+            for (&i, &v) in rows.iter().zip(vals) {
+                out[i * n + j] = out[i * n + j] + v;
+            }
+        }
+        Array::from_vec(out, &[m as i32, n as i32])
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        self.transpose().to_coo().transpose()
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix<T> {
+        self.to_coo().to_csr()
+    }
+
+    // The transpose is a copy of the same arrays, read row-wise
+    pub fn transpose(&self) -> CsrMatrix<T> {
+        CsrMatrix {
+            shape: (self.shape.1, self.shape.0),
+            indptr: self.indptr.clone(),
+            indices: self.indices.clone(),
+            data: self.data.clone(),
+        }
+    }
+
+    // Columns start..end as a new matrix (A[:, start:end] in scipy)
+    pub fn slice_cols(&self, start: usize, end: usize) -> CscMatrix<T> {
+        self.transpose().slice_rows(start, end).transpose()
+    }
+
+    pub fn scale(&self, s: T) -> CscMatrix<T> {
+        self.transpose().scale(s).transpose()
+    }
+
+    pub fn add(&self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        self.transpose().add(&other.transpose()).transpose()
+    }
+
+    pub fn sub(&self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        self.transpose().sub(&other.transpose()).transpose()
+    }
+
+    pub fn multiply(&self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        self.transpose().multiply(&other.transpose()).transpose()
+    }
+
+    // (A B)^T = B^T A^T, with both transposes available in CSR for free
+    pub fn matmul(&self, other: &CscMatrix<T>) -> CscMatrix<T> {
+        if self.shape.1 != other.shape.0 {
+            panic!(
+                "matmul: dimension mismatch, {:?} @ {:?} ({} != {})",
+                self.shape, other.shape, self.shape.1, other.shape.0
+            );
+        }
+        other.transpose().matmul(&self.transpose()).transpose()
+    }
+
+    // Sparse @ dense. `b` is (n,) or (n, k); the result has b's dimensionality.
+    pub fn matmul_dense(&self, b: &Array<T>) -> Array<T> {
+        let (rows, k) = dense_dims(b, "matmul_dense");
+        if rows != self.shape.1 {
+            panic!(
+                "matmul_dense: dimension mismatch, {:?} @ {:?} ({} != {})",
+                self.shape, b.shape, self.shape.1, rows
+            );
+        }
+        let mut out = vec![T::zero(); self.shape.0 * k];
+        for j in 0..self.shape.1 {
+            let b_row = &b.data[j * k..(j + 1) * k];
+            let (rows, vals) = self.col(j);
+            for (&i, &v) in rows.iter().zip(vals) {
+                for (o, &x) in out[i * k..(i + 1) * k].iter_mut().zip(b_row) {
+                    *o = *o + v * x;
+                }
+            }
+        }
+        if b.ndim == 1 {
+            Array::from_vec(out, &[self.shape.0 as i32])
+        } else {
+            Array::from_vec(out, &[self.shape.0 as i32, k as i32])
+        }
+    }
+
+    // Dense @ sparse for a of shape (p, m) or (m,)
+    pub fn dense_matmul(&self, a: &Array<T>) -> Array<T> {
+        let (p, m) = if a.ndim == 1 { (1, a.shape[0] as usize) } else { dense_dims(a, "dense_matmul") };
+        if m != self.shape.0 {
+            panic!(
+                "dense_matmul: dimension mismatch, {:?} @ {:?} ({} != {})",
+                a.shape, self.shape, m, self.shape.0
+            );
+        }
+        let n = self.shape.1;
+        let mut out = vec![T::zero(); p * n];
+        for r in 0..p {
+            let a_row = &a.data[r * m..(r + 1) * m];
+            for j in 0..n {
+                let (rows, vals) = self.col(j);
+                out[r * n + j] = rows.iter().zip(vals).fold(T::zero(), |acc, (&i, &v)| acc + a_row[i] * v);
+            }
+        }
+        if a.ndim == 1 {
+            Array::from_vec(out, &[n as i32])
+        } else {
+            Array::from_vec(out, &[p as i32, n as i32])
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use super::{dense_dims, validate_compressed, CooMatrix, CscMatrix};
+use crate::array::Array;
+use num_traits::Num;
+use rayon::prelude::*;
+
+// Compressed sparse row matrix: the column indices and values of row i are
+// indices[indptr[i]..indptr[i + 1]] and data[indptr[i]..indptr[i + 1]].
+// Matrices built by this module keep the indices of each row sorted with
+// no duplicates.
+#[derive(Debug, Clone)]
+pub struct CsrMatrix<T> {
+    pub shape: (usize, usize),
+    pub indptr: Vec<usize>,
+    pub indices: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+impl<T: Num + Copy> CsrMatrix<T> {
+    pub fn new(shape: (usize, usize), indptr: Vec<usize>, indices: Vec<usize>, data: Vec<T>) -> CsrMatrix<T> {
+        validate_compressed("CsrMatrix", shape.0, shape.1, &indptr, &indices, data.len());
+        CsrMatrix { shape, indptr, indices, data }
+    }
+
+    // n x n identity
+    pub fn eye(n: usize) -> CsrMatrix<T> {
+        CsrMatrix { shape: (n, n), indptr: (0..=n).collect(), indices: (0..n).collect(), data: vec![T::one(); n] }
+    }
+
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    // Column indices and values stored in row i
+    pub fn row(&self, i: usize) -> (&[usize], &[T]) {
+        let (start, end) = (self.indptr[i], self.indptr[i + 1]);
+        (&self.indices[start..end], &self.data[start..end])
+    }
+
+    // Value at (i, j), zero when not stored
+    pub fn get(&self, i: usize, j: usize) -> T {
+        let (cols, vals) = self.row(i);
+        cols.iter().zip(vals).filter(|(&c, _)| c == j).fold(T::zero(), |acc, (_, &v)| acc + v)
+    }
+
+    pub fn from_dense(a: &Array<T>) -> CsrMatrix<T> {
+        CooMatrix::from_dense(a).to_csr()
+    }
+
+    pub fn to_dense(&self) -> Array<T> {
+        let (m, n) = self.shape;
+        let mut out = vec![T::zero(); m * n];
+        for i in 0..m {
+            let (cols, vals) = self.row(i);
+            for (&j, &v) in cols.iter().zip(vals) {
+                out[i * n + j] = out[i * n + j] + v;
+            }
+        }
+        Array::from_vec(out, &[m as i32, n as i32])
+    }
+
+    pub fn to_coo(&self) -> CooMatrix<T> {
+        let row = (0..self.shape.0).flat_map(|i| std::iter::repeat_n(i, self.indptr[i + 1] - self.indptr[i])).collect();
+        CooMatrix { shape: self.shape, row, col: self.indices.clone(), data: self.data.clone() }
+    }
+
+    pub fn to_csc(&self) -> CscMatrix<T> {
+        self.to_coo().to_csc()
+    }
+
+    // The transpose is a copy of the same arrays, read column-wise
+    pub fn transpose(&self) -> CscMatrix<T> {
+        CscMatrix {
+            shape: (self.shape.1, self.shape.0),
+            indptr: self.indptr.clone(),
+            indices: self.indices.clone(),
+            data: self.data.clone(),
+        }
+    }
+
+    // Rows start..end as a new matrix (A[start:end] in scipy)
+    pub fn slice_rows(&self, start: usize, end: usize) -> CsrMatrix<T> {
+        if start > end || end > self.shape.0 {
+            panic!("CsrMatrix: row range {}..{} is out of bounds for {} rows", start, end, self.shape.0);
+        }
+        let (lo, hi) = (self.indptr[start], self.indptr[end]);
+        CsrMatrix {
+            shape: (end - start, self.shape.1),
+            indptr: self.indptr[start..=end].iter().map(|&p| p - lo).collect(),
+            indices: self.indices[lo..hi].to_vec(),
+            data: self.data[lo..hi].to_vec(),
+        }
+    }
+
+    // Every stored value multiplied by s
+    pub fn scale(&self, s: T) -> CsrMatrix<T> {
+        CsrMatrix { data: self.data.iter().map(|&v| v * s).collect(), ..self.clone() }
+    }
+
+    pub fn add(&self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        self.combine(other, "add", true, |a, b| a + b)
+    }
+
+    pub fn sub(&self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        self.combine(other, "sub", true, |a, b| a - b)
+    }
+
+    // Element-wise (Hadamard) product; only entries stored in both survive
+    pub fn multiply(&self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        self.combine(other, "multiply", false, |a, b| a * b)
+    }
+
+    // Apply op to matching entries row by row, over the union of both
+    // sparsity patterns or their intersection
+    fn combine(&self, other: &CsrMatrix<T>, name: &str, union: bool, op: impl Fn(T, T) -> T) -> CsrMatrix<T> {
+        if self.shape != other.shape {
+            panic!("{}: inconsistent shapes {:?} and {:?}", name, self.shape, other.shape);
+        }
+        let n = self.shape.1;
+        // Dense accumulators for one row: values from each side and who set them
+        let mut left = vec![T::zero(); n];
+        let mut right = vec![T::zero(); n];
+        let mut seen = vec![0u8; n];
+        let mut touched = Vec::new();
+
+        let mut indptr = vec![0];
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        for i in 0..self.shape.0 {
+            for (side, mat) in [(1u8, self), (2u8, other)] {
+                let (cols, vals) = mat.row(i);
+                let acc = if side == 1 { &mut left } else { &mut right };
+                for (&j, &v) in cols.iter().zip(vals) {
+                    if seen[j] == 0 {
+                        touched.push(j);
+                    }
+                    seen[j] |= side;
+                    acc[j] = acc[j] + v;
+                }
+            }
+            touched.sort_unstable();
+            for &j in &touched {
+                if union || seen[j] == 3 {
+                    indices.push(j);
+                    data.push(op(left[j], right[j]));
+                }
+                left[j] = T::zero();
+                right[j] = T::zero();
+                seen[j] = 0;
+            }
+            touched.clear();
+            indptr.push(indices.len());
+        }
+        CsrMatrix { shape: self.shape, indptr, indices, data }
+    }
+
+    // Sparse-sparse product by Gustavson's row-wise algorithm
+    pub fn matmul(&self, other: &CsrMatrix<T>) -> CsrMatrix<T> {
+        if self.shape.1 != other.shape.0 {
+            panic!(
+                "matmul: dimension mismatch, {:?} @ {:?} ({} != {})",
+                self.shape, other.shape, self.shape.1, other.shape.0
+            );
+        }
+        let n = other.shape.1;
+        let mut acc = vec![T::zero(); n];
+        let mut seen = vec![false; n];
+        let mut touched = Vec::new();
+
+        let mut indptr = vec![0];
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        for i in 0..self.shape.0 {
+            let (a_cols, a_vals) = self.row(i);
+            for (&k, &a) in a_cols.iter().zip(a_vals) {
+                let (b_cols, b_vals) = other.row(k);
+                for (&j, &b) in b_cols.iter().zip(b_vals) {
+                    if !seen[j] {
+                        seen[j] = true;
+                        touched.push(j);
+                    }
+                    acc[j] = acc[j] + a * b;
+                }
+            }
+            touched.sort_unstable();
+            for &j in &touched {
+                indices.push(j);
+                data.push(acc[j]);
+                acc[j] = T::zero();
+                seen[j] = false;
+            }
+            touched.clear();
+            indptr.push(indices.len());
+        }
+        CsrMatrix { shape: (self.shape.0, n), indptr, indices, data }
+    }
+}
+
+impl<T: Num + Copy + Send + Sync> CsrMatrix<T> {
+    // Sparse @ dense. `b` is (n,) or (n, k); the result has b's dimensionality.
+    // Rows of the result are computed in parallel.
+    pub fn matmul_dense(&self, b: &Array<T>) -> Array<T> {
+        let (rows, k) = dense_dims(b, "matmul_dense");
+        if rows != self.shape.1 {
+            panic!(
+                "matmul_dense: dimension mismatch, {:?} @ {:?} ({} != {})",
+                self.shape, b.shape, self.shape.1, rows
+            );
+        }
+        let mut out = vec![T::zero(); self.shape.0 * k];
+        out.par_chunks_mut(k.max(1)).enumerate().for_each(|(i, out_row)| {
+            let (cols, vals) = self.row(i);
+            for (&j, &v) in cols.iter().zip(vals) {
+                for (o, &x) in out_row.iter_mut().zip(&b.data[j * k..(j + 1) * k]) {
+                    *o = *o + v * x;
+                }
+            }
+        });
+        if b.ndim == 1 {
+            Array::from_vec(out, &[self.shape.0 as i32])
+        } else {
+            Array::from_vec(out, &[self.shape.0 as i32, k as i32])
+        }
+    }
+
+    // Dense @ sparse for a of shape (p, m) or (m,)
+    pub fn dense_matmul(&self, a: &Array<T>) -> Array<T> {
+        let (p, m) = if a.ndim == 1 { (1, a.shape[0] as usize) } else { dense_dims(a, "dense_matmul") };
+        if m != self.shape.0 {
+            panic!(
+                "dense_matmul: dimension mismatch, {:?} @ {:?} ({} != {})",
+                a.shape, self.shape, m, self.shape.0
+            );
+        }
+        let n = self.shape.1;
+        let mut out = vec![T::zero(); p * n];
+        out.par_chunks_mut(n.max(1)).enumerate().for_each(|(r, out_row)| {
+            for i in 0..m {
+                let x = a.data[r * m + i];
+                if x == T::zero() {
+                    continue;
+                }
+                let (cols, vals) = self.row(i);
+                for (&j, &v) in cols.iter().zip(vals) {
+                    out_row[j] = out_row[j] + x * v;
+                }
+            }
+        });
+        if a.ndim == 1 {
+            Array::from_vec(out, &[n as i32])
+        } else {
+            Array::from_vec(out, &[p as i32, n as i32])
+        }
+    }
+}
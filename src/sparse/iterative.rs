@@ -0,0 +1,451 @@
+use super::{CscMatrix, CsrMatrix};
+use crate::array::Array;
+use crate::ops::nr_matmul;
+use num_traits::{Float, Num};
+
+// Anything that can compute y = A x for a square A. The iterative solvers
+// only touch the matrix through this trait, so dense arrays, the sparse
+// formats and matrix-free operators all work.
+pub trait LinearOperator<T> {
+    fn shape(&self) -> (usize, usize);
+    fn apply(&self, x: &[T]) -> Vec<T>;
+}
+
+// Dense 2-D arrays, applied with nr_matmul
+impl<T: Num + Copy + Send + Sync + 'static> LinearOperator<T> for Array<T> {
+    fn shape(&self) -> (usize, usize) {
+        if self.ndim != 2 {
+            panic!("LinearOperator: expected a 2-D array, got shape {:?}", self.shape);
+        }
+        (self.shape[0] as usize, self.shape[1] as usize)
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        nr_matmul(self, &Array::from_vec(x.to_vec(), &[x.len() as i32])).data
+    }
+}
+
+impl<T: Num + Copy> LinearOperator<T> for CsrMatrix<T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        (0..self.shape.0)
+            .map(|i| {
+                let (cols, vals) = self.row(i);
+                cols.iter().zip(vals).fold(T::zero(), |acc, (&j, &v)| acc + v * x[j])
+            })
+            .collect()
+    }
+}
+
+impl<T: Num + Copy> LinearOperator<T> for CscMatrix<T> {
+    fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    fn apply(&self, x: &[T]) -> Vec<T> {
+        let mut y = vec![T::zero(); self.shape.0];
+        for (j, &xj) in x.iter().enumerate() {
+            let (rows, vals) = self.col(j);
+            for (&i, &v) in rows.iter().zip(vals) {
+                y[i] = y[i] + v * xj;
+            }
+        }
+        y
+    }
+}
+
+// Approximate inverse M^-1 applied to a residual
+pub trait Preconditioner<T> {
+    fn apply(&self, r: &[T]) -> Vec<T>;
+}
+
+// Diagonal (Jacobi) preconditioner, M = diag(A)
+#[derive(Debug, Clone)]
+pub struct JacobiPreconditioner<T> {
+    inv_diag: Vec<T>,
+}
+
+impl<T: Float> JacobiPreconditioner<T> {
+    pub fn new(a: &CsrMatrix<T>) -> JacobiPreconditioner<T> {
+        let diag: Vec<T> = (0..a.shape.0).map(|i| a.get(i, i)).collect();
+        JacobiPreconditioner::from_diagonal(&diag)
+    }
+
+    // From a square 2-D array, e.g. for a dense operator passed to nr_cg
+    pub fn from_dense(a: &Array<T>) -> JacobiPreconditioner<T> {
+        if a.ndim != 2 || a.shape[0] != a.shape[1] {
+            panic!("JacobiPreconditioner: expected a square 2-D array, got shape {:?}", a.shape);
+        }
+        let n = a.shape[0] as usize;
+        let diag: Vec<T> = (0..n).map(|i| a.data[i * n + i]).collect();
+        JacobiPreconditioner::from_diagonal(&diag)
+    }
+
+    // From the diagonal itself, for matrix-free operators that know it
+    pub fn from_diagonal(diag: &[T]) -> JacobiPreconditioner<T> {
+        let inv_diag = diag
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| {
+                if d == T::zero() {
+                    panic!("JacobiPreconditioner: zero on the diagonal in row {}", i);
+                }
+                d.recip()
+            })
+            .collect();
+        JacobiPreconditioner { inv_diag }
+    }
+}
+
+impl<T: Float> Preconditioner<T> for JacobiPreconditioner<T> {
+    fn apply(&self, r: &[T]) -> Vec<T> {
+        r.iter().zip(&self.inv_diag).map(|(&r, &d)| r * d).collect()
+    }
+}
+
+// Incomplete LU factorization with zero fill-in: L and U keep the sparsity
+// pattern of A. Expects sorted column indices with every diagonal stored.
+#[derive(Debug, Clone)]
+pub struct Ilu0Preconditioner<T> {
+    // Strict lower part holds L (unit diagonal implied), the rest U
+    lu: CsrMatrix<T>,
+    diag: Vec<usize>,
+}
+
+impl<T: Float> Ilu0Preconditioner<T> {
+    // From a square 2-D array; zeros are dropped, so its diagonal must be
+    // nonzero
+    pub fn from_dense(a: &Array<T>) -> Ilu0Preconditioner<T> {
+        Ilu0Preconditioner::new(&CsrMatrix::from_dense(a))
+    }
+
+    pub fn new(a: &CsrMatrix<T>) -> Ilu0Preconditioner<T> {
+        let n = a.shape.0;
+        if a.shape.1 != n {
+            panic!("Ilu0Preconditioner: matrix must be square, got shape {:?}", a.shape);
+        }
+        let mut lu = a.clone();
+        let diag: Vec<usize> = (0..n)
+            .map(|i| {
+                let (start, end) = (lu.indptr[i], lu.indptr[i + 1]);
+                (start..end)
+                    .find(|&p| lu.indices[p] == i)
+                    .unwrap_or_else(|| panic!("Ilu0Preconditioner: no diagonal entry in row {}", i))
+            })
+            .collect();
+
+        // Position of each column of the current row, or usize::MAX
+        let mut pos = vec![usize::MAX; n];
+        for i in 0..n {
+            let (start, end) = (lu.indptr[i], lu.indptr[i + 1]);
+            for p in start..end {
+                pos[lu.indices[p]] = p;
+            }
+            for p in start..diag[i] {
+                let k = lu.indices[p];
+                let pivot = lu.data[diag[k]];
+                if pivot == T::zero() {
+                    panic!("Ilu0Preconditioner: zero pivot in row {}", k);
+                }
+                let l = lu.data[p] / pivot;
+                lu.data[p] = l;
+                for q in diag[k] + 1..lu.indptr[k + 1] {
+                    let target = pos[lu.indices[q]];
+                    if target != usize::MAX {
+                        lu.data[target] = lu.data[target] - l * lu.data[q];
+                    }
+                }
+            }
+            for p in start..end {
+                pos[lu.indices[p]] = usize::MAX;
+            }
+        }
+        if let Some(i) = (0..n).find(|&i| lu.data[diag[i]] == T::zero()) {
+            panic!("Ilu0Preconditioner: zero pivot in row {}", i);
+        }
+        Ilu0Preconditioner { lu, diag }
+    }
+}
+
+impl<T: Float> Preconditioner<T> for Ilu0Preconditioner<T> {
+    fn apply(&self, r: &[T]) -> Vec<T> {
+        let lu = &self.lu;
+        let n = lu.shape.0;
+        let mut x = r.to_vec();
+        for i in 0..n {
+            for p in lu.indptr[i]..self.diag[i] {
+                x[i] = x[i] - lu.data[p] * x[lu.indices[p]];
+            }
+        }
+        for i in (0..n).rev() {
+            for p in self.diag[i] + 1..lu.indptr[i + 1] {
+                x[i] = x[i] - lu.data[p] * x[lu.indices[p]];
+            }
+            x[i] = x[i] / lu.data[self.diag[i]];
+        }
+        x
+    }
+}
+
+// Output of the iterative solvers
+#[derive(Debug)]
+pub struct IterativeResult<T> {
+    pub solution: Array<T>,
+    // Whether ||b - A x|| <= tol * ||b|| was reached
+    pub converged: bool,
+    // Iterations performed (inner iterations for GMRES)
+    pub iterations: usize,
+    // Residual norm ||b - A x|| of the returned solution
+    pub residual_norm: T,
+}
+
+// Solve A x = b for symmetric positive-definite A by (preconditioned)
+// conjugate gradients (scipy.sparse.linalg.cg). Stops once
+// ||b - A x|| <= tol * ||b|| or after maxiter iterations (default 10 n).
+pub fn nr_cg<T: Float>(
+    a: &dyn LinearOperator<T>,
+    b: &Array<T>,
+    x0: Option<&Array<T>>,
+    tol: T,
+    maxiter: Option<usize>,
+    precond: Option<&dyn Preconditioner<T>>,
+) -> IterativeResult<T> {
+    let (n, mut x, mut r, target) = setup(a, b, x0, tol, "cg");
+    let maxiter = maxiter.unwrap_or(10 * n);
+    let mut iterations = 0;
+    if norm(&r) > target {
+        let mut z = precondition(precond, &r);
+        let mut p = z.clone();
+        let mut rz = dot(&r, &z);
+        while iterations < maxiter {
+            iterations += 1;
+            let ap = a.apply(&p);
+            let pap = dot(&p, &ap);
+            if pap == T::zero() {
+                break;
+            }
+            let alpha = rz / pap;
+            axpy(&mut x, alpha, &p);
+            axpy(&mut r, -alpha, &ap);
+            if norm(&r) <= target {
+                break;
+            }
+            z = precondition(precond, &r);
+            let rz_next = dot(&r, &z);
+            let beta = rz_next / rz;
+            rz = rz_next;
+            for (p, &z) in p.iter_mut().zip(&z) {
+                *p = z + beta * *p;
+            }
+        }
+    }
+    finish(a, b, x, target, iterations)
+}
+
+// Solve A x = b by restarted GMRES(restart) with right preconditioning
+// (scipy.sparse.linalg.gmres). `restart` defaults to min(20, n) and
+// maxiter, counting inner iterations, to 10 n.
+pub fn nr_gmres<T: Float>(
+    a: &dyn LinearOperator<T>,
+    b: &Array<T>,
+    x0: Option<&Array<T>>,
+    tol: T,
+    restart: Option<usize>,
+    maxiter: Option<usize>,
+    precond: Option<&dyn Preconditioner<T>>,
+) -> IterativeResult<T> {
+    let (n, mut x, mut r, target) = setup(a, b, x0, tol, "gmres");
+    let restart = restart.unwrap_or(20).clamp(1, n.max(1));
+    let maxiter = maxiter.unwrap_or(10 * n);
+    let mut iterations = 0;
+
+    let mut beta = norm(&r);
+    while beta > target && iterations < maxiter {
+        // Arnoldi basis v, Hessenberg h (column j has j + 2 entries) reduced
+        // to triangular form by the Givens rotations (cs, sn) as it grows
+        let mut v = vec![r.iter().map(|&x| x / beta).collect::<Vec<T>>()];
+        let mut h: Vec<Vec<T>> = Vec::new();
+        let mut cs: Vec<T> = Vec::new();
+        let mut sn: Vec<T> = Vec::new();
+        let mut g = vec![beta];
+        let mut z_basis = Vec::new();
+
+        for j in 0..restart {
+            if iterations == maxiter {
+                break;
+            }
+            iterations += 1;
+            let z = precondition(precond, &v[j]);
+            let mut w = a.apply(&z);
+            z_basis.push(z);
+
+            let mut col = Vec::with_capacity(j + 2);
+            for vi in &v {
+                let hij = dot(&w, vi);
+                axpy(&mut w, -hij, vi);
+                col.push(hij);
+            }
+            let h_next = norm(&w);
+            col.push(h_next);
+
+            for i in 0..j {
+                let (a, b) = (col[i], col[i + 1]);
+                col[i] = cs[i] * a + sn[i] * b;
+                col[i + 1] = cs[i] * b - sn[i] * a;
+            }
+            let denom = col[j].hypot(col[j + 1]);
+            let (c, s) = if denom == T::zero() { (T::one(), T::zero()) } else { (col[j] / denom, col[j + 1] / denom) };
+            col[j] = denom;
+            col[j + 1] = T::zero();
+            cs.push(c);
+            sn.push(s);
+            g.push(-s * g[j]);
+            g[j] = c * g[j];
+            h.push(col);
+
+            if g[j + 1].abs() <= target || h_next == T::zero() {
+                break;
+            }
+            v.push(w.iter().map(|&x| x / h_next).collect());
+        }
+
+        // Back substitution for y, then x += M^-1 V y
+        let k = h.len();
+        let mut y = vec![T::zero(); k];
+        for i in (0..k).rev() {
+            let mut s = g[i];
+            for l in i + 1..k {
+                s = s - h[l][i] * y[l];
+            }
+            y[i] = if h[i][i] == T::zero() { T::zero() } else { s / h[i][i] };
+        }
+        for (zi, &yi) in z_basis.iter().zip(&y) {
+            axpy(&mut x, yi, zi);
+        }
+
+        let ax = a.apply(&x);
+        r = b.data.iter().zip(&ax).map(|(&b, &ax)| b - ax).collect();
+        if k == 0 {
+            break;
+        }
+        beta = norm(&r);
+    }
+    finish(a, b, x, target, iterations)
+}
+
+// Solve A x = b for general A by BiCGSTAB with right preconditioning
+// (scipy.sparse.linalg.bicgstab). Stops on convergence, after maxiter
+// iterations (default 10 n), or on breakdown.
+pub fn nr_bicgstab<T: Float>(
+    a: &dyn LinearOperator<T>,
+    b: &Array<T>,
+    x0: Option<&Array<T>>,
+    tol: T,
+    maxiter: Option<usize>,
+    precond: Option<&dyn Preconditioner<T>>,
+) -> IterativeResult<T> {
+    let (n, mut x, mut r, target) = setup(a, b, x0, tol, "bicgstab");
+    let maxiter = maxiter.unwrap_or(10 * n);
+    let r_hat = r.clone();
+    let (mut rho, mut alpha, mut omega) = (T::one(), T::one(), T::one());
+    let mut v = vec![T::zero(); n];
+    let mut p = vec![T::zero(); n];
+    let mut iterations = 0;
+
+    while norm(&r) > target && iterations < maxiter {
+        iterations += 1;
+        let rho_next = dot(&r_hat, &r);
+        if rho_next == T::zero() || omega == T::zero() {
+            break;
+        }
+        let beta = (rho_next / rho) * (alpha / omega);
+        rho = rho_next;
+        for ((p, &r), &v) in p.iter_mut().zip(&r).zip(&v) {
+            *p = r + beta * (*p - omega * v);
+        }
+
+        let p_hat = precondition(precond, &p);
+        v = a.apply(&p_hat);
+        let rv = dot(&r_hat, &v);
+        if rv == T::zero() {
+            break;
+        }
+        alpha = rho / rv;
+        axpy(&mut x, alpha, &p_hat);
+        let mut s = r.clone();
+        axpy(&mut s, -alpha, &v);
+        if norm(&s) <= target {
+            break;
+        }
+
+        let s_hat = precondition(precond, &s);
+        let t = a.apply(&s_hat);
+        let tt = dot(&t, &t);
+        omega = if tt == T::zero() { T::zero() } else { dot(&t, &s) / tt };
+        axpy(&mut x, omega, &s_hat);
+        r = s;
+        axpy(&mut r, -omega, &t);
+    }
+    finish(a, b, x, target, iterations)
+}
+
+// Check shapes and build the starting point: (n, x0, r0, absolute target)
+fn setup<T: Float>(
+    a: &dyn LinearOperator<T>,
+    b: &Array<T>,
+    x0: Option<&Array<T>>,
+    tol: T,
+    name: &str,
+) -> (usize, Vec<T>, Vec<T>, T) {
+    let (m, n) = a.shape();
+    if m != n {
+        panic!("{}: operator must be square, got shape ({}, {})", name, m, n);
+    }
+    if b.ndim != 1 || b.totalsize != n {
+        panic!("{}: b of shape {:?} does not match operator of size {}", name, b.shape, n);
+    }
+    let x = match x0 {
+        Some(x0) if x0.totalsize != n => panic!("{}: x0 of shape {:?} does not match b", name, x0.shape),
+        Some(x0) => x0.data.clone(),
+        None => vec![T::zero(); n],
+    };
+    let ax = a.apply(&x);
+    let r = b.data.iter().zip(&ax).map(|(&b, &ax)| b - ax).collect();
+    (n, x, r, tol * norm(&b.data))
+}
+
+fn finish<T: Float>(a: &dyn LinearOperator<T>, b: &Array<T>, x: Vec<T>, target: T, iterations: usize) -> IterativeResult<T> {
+    let ax = a.apply(&x);
+    let residual_norm = norm(&b.data.iter().zip(&ax).map(|(&b, &ax)| b - ax).collect::<Vec<T>>());
+    IterativeResult {
+        solution: Array::from_vec(x, &b.shape),
+        converged: residual_norm <= target,
+        iterations,
+        residual_norm,
+    }
+}
+
+fn precondition<T: Float>(precond: Option<&dyn Preconditioner<T>>, r: &[T]) -> Vec<T> {
+    match precond {
+        Some(m) => m.apply(r),
+        None => r.to_vec(),
+    }
+}
+
+fn dot<T: Float>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y).fold(T::zero(), |acc, (&a, &b)| acc + a * b)
+}
+
+fn norm<T: Float>(x: &[T]) -> T {
+    dot(x, x).sqrt()
+}
+
+// y += alpha * x
+fn axpy<T: Float>(y: &mut [T], alpha: T, x: &[T]) {
+    for (y, &x) in y.iter_mut().zip(x) {
+        *y = *y + alpha * x;
+    }
+}
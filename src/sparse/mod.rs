@@ -0,0 +1,94 @@
+// Sparse matrices in coordinate (COO), compressed sparse row (CSR) and
+// compressed sparse column (CSC) formats, mirroring scipy.sparse.
+//
+// COO is the easy format to build incrementally; CSR is the one to compute
+// with (row slicing, products, element-wise ops) and CSC serves column
+// access. Dense operands and results are regular `Array<T>` values, so they
+// can be passed straight on to nr_matmul and friends. The iterative
+// solvers accept any LinearOperator, dense or sparse.
+mod coo;
+mod csc;
+mod csr;
+mod iterative;
+
+pub use coo::CooMatrix;
+pub use csc::CscMatrix;
+pub use csr::CsrMatrix;
+pub use iterative::{
+    nr_bicgstab, nr_cg, nr_gmres, Ilu0Preconditioner, IterativeResult, JacobiPreconditioner, LinearOperator,
+    Preconditioner,
+};
+
+use crate::array::Array;
+use num_traits::Num;
+
+// Dimensions of a 2-D dense operand, or (n, 1) for a 1-D vector
+pub(crate) fn dense_dims<T>(a: &Array<T>, name: &str) -> (usize, usize) {
+    match a.ndim {
+        1 => (a.shape[0] as usize, 1),
+        2 => (a.shape[0] as usize, a.shape[1] as usize),
+        _ => panic!("{}: expected a 1-D or 2-D array, got shape {:?}", name, a.shape),
+    }
+}
+
+// Compress (major, minor, value) triplets into indptr/indices/data over
+// `n_major` major lines. Minor indices come out sorted within each line and
+// duplicate entries are summed.
+pub(crate) fn compress<T: Num + Copy>(
+    n_major: usize,
+    major: &[usize],
+    minor: &[usize],
+    values: &[T],
+) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    let mut indptr = vec![0; n_major + 1];
+    for &m in major {
+        indptr[m + 1] += 1;
+    }
+    for i in 0..n_major {
+        indptr[i + 1] += indptr[i];
+    }
+
+    // Counting sort by major index
+    let mut next = indptr.clone();
+    let mut order = vec![0; major.len()];
+    for (k, &m) in major.iter().enumerate() {
+        order[next[m]] = k;
+        next[m] += 1;
+    }
+
+    let mut out_ptr = Vec::with_capacity(n_major + 1);
+    let mut indices = Vec::with_capacity(major.len());
+    let mut data = Vec::with_capacity(major.len());
+    out_ptr.push(0);
+    for line in 0..n_major {
+        let slot = &mut order[indptr[line]..indptr[line + 1]];
+        slot.sort_by_key(|&k| minor[k]);
+        for &k in slot.iter() {
+            if indices.len() > *out_ptr.last().unwrap() && *indices.last().unwrap() == minor[k] {
+                let last = data.len() - 1;
+                data[last] = data[last] + values[k];
+            } else {
+                indices.push(minor[k]);
+                data.push(values[k]);
+            }
+        }
+        out_ptr.push(indices.len());
+    }
+    (out_ptr, indices, data)
+}
+
+// Check a compressed structure, panicking with `name` on malformed input
+pub(crate) fn validate_compressed(name: &str, n_major: usize, n_minor: usize, indptr: &[usize], indices: &[usize], nnz: usize) {
+    if indptr.len() != n_major + 1 {
+        panic!("{}: indptr has length {}, expected {}", name, indptr.len(), n_major + 1);
+    }
+    if indptr[0] != 0 || indptr[n_major] != indices.len() || indices.len() != nnz {
+        panic!("{}: indptr, indices and data lengths are inconsistent", name);
+    }
+    if indptr.windows(2).any(|w| w[0] > w[1]) {
+        panic!("{}: indptr must be non-decreasing", name);
+    }
+    if let Some(&bad) = indices.iter().find(|&&i| i >= n_minor) {
+        panic!("{}: index {} is out of bounds for dimension {}", name, bad, n_minor);
+    }
+}
@@ -0,0 +1,192 @@
+// Bessel functions of integer order 0 and 1, as f64 kernels
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+// Above this the Hankel asymptotic expansions are accurate to rounding
+const ASYMPTOTIC: f64 = 25.0;
+// Below this the first terms of the power series are accurate to rounding;
+// the backward recurrence would overflow for x under ~1e-56
+const SMALL: f64 = 1e-5;
+
+// J0, J1 and the two Neumann series sums needed for Y0 and Y1, by Miller's
+// backward recurrence normalized with J0 + 2 (J2 + J4 + ...) = 1, for x > 0:
+//   s0 = sum_k>=1 (-1)^k J_2k / k
+//   s1 = sum_k>=1 (-1)^k (2k + 1) J_(2k+1) / (k (k + 1))
+fn miller(x: f64) -> (f64, f64, f64, f64) {
+    // Start far enough above x that J_n is negligible there
+    let start = 2 * ((1.5 * x) as usize / 2 + 20);
+    let (mut jn, mut jn1) = (1.0, 0.0);
+    let (mut norm, mut s0, mut s1) = (0.0, 0.0, 0.0);
+    for n in (1..=start).rev() {
+        // jn holds J_n and jn1 holds J_(n+1), up to a common factor
+        let k = (n / 2) as f64;
+        let sign = if (n / 2) % 2 == 0 { 1.0 } else { -1.0 };
+        if n % 2 == 0 {
+            norm += 2.0 * jn;
+            s0 += sign * jn / k;
+        } else if n > 1 {
+            s1 += sign * (2.0 * k + 1.0) * jn / (k * (k + 1.0));
+        }
+        let prev = 2.0 * n as f64 / x * jn - jn1;
+        jn1 = jn;
+        jn = prev;
+        if jn.abs() > 1e250 {
+            for v in [&mut jn, &mut jn1, &mut norm, &mut s0, &mut s1] {
+                *v *= 1e-250;
+            }
+        }
+    }
+    norm += jn;
+    (jn / norm, jn1 / norm, s0 / norm, s1 / norm)
+}
+
+// Hankel's expansions P and Q for order nu, with
+// J = sqrt(2 / (pi x)) (P cos chi - Q sin chi) and
+// Y = sqrt(2 / (pi x)) (P sin chi + Q cos chi), chi = x - (nu / 2 + 1/4) pi
+fn hankel(nu: f64, x: f64) -> (f64, f64) {
+    let mu = 4.0 * nu * nu;
+    let (mut p, mut q) = (1.0f64, 0.0);
+    let mut term = 1.0;
+    let mut last = f64::INFINITY;
+    for k in 1..200 {
+        let odd = (2 * k - 1) as f64;
+        term *= (mu - odd * odd) / (k as f64 * 8.0 * x);
+        if term.abs() >= last || term.abs() < f64::EPSILON * p.abs() * 1e-2 {
+            break;
+        }
+        last = term.abs();
+        // Terms alternate between Q and P, each with alternating signs
+        match k % 4 {
+            1 => q += term,
+            2 => p -= term,
+            3 => q -= term,
+            _ => p += term,
+        }
+    }
+    (p, q)
+}
+
+pub(super) fn j0(x: f64) -> f64 {
+    let x = x.abs();
+    if x.is_nan() {
+        return x;
+    }
+    if x < SMALL {
+        return 1.0 - x * x / 4.0;
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    if x > ASYMPTOTIC {
+        let (p, q) = hankel(0.0, x);
+        let (s, c) = x.sin_cos();
+        // cos(x - pi/4) and sin(x - pi/4) without rounding x - pi/4
+        let (cos_chi, sin_chi) = ((c + s) * FRAC_1_SQRT_2, (s - c) * FRAC_1_SQRT_2);
+        return (2.0 / (PI * x)).sqrt() * (p * cos_chi - q * sin_chi);
+    }
+    miller(x).0
+}
+
+pub(super) fn j1(x: f64) -> f64 {
+    let ax = x.abs();
+    if x.is_nan() || x == 0.0 {
+        return x;
+    }
+    let r = if ax < SMALL {
+        ax / 2.0 * (1.0 - ax * ax / 8.0)
+    } else if ax == f64::INFINITY {
+        0.0
+    } else if ax > ASYMPTOTIC {
+        let (p, q) = hankel(1.0, ax);
+        let (s, c) = ax.sin_cos();
+        let (cos_chi, sin_chi) = ((s - c) * FRAC_1_SQRT_2, -(s + c) * FRAC_1_SQRT_2);
+        (2.0 / (PI * ax)).sqrt() * (p * cos_chi - q * sin_chi)
+    } else {
+        miller(ax).1
+    };
+    if x < 0.0 {
+        -r
+    } else {
+        r
+    }
+}
+
+pub(super) fn y0(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x < SMALL {
+        let x2 = x * x / 4.0;
+        return 2.0 / PI * (((x / 2.0).ln() + EULER_GAMMA) * (1.0 - x2) + x2);
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    if x > ASYMPTOTIC {
+        let (p, q) = hankel(0.0, x);
+        let (s, c) = x.sin_cos();
+        let (cos_chi, sin_chi) = ((c + s) * FRAC_1_SQRT_2, (s - c) * FRAC_1_SQRT_2);
+        return (2.0 / (PI * x)).sqrt() * (p * sin_chi + q * cos_chi);
+    }
+    let (j0, _, s0, _) = miller(x);
+    2.0 / PI * (((x / 2.0).ln() + EULER_GAMMA) * j0 - 2.0 * s0)
+}
+
+pub(super) fn y1(x: f64) -> f64 {
+    if x.is_nan() || x < 0.0 {
+        return f64::NAN;
+    }
+    if x == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if x < SMALL {
+        return -2.0 / (PI * x) + x / PI * ((x / 2.0).ln() + EULER_GAMMA - 0.5);
+    }
+    if x == f64::INFINITY {
+        return 0.0;
+    }
+    if x > ASYMPTOTIC {
+        let (p, q) = hankel(1.0, x);
+        let (s, c) = x.sin_cos();
+        let (cos_chi, sin_chi) = ((s - c) * FRAC_1_SQRT_2, -(s + c) * FRAC_1_SQRT_2);
+        return (2.0 / (PI * x)).sqrt() * (p * sin_chi + q * cos_chi);
+    }
+    let (j0, j1, _, s1) = miller(x);
+    2.0 / PI * (-j0 / x + ((x / 2.0).ln() + EULER_GAMMA - 1.0) * j1 - s1)
+}
+
+// Modified Bessel function of the first kind, order zero: the power series
+// sum ((x/2)^k / k!)^2 up to |x| = 30, the asymptotic expansion
+// e^x / sqrt(2 pi x) sum_k ((2k - 1)!!)^2 / (k! (8x)^k) beyond
+pub(crate) fn i0(x: f64) -> f64 {
+    let x = x.abs();
+    if x.is_nan() || x == f64::INFINITY {
+        return x;
+    }
+    if x <= 30.0 {
+        let q = x * x / 4.0;
+        let (mut term, mut sum) = (1.0, 1.0);
+        let mut k = 1.0;
+        while term > sum * f64::EPSILON {
+            term *= q / (k * k);
+            sum += term;
+            k += 1.0;
+        }
+        return sum;
+    }
+    let (mut term, mut sum) = (1.0, 1.0);
+    for k in 1..100 {
+        let odd = (2 * k - 1) as f64;
+        term *= odd * odd / (k as f64 * 8.0 * x);
+        sum += term;
+        if term < sum * f64::EPSILON {
+            break;
+        }
+    }
+    // Split e^x so it does not overflow before the division
+    let half = (x / 2.0).exp();
+    half * (half / (2.0 * PI * x).sqrt()) * sum
+}
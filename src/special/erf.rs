@@ -0,0 +1,75 @@
+// Error function, its complement and inverse, as f64 kernels
+use super::gamma::{erf_series, erfc_fraction};
+use std::f64::consts::PI;
+
+// Below this the series for erf is used, above it the continued fraction
+// for erfc (x^2 = 3/2, where the incomplete gamma methods switch over)
+const SWITCH: f64 = 1.224_744_871_391_589;
+
+// e^(-x^2) with x split into a head whose square is exact and a tail, so
+// the rounding of x^2 does not spoil the result for large x
+fn exp_neg_sq(x: f64) -> f64 {
+    let head = (x * 65536.0).trunc() / 65536.0;
+    (-head * head).exp() * (-(x - head) * (x + head)).exp()
+}
+
+pub(super) fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let ax = x.abs();
+    let r = if ax < SWITCH {
+        erf_series(ax, exp_neg_sq(ax))
+    } else if ax < 6.0 {
+        1.0 - erfc_fraction(ax, exp_neg_sq(ax))
+    } else {
+        1.0
+    };
+    r.copysign(x)
+}
+
+pub(super) fn erfc(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+    if x < SWITCH {
+        return 1.0 - erf_series(x, exp_neg_sq(x));
+    }
+    if x > 27.3 {
+        return 0.0;
+    }
+    erfc_fraction(x, exp_neg_sq(x))
+}
+
+// Inverse of erf on [-1, 1]: Winitzki's closed-form estimate polished by
+// Halley steps on erf(y) = x, or on erfc(y) = 1 - x for |x| >= 1/2 where
+// that difference is exact
+pub(super) fn erfinv(x: f64) -> f64 {
+    if x.is_nan() || x.abs() > 1.0 {
+        return f64::NAN;
+    }
+    if x.abs() == 1.0 {
+        return f64::INFINITY.copysign(x);
+    }
+    if x == 0.0 {
+        return x;
+    }
+    let ax = x.abs();
+    let a = 0.147;
+    let ln = (-ax * ax).ln_1p();
+    let c = 2.0 / (PI * a) + ln / 2.0;
+    let mut y = ((c * c - ln / a).sqrt() - c).sqrt();
+    for _ in 0..8 {
+        let residual = if ax < 0.5 { erf(y) - ax } else { (1.0 - ax) - erfc(y) };
+        let u = residual / (2.0 / PI.sqrt() * exp_neg_sq(y));
+        let step = u / (1.0 + y * u);
+        y -= step;
+        if step.abs() <= f64::EPSILON * y.abs() {
+            break;
+        }
+    }
+    y.copysign(x)
+}
@@ -1,6 +0,0 @@
-use rand::Rng;
-
-pub fn get_random_float(min: f32, max: f32) -> f32 {
-    let mut rng = rand::rng();
-    rng.random_range(min..max)
-}
\ No newline at end of file